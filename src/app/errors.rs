@@ -0,0 +1,35 @@
+use std::fmt::{Debug, Display};
+
+/// An error waiting to be shown in the error window
+pub struct ErrorReport {
+    /// What failed, followed by the error's own message
+    pub summary: String,
+    /// Full `Debug` output of the error, including any nested causes and SANE status codes
+    pub details: String,
+    /// Offer to reopen the scanner, for errors that leave it closed
+    pub offer_device_reset: bool,
+}
+
+impl ErrorReport {
+    pub fn new(context: &str, error: &(impl Display + Debug + ?Sized)) -> Self {
+        Self {
+            summary: format!("{context} {error}"),
+            details: format!("{error:#?}"),
+            offer_device_reset: false,
+        }
+    }
+
+    /// Takes the place of reports of repeated failures from a scanner that was closed because of them, ending with the last one
+    pub fn device_closed(summary: &str, last_error: &Self) -> Self {
+        Self {
+            summary: format!("{summary}\n\n{}", last_error.summary),
+            details: last_error.details.clone(),
+            offer_device_reset: true,
+        }
+    }
+
+    /// Summary and details together, as copied to the clipboard
+    pub fn full_text(&self) -> String {
+        format!("{}\n\n{}", self.summary, self.details)
+    }
+}
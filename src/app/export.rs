@@ -0,0 +1,204 @@
+use std::{error::Error, fs::File, io::{BufWriter, Write}, path::Path, time::SystemTime};
+
+use ::image::{ColorType, ImageEncoder, codecs::{jpeg::JpegEncoder, png::PngEncoder}};
+use printpdf::{ColorBits, ColorSpace, OffsetDateTime};
+use serde::{Deserialize, Serialize};
+
+use super::{image::PageFormat, pdf::image_encoding, strings::Strings};
+use crate::util::{crc32, unpack_bits};
+
+/// File format of page images exported in a ZIP archive
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageFileFormat {
+    #[default]
+    Png,
+    Jpeg,
+}
+
+impl ImageFileFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Png   => "png",
+            Self::Jpeg  => "jpg",
+        }
+    }
+
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::Png   => text.image_format_png,
+            Self::Jpeg  => text.image_format_jpeg,
+        }
+    }
+}
+
+/// High enough that text stays crisp
+const EXPORT_JPEG_QUALITY: u8 = 90;
+
+/// Encodes 8-bit RGB `pixels` as an image file. Pages that would be stored in gray in a PDF are exported with a single gray channel.
+pub fn encode_page_image(pixels: &[u8], size: [usize; 2], format: PageFormat, file_format: ImageFileFormat) -> Result<Vec<u8>, Box<dyn Error>> {
+    let (color_space, bits, data) = image_encoding(pixels, size, format);
+    let (data, color_type) = match (color_space, bits) {
+        // Neither encoder takes 1-bit samples, so black-and-white pages are widened to 8 bits, which PNG compresses well
+        (ColorSpace::Greyscale, ColorBits::Bit1) => {
+            let row_bytes = size[0].div_ceil(8);
+            let samples = data.chunks_exact(row_bytes.max(1))
+                .flat_map(|row| unpack_bits(row).take(size[0]))
+                .map(|white| if white { u8::MAX } else { 0 })
+                .collect();
+            (samples, ColorType::L8)
+        },
+        (ColorSpace::Greyscale, _) => (data, ColorType::L8),
+        _ => (data, ColorType::Rgb8),
+    };
+
+    let (width, height) = (u32::try_from(size[0])?, u32::try_from(size[1])?);
+    let mut encoded = Vec::new();
+    match file_format {
+        ImageFileFormat::Png => PngEncoder::new(&mut encoded).write_image(&data, width, height, color_type)?,
+        ImageFileFormat::Jpeg => JpegEncoder::new_with_quality(&mut encoded, EXPORT_JPEG_QUALITY).write_image(&data, width, height, color_type)?,
+    }
+
+    Ok(encoded)
+}
+
+/// Marks file names as UTF-8
+const ZIP_UTF8_FLAG: u16 = 0x0800;
+/// ZIP 2.0, which every reader supports
+const ZIP_VERSION: u16 = 20;
+
+/// Time and date of `time` in the MS-DOS format ZIP archives use, in UTC
+fn dos_date_time(time: SystemTime) -> (u16, u16) {
+    let seconds = time.duration_since(SystemTime::UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let time = i64::try_from(seconds).ok()
+        .and_then(|seconds| OffsetDateTime::from_unix_timestamp(seconds).ok())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+
+    // DOS dates start in 1980 and count seconds in twos
+    let year = u16::try_from(time.year() - 1980).unwrap_or_default();
+    let dos_time = (u16::from(time.hour()) << 11) | (u16::from(time.minute()) << 5) | u16::from(time.second() / 2);
+    let dos_date = (year << 9) | (u16::from(u8::from(time.month())) << 5) | u16::from(time.day());
+    (dos_time, dos_date)
+}
+
+/// Writes a ZIP archive one file at a time, so only the central directory is held in memory.
+/// Files are stored uncompressed, since the images in them are compressed already.
+pub struct ZipWriter {
+    file: BufWriter<File>,
+    /// Bytes written so far, which is where the next file's header starts
+    position: usize,
+    directory: Vec<u8>,
+    entries: u16,
+    time: u16,
+    date: u16,
+}
+
+impl ZipWriter {
+    pub fn create(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let (time, date) = dos_date_time(SystemTime::now());
+        Ok(Self { file: BufWriter::new(File::create(path)?), position: 0, directory: Vec::new(), entries: 0, time, date })
+    }
+
+    pub fn add_file(&mut self, name: &str, contents: &[u8]) -> Result<(), Box<dyn Error>> {
+        let offset = u32::try_from(self.position)?;
+        let size = u32::try_from(contents.len())?;
+        let name_length = u16::try_from(name.len())?;
+        self.entries = self.entries.checked_add(1).ok_or("Too many files for a ZIP archive")?;
+        // Fields shared by the local header and the central directory entry, from the version needed to the extra field length
+        let mut common = Vec::new();
+        for field in [ZIP_VERSION, ZIP_UTF8_FLAG, 0, self.time, self.date] {
+            common.extend_from_slice(&field.to_le_bytes());
+        }
+        for field in [crc32(contents), size, size] {
+            common.extend_from_slice(&field.to_le_bytes());
+        }
+        common.extend_from_slice(&name_length.to_le_bytes());
+        common.extend_from_slice(&0u16.to_le_bytes());
+
+        self.write(&0x0403_4b50u32.to_le_bytes())?;
+        self.write(&common)?;
+        self.write(name.as_bytes())?;
+        self.write(contents)?;
+
+        self.directory.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+        self.directory.extend_from_slice(&ZIP_VERSION.to_le_bytes());
+        self.directory.extend_from_slice(&common);
+        // Comment length, disk number and internal and external attributes
+        self.directory.extend_from_slice(&[0; 10]);
+        self.directory.extend_from_slice(&offset.to_le_bytes());
+        self.directory.extend_from_slice(name.as_bytes());
+
+        Ok(())
+    }
+
+    /// Writes the central directory, completing the archive
+    pub fn finish(mut self) -> Result<(), Box<dyn Error>> {
+        let directory_offset = u32::try_from(self.position)?;
+        let directory_size = u32::try_from(self.directory.len())?;
+        let directory = std::mem::take(&mut self.directory);
+        self.write(&directory)?;
+
+        let mut end = Vec::new();
+        end.extend_from_slice(&0x0605_4b50u32.to_le_bytes());
+        // Number of this disk and of the disk where the central directory starts
+        end.extend_from_slice(&[0; 4]);
+        end.extend_from_slice(&self.entries.to_le_bytes());
+        end.extend_from_slice(&self.entries.to_le_bytes());
+        end.extend_from_slice(&directory_size.to_le_bytes());
+        end.extend_from_slice(&directory_offset.to_le_bytes());
+        end.extend_from_slice(&0u16.to_le_bytes());
+        self.write(&end)?;
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.file.write_all(bytes)?;
+        self.position += bytes.len();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+        u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+    }
+
+    fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn zip_entry_headers_describe_the_file() {
+        let path = std::env::temp_dir().join(format!("slickscan-zip-test-{}.zip", std::process::id()));
+        let mut zip = ZipWriter::create(&path).unwrap();
+        zip.add_file("page.png", b"contents").unwrap();
+        zip.finish().unwrap();
+        let archive = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        // Local header: signature, version, UTF-8 flag, stored, CRC and sizes, then the name and contents
+        assert_eq!(u32_at(&archive, 0), 0x0403_4b50);
+        assert_eq!((u16_at(&archive, 4), u16_at(&archive, 6), u16_at(&archive, 8)), (ZIP_VERSION, ZIP_UTF8_FLAG, 0));
+        assert_eq!((u32_at(&archive, 14), u32_at(&archive, 18), u32_at(&archive, 22)), (crc32(b"contents"), 8, 8));
+        assert_eq!((u16_at(&archive, 26), u16_at(&archive, 28)), (8, 0));
+        assert_eq!(&archive[30..46], b"page.pngcontents");
+
+        // Central directory entry, which repeats the local header's fields and points back at it
+        let central = 46;
+        assert_eq!(u32_at(&archive, central), 0x0201_4b50);
+        assert_eq!(&archive[central + 6..central + 32], &archive[4..30]);
+        assert_eq!(u32_at(&archive, central + 42), 0);
+        assert_eq!(&archive[central + 46..central + 54], b"page.png");
+
+        // End of central directory: one entry, with the directory's size and offset
+        let end = central + 54;
+        assert_eq!(u32_at(&archive, end), 0x0605_4b50);
+        assert_eq!((u16_at(&archive, end + 8), u16_at(&archive, end + 10)), (1, 1));
+        assert_eq!((u32_at(&archive, end + 12), u32_at(&archive, end + 16)), (54, 46));
+        assert_eq!(archive.len(), end + 22);
+    }
+}
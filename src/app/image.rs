@@ -1,25 +1,943 @@
-use eframe::epaint::{Vec2, TextureHandle, Color32};
+use std::{borrow::Cow, fs, io, ops::Range, path::{Path, PathBuf}, sync::atomic::{AtomicUsize, Ordering}, time::SystemTime};
+
+use eframe::{egui::{Context, TextureOptions}, epaint::{Vec2, Pos2, Rect, TextureHandle, Color32, ColorImage}};
+use serde::{Deserialize, Serialize};
+
+use super::{pdf::{Margins, PaperSize, PdfCompression}, strings::Strings};
+use crate::{util::{content_bounds, insert_after_every}, ID1_CARD_HEIGHT_MM, ID1_CARD_WIDTH_MM, MM_PER_INCH, SCAN_DPI};
 
 pub fn scale_image_size(original: Vec2, max_x: f32) -> Vec2 {
     let factor = max_x / original.x;
     original * factor
 }
 
-pub fn selection_tint_color(page_i: usize, total_selected: usize) -> Color32 {
+pub fn fit_image_size(original: Vec2, max: Vec2) -> Vec2 {
+    let factor = (max.x / original.x).min(max.y / original.y);
+    original * factor
+}
+
+/// Fraction of the median page height below which a page is considered a partial read
+const PARTIAL_PAGE_HEIGHT_RATIO: f32 = 0.8;
+
+pub fn find_partial_pages(heights: &[usize]) -> Vec<usize> {
+    if heights.len() < 2 {
+        return vec![];
+    }
+
+    let mut sorted = heights.to_vec();
+    sorted.sort_unstable();
+    #[allow(clippy::cast_precision_loss)]
+    let threshold = sorted[sorted.len() / 2] as f32 * PARTIAL_PAGE_HEIGHT_RATIO;
+
+    #[allow(clippy::cast_precision_loss)]
+    heights.iter().enumerate()
+        .filter(|(_, &height)| (height as f32) < threshold)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Tints selected pages with a gradient from white towards `color` as the page number increases
+pub fn selection_tint_color(page_i: usize, total_selected: usize, color: [u8; 3], alpha: u8) -> Color32 {
+    #[allow(clippy::cast_precision_loss)]
+    let strength = if let 1 = total_selected {
+        1.0
+    } else {
+        ((page_i + 1) as f32) / (total_selected as f32)
+    };
+
     #[allow(clippy::cast_possible_truncation)]
     #[allow(clippy::cast_sign_loss)]
+    let channel = |c: u8| 255 - (f32::from(255 - c) * strength) as u8;
+    Color32::from_rgba_premultiplied(channel(color[0]), channel(color[1]), channel(color[2]), alpha)
+}
+
+/// Pixel processing applied on top of the scanned data, both in previews and in saved output
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PixelAdjustments {
+    pub invert: bool,
+    /// Reduce pages to black text on a white background, see `clean_up_document`
+    #[serde(default)]
+    pub cleanup: bool,
+    #[serde(default)]
+    pub cleanup_settings: DocumentCleanup,
+}
+
+impl PixelAdjustments {
+    pub fn is_identity(&self) -> bool {
+        !self.invert && !self.cleanup
+    }
+}
+
+/// Strength parameters of the document cleanup filter
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentCleanup {
+    /// Width of the area each pixel is compared with, as a percentage of the page width. Should be larger than the text.
+    pub neighborhood_percent: u8,
+    /// How much darker than its surroundings a pixel must be to count as ink, in percent
+    pub threshold_percent: u8,
+}
+
+impl Default for DocumentCleanup {
+    fn default() -> Self {
+        Self {
+            neighborhood_percent: 4,
+            threshold_percent: 15,
+        }
+    }
+}
+
+/// Adaptive thresholding of RGB pixels: pixels clearly darker than their surroundings become black and all others white.
+/// Unlike a fixed threshold, this also removes shading, yellowed paper and faint bleed-through.
+pub fn clean_up_document(pixels: &mut [u8], size: [usize; 2], cleanup: DocumentCleanup) {
+    let [width, height] = size;
+    if width == 0 || height == 0 || pixels.len() < width * height * 3 {
+        return;
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    let luminance: Vec<u8> = pixels.chunks_exact(3)
+        .map(|pixel| ((u32::from(pixel[0]) * 299 + u32::from(pixel[1]) * 587 + u32::from(pixel[2]) * 114) / 1000) as u8)
+        .collect();
+    let row = |y: usize| &luminance[y * width..(y + 1) * width];
+
+    // Each column's sum over the rows around the current one, moved down a row at a time.
+    // Summing those along a row then gives any neighborhood's sum without a page-sized table.
+    let half = (width * usize::from(cleanup.neighborhood_percent) / 200).max(1);
+    let mut column_sums = vec![0u32; width];
+    let mut row_sums = vec![0u64; width + 1];
+    let mut rows_summed = 0..0;
+
+    let keep_percent = 100 - u64::from(cleanup.threshold_percent.min(100));
+    for y in 0..height {
+        let (top, bottom) = (y.saturating_sub(half), (y + half + 1).min(height));
+        for added in rows_summed.end..bottom {
+            column_sums.iter_mut().zip(row(added)).for_each(|(sum, value)| *sum += u32::from(*value));
+        }
+        for removed in rows_summed.start..top {
+            column_sums.iter_mut().zip(row(removed)).for_each(|(sum, value)| *sum -= u32::from(*value));
+        }
+        rows_summed = top..bottom;
+
+        for x in 0..width {
+            row_sums[x + 1] = row_sums[x] + u64::from(column_sums[x]);
+        }
+
+        for x in 0..width {
+            let (left, right) = (x.saturating_sub(half), (x + half + 1).min(width));
+            let sum = row_sums[right] - row_sums[left];
+            let count = ((right - left) * (bottom - top)) as u64;
+
+            let ink = u64::from(luminance[y * width + x]) * count * 100 < sum * keep_percent;
+            let index = (y * width + x) * 3;
+            pixels[index..index + 3].fill(if ink { 0 } else { u8::MAX });
+        }
+    }
+}
+
+pub fn invert_pixels(pixels: &mut [u8]) {
+    for byte in pixels {
+        *byte = !*byte;
+    }
+}
+
+pub fn apply_adjustments<'a>(pixels: &'a [u8], size: [usize; 2], adjustments: &PixelAdjustments) -> Cow<'a, [u8]> {
+    if adjustments.is_identity() {
+        return Cow::Borrowed(pixels);
+    }
+
+    let mut adjusted = pixels.to_vec();
+    if adjustments.invert {
+        invert_pixels(&mut adjusted);
+    }
+    if adjustments.cleanup {
+        clean_up_document(&mut adjusted, size, adjustments.cleanup_settings);
+    }
+
+    Cow::Owned(adjusted)
+}
+
+/// Preview textures are generated at this multiple of the displayed thumbnail width,
+/// leaving some detail for high-DPI displays and the hover zoom
+const PREVIEW_OVERSAMPLING: f32 = 2.0;
+
+/// Maximum width in pixels of preview textures for thumbnails shown `max_x` points wide
+pub fn preview_width(max_x: f32) -> usize {
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    let width = (max_x * PREVIEW_OVERSAMPLING).round() as usize;
+    width
+}
+
+/// Shrinks RGB pixel data by an integer factor so it is at most `max_width` pixels wide,
+/// averaging each block of source pixels
+pub fn downscale_pixels(pixels: &[u8], size: [usize; 2], max_width: usize) -> ([usize; 2], Cow<'_, [u8]>) {
+    let [width, height] = size;
+    let factor = width.div_ceil(max_width.max(1));
+    if factor <= 1 {
+        return (size, Cow::Borrowed(pixels));
+    }
+
+    let scaled = [(width / factor).max(1), (height / factor).max(1)];
+    let mut downscaled = Vec::with_capacity(scaled[0] * scaled[1] * 3);
+    for y in 0..scaled[1] {
+        let rows = y * factor..((y + 1) * factor).min(height);
+        for x in 0..scaled[0] {
+            let columns = x * factor..((x + 1) * factor).min(width);
+
+            let mut sums = [0u32; 3];
+            for row in rows.clone() {
+                for column in columns.clone() {
+                    let start = (row * width + column) * 3;
+                    for (sum, value) in sums.iter_mut().zip(&pixels[start..start + 3]) {
+                        *sum += u32::from(*value);
+                    }
+                }
+            }
+
+            #[allow(clippy::cast_possible_truncation)]
+            let count = (rows.len() * columns.len()) as u32;
+            #[allow(clippy::cast_possible_truncation)]
+            downscaled.extend(sums.map(|sum| (sum / count) as u8));
+        }
+    }
+
+    (scaled, Cow::Owned(downscaled))
+}
+
+/// Resizes RGB pixel data to `new_size` with bilinear filtering, or `None` if `size` doesn't describe the data
+pub fn resample_pixels(pixels: &[u8], size: [usize; 2], new_size: [usize; 2]) -> Option<Vec<u8>> {
+    let source = ::image::ImageBuffer::<::image::Rgb<u8>, &[u8]>::from_raw(u32::try_from(size[0]).ok()?, u32::try_from(size[1]).ok()?, pixels)?;
+    let resized = ::image::imageops::resize(&source, u32::try_from(new_size[0]).ok()?, u32::try_from(new_size[1]).ok()?, ::image::imageops::FilterType::Triangle);
+    Some(resized.into_raw())
+}
+
+/// Builds a preview texture image no wider than `max_width` from full-resolution RGB pixel data
+pub fn build_preview_image(size: [usize; 2], pixels: &[u8], adjustments: &PixelAdjustments, max_width: usize) -> ColorImage {
+    let (size, pixels) = downscale_pixels(pixels, size, max_width);
+    let pixels_with_alpha = insert_after_every(&apply_adjustments(&pixels, size, adjustments), 3, 255);
+    ColorImage::from_rgba_unmultiplied(size, &pixels_with_alpha)
+}
+
+/// Row positions splitting `height` rows into segments of at most `rows_per_segment` rows
+pub fn interval_breaks(height: usize, rows_per_segment: usize) -> Vec<usize> {
+    (1..).map(|n| n * rows_per_segment.max(1))
+        .take_while(|row| *row < height)
+        .collect()
+}
+
+/// Row ranges of the segments that `height` rows are split into at the given (sorted) row positions
+pub fn segment_rows(height: usize, breaks: &[usize]) -> Vec<Range<usize>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for row in breaks.iter().copied().filter(|row| *row > 0 && *row < height).chain([height]) {
+        if row > start {
+            segments.push(start..row);
+            start = row;
+        }
+    }
+
+    segments
+}
+
+/// Splits RGB pixel data into vertical segments at the given (sorted) row positions
+pub fn split_at_rows<'a>(pixels: &'a [u8], size: [usize; 2], breaks: &[usize]) -> Vec<([usize; 2], &'a [u8])> {
+    let row_bytes = size[0] * 3;
+    if row_bytes == 0 {
+        return vec![];
+    }
+
+    segment_rows(size[1], breaks).into_iter()
+        .map(|rows| ([size[0], rows.len()], &pixels[rows.start * row_bytes..rows.end * row_bytes]))
+        .collect()
+}
+
+/// How two pages are joined into one
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergeDirection {
+    /// The second page to the right of the first
+    #[default]
+    SideBySide,
+    /// The second page below the first
+    Stacked,
+}
+
+/// Placement of two pages within their merged image
+pub struct MergeLayout {
+    pub size: [usize; 2],
+    /// Size of the second page once fitted, and its top-left corner in the merged image. The first page is always at the origin.
+    pub second_size: [usize; 2],
+    pub second_offset: [usize; 2],
+}
+
+impl MergeLayout {
+    /// The second page is scaled so the shared dimension (height side by side, width stacked) matches the first when
+    /// `scale_to_match`; otherwise the shorter page is padded with white.
+    pub fn new(first: [usize; 2], second: [usize; 2], direction: MergeDirection, scale_to_match: bool) -> Self {
+        let shared = match direction {
+            MergeDirection::SideBySide  => 1,
+            MergeDirection::Stacked     => 0,
+        };
+        let joined = 1 - shared;
+
+        let second_size = if scale_to_match && second[shared] != first[shared] && second[shared] > 0 {
+            let mut scaled = [0; 2];
+            scaled[shared] = first[shared];
+            #[allow(clippy::cast_precision_loss)]
+            #[allow(clippy::cast_possible_truncation)]
+            #[allow(clippy::cast_sign_loss)]
+            let length = (second[joined] as f64 * first[shared] as f64 / second[shared] as f64).round() as usize;
+            scaled[joined] = length.max(1);
+            scaled
+        } else {
+            second
+        };
+
+        let mut size = [0; 2];
+        size[shared] = first[shared].max(second_size[shared]);
+        size[joined] = first[joined] + second_size[joined];
+        let mut second_offset = [0; 2];
+        second_offset[joined] = first[joined];
+
+        Self { size, second_size, second_offset }
+    }
+}
+
+/// Joins two pages of RGB pixel data as laid out by `layout`, or `None` if a size doesn't describe its data
+pub fn merge_pixels(first: &[u8], first_size: [usize; 2], second: &[u8], second_size: [usize; 2], layout: &MergeLayout) -> Option<Vec<u8>> {
+    let second = if second_size == layout.second_size {
+        Cow::Borrowed(second)
+    } else {
+        Cow::Owned(resample_pixels(second, second_size, layout.second_size)?)
+    };
+    if first.len() != first_size[0] * first_size[1] * 3 || second.len() != layout.second_size[0] * layout.second_size[1] * 3 {
+        return None;
+    }
+
+    let mut merged = vec![255; layout.size[0] * layout.size[1] * 3];
+    let row_bytes = layout.size[0] * 3;
+    for (pixels, size, [x, y]) in [(first, first_size, [0, 0]), (&second[..], layout.second_size, layout.second_offset)] {
+        for (row, source) in pixels.chunks_exact(size[0] * 3).enumerate() {
+            let start = (y + row) * row_bytes + x * 3;
+            merged[start..start + source.len()].copy_from_slice(source);
+        }
+    }
+
+    Some(merged)
+}
+
+/// Physical size of a card whose two sides are put on one page
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IdCardSize {
+    pub width_mm: f32,
+    pub height_mm: f32,
+}
+
+impl Default for IdCardSize {
+    fn default() -> Self {
+        Self { width_mm: ID1_CARD_WIDTH_MM, height_mm: ID1_CARD_HEIGHT_MM }
+    }
+}
+
+/// Space between the two sides of a card on its page
+const ID_CARD_GAP_MM: f32 = 10.0;
+
+#[allow(clippy::cast_possible_truncation)]
+#[allow(clippy::cast_sign_loss)]
+fn mm_to_scan_pixels(mm: f32) -> usize {
+    (mm / MM_PER_INCH * SCAN_DPI).round().max(1.0) as usize
+}
+
+/// Lays out the two sides of a card, each cropped to what stands out from the scanner's background and scaled to `card`,
+/// one above the other in the middle of a white image filling `area_mm` at `SCAN_DPI`, so that it prints at actual size.
+/// Returns the image's size and RGB pixel data, or `None` if a size doesn't describe its data.
+pub fn id_card_pixels(front: &[u8], front_size: [usize; 2], back: &[u8], back_size: [usize; 2], card: IdCardSize, area_mm: (f32, f32)) -> Option<([usize; 2], Vec<u8>)> {
+    let size = [mm_to_scan_pixels(area_mm.0), mm_to_scan_pixels(area_mm.1)];
+    // A card too large for the page is shrunk to fit, keeping both sides on it
+    let card_size = [mm_to_scan_pixels(card.width_mm).min(size[0]), mm_to_scan_pixels(card.height_mm).min(size[1] / 2).max(1)];
+    let gap = mm_to_scan_pixels(ID_CARD_GAP_MM).min(size[1] - card_size[1] * 2);
+    let left = (size[0] - card_size[0]) / 2;
+    let top = (size[1] - card_size[1] * 2 - gap) / 2;
+
+    let mut page = vec![255; size[0] * size[1] * 3];
+    for (i, (pixels, pixels_size)) in [(front, front_size), (back, back_size)].into_iter().enumerate() {
+        if pixels.len() != pixels_size[0] * pixels_size[1] * 3 {
+            return None;
+        }
+        let [x0, y0, x1, y1] = content_bounds(pixels, pixels_size).unwrap_or([0, 0, pixels_size[0], pixels_size[1]]);
+        let cropped: Vec<u8> = pixels.chunks_exact(pixels_size[0] * 3)
+            .take(y1)
+            .skip(y0)
+            .flat_map(|row| &row[x0 * 3..x1 * 3])
+            .copied()
+            .collect();
+        let scaled = resample_pixels(&cropped, [x1 - x0, y1 - y0], card_size)?;
+
+        let y = top + i * (card_size[1] + gap);
+        for (row, source) in scaled.chunks_exact(card_size[0] * 3).enumerate() {
+            let start = ((y + row) * size[0] + left) * 3;
+            page[start..start + source.len()].copy_from_slice(source);
+        }
+    }
+
+    Some((size, page))
+}
+
+/// Mirroring applied to a page before any other per-page processing
+#[derive(Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Flip {
+    pub horizontal: bool,
+    pub vertical: bool,
+}
+
+impl Flip {
+    pub fn is_identity(self) -> bool {
+        self == Self::default()
+    }
+
+    /// Texture coordinates that show a preview texture with this flip applied
+    pub fn uv(self) -> Rect {
+        let (left, right) = if self.horizontal { (1.0, 0.0) } else { (0.0, 1.0) };
+        let (top, bottom) = if self.vertical { (1.0, 0.0) } else { (0.0, 1.0) };
+        Rect::from_min_max(Pos2::new(left, top), Pos2::new(right, bottom))
+    }
+}
+
+/// Mirrors RGB pixel data, returning the flipped copy
+pub fn flip_pixels(pixels: &[u8], size: [usize; 2], flip: Flip) -> Vec<u8> {
+    let row_bytes = size[0] * 3;
+    if row_bytes == 0 {
+        return Vec::new();
+    }
+
+    let mut rows: Vec<&[u8]> = pixels.chunks_exact(row_bytes).collect();
+    if flip.vertical {
+        rows.reverse();
+    }
+
+    let mut flipped = Vec::with_capacity(pixels.len());
+    for row in rows {
+        if flip.horizontal {
+            for pixel in row.chunks_exact(3).rev() {
+                flipped.extend_from_slice(pixel);
+            }
+        } else {
+            flipped.extend_from_slice(row);
+        }
+    }
+
+    flipped
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpineSide {
+    Left,
+    Right,
+}
+
+/// Simple book-curvature correction. Columns near the spine are stretched vertically about the page's
+/// center, undoing the compression caused by the page curving away from the scanner glass.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Dewarp {
+    pub spine: SpineSide,
+    /// Vertical stretch at the spine itself, as a fraction of the page height
+    pub strength: f32,
+    /// Fraction of the page width, measured from the spine, that is corrected
+    pub width: f32,
+}
+
+impl Default for Dewarp {
+    fn default() -> Self {
+        Self {
+            spine: SpineSide::Right,
+            strength: 0.05,
+            width: 0.25,
+        }
+    }
+}
+
+/// Applies a curvature correction to RGB pixel data, returning the corrected copy
+pub fn dewarp_pixels(pixels: &[u8], size: [usize; 2], dewarp: &Dewarp) -> Vec<u8> {
+    let [width, height] = size;
+    let mut corrected = pixels.to_vec();
+
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_precision_loss)]
+    let band = ((width as f32 * dewarp.width).round() as usize).min(width);
+    if band == 0 || height == 0 || dewarp.strength <= 0.0 {
+        return corrected;
+    }
+
     #[allow(clippy::cast_precision_loss)]
-    let blueness = if let 1 = total_selected {
-        255.0
+    let center = (height - 1) as f32 / 2.0;
+    for offset in 0..band {
+        let x = match dewarp.spine {
+            SpineSide::Left     => offset,
+            SpineSide::Right    => width - 1 - offset,
+        };
+
+        // Strongest at the spine, easing off to nothing at the edge of the band
+        #[allow(clippy::cast_precision_loss)]
+        let closeness = 1.0 - offset as f32 / band as f32;
+        let stretch = 1.0 + dewarp.strength * closeness * closeness;
+
+        for y in 0..height {
+            #[allow(clippy::cast_precision_loss)]
+            #[allow(clippy::cast_possible_truncation)]
+            #[allow(clippy::cast_sign_loss)]
+            let source_y = ((center + (y as f32 - center) / stretch).round() as usize).min(height - 1);
+
+            let target = (y * width + x) * 3;
+            let source = (source_y * width + x) * 3;
+            corrected[target..target + 3].copy_from_slice(&pixels[source..source + 3]);
+        }
+    }
+
+    corrected
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AnnotationTool {
+    Pen,
+    Highlighter,
+}
+
+impl AnnotationTool {
+    /// Stroke width as a fraction of the page width
+    pub fn width(self) -> f32 {
+        match self {
+            Self::Pen           => 0.004,
+            Self::Highlighter   => 0.02,
+        }
+    }
+
+    pub fn color(self) -> Color32 {
+        match self {
+            Self::Pen           => Color32::from_rgb(200, 0, 0),
+            Self::Highlighter   => Color32::from_rgb(255, 230, 0),
+        }
+    }
+}
+
+/// A freehand stroke drawn over a page. Kept apart from the pixels and only drawn into them when saving.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub tool: AnnotationTool,
+    /// Points along the stroke as fractions of the page's width and height
+    pub points: Vec<[f32; 2]>,
+}
+
+/// Draws annotations into RGB pixel data, returning the annotated copy.
+/// Pen strokes are opaque; highlighter strokes multiply with the page so text stays legible.
+pub fn render_annotations(pixels: &[u8], size: [usize; 2], annotations: &[Annotation]) -> Vec<u8> {
+    let [width, height] = size;
+    let mut annotated = pixels.to_vec();
+
+    for annotation in annotations {
+        #[allow(clippy::cast_precision_loss)]
+        let points: Vec<[f32; 2]> = annotation.points.iter()
+            .map(|[x, y]| [x * width as f32, y * height as f32])
+            .collect();
+        #[allow(clippy::cast_precision_loss)]
+        let radius = (annotation.tool.width() * width as f32 / 2.0).max(0.5);
+        if points.is_empty() {
+            continue;
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let to_pixel = |value: f32, limit: usize| (value.max(0.0) as usize).min(limit);
+        // Pixels within `radius` of the points, clipped to the page
+        let span = |from: &[[f32; 2]], axis: usize, limit: usize| {
+            let low = from.iter().map(|point| point[axis]).fold(f32::INFINITY, f32::min);
+            let high = from.iter().map(|point| point[axis]).fold(f32::NEG_INFINITY, f32::max);
+            to_pixel(low - radius, limit)..to_pixel(high + radius + 1.0, limit)
+        };
+
+        // Mark covered pixels first so overlapping segments don't blend twice. Only the stroke's bounds are tracked.
+        let (bounds_columns, bounds_rows) = (span(&points, 0, width), span(&points, 1, height));
+        let bounds_width = bounds_columns.len();
+        let mut covered = vec![false; bounds_width * bounds_rows.len()];
+        let segments = points.windows(2).map(|pair| (pair[0], pair[1]))
+            .chain(points.first().filter(|_| points.len() == 1).map(|point| (*point, *point)));
+        for (start, end) in segments {
+            for y in span(&[start, end], 1, height) {
+                for x in span(&[start, end], 0, width) {
+                    #[allow(clippy::cast_precision_loss)]
+                    let center = [x as f32 + 0.5, y as f32 + 0.5];
+                    if distance_to_segment(center, start, end) <= radius {
+                        covered[(y - bounds_rows.start) * bounds_width + x - bounds_columns.start] = true;
+                    }
+                }
+            }
+        }
+
+        let color = annotation.tool.color();
+        let color = [color.r(), color.g(), color.b()];
+        let covered_pixels = bounds_rows.clone()
+            .flat_map(|y| bounds_columns.clone().map(move |x| (x, y)))
+            .zip(&covered)
+            .filter(|(_, covered)| **covered);
+        for ((x, y), _) in covered_pixels {
+            let index = (y * width + x) * 3;
+            for (channel, color_channel) in annotated[index..index + 3].iter_mut().zip(color) {
+                *channel = match annotation.tool {
+                    AnnotationTool::Pen         => color_channel,
+                    #[allow(clippy::cast_possible_truncation)]
+                    AnnotationTool::Highlighter => (u16::from(*channel) * u16::from(color_channel) / 255) as u8,
+                };
+            }
+        }
+    }
+
+    annotated
+}
+
+fn distance_to_segment(point: [f32; 2], start: [f32; 2], end: [f32; 2]) -> f32 {
+    let segment = [end[0] - start[0], end[1] - start[1]];
+    let length_squared = segment[0] * segment[0] + segment[1] * segment[1];
+    let t = if length_squared > 0.0 {
+        (((point[0] - start[0]) * segment[0] + (point[1] - start[1]) * segment[1]) / length_squared).clamp(0.0, 1.0)
     } else {
-        (((page_i + 1) as f32) / (total_selected as f32)) * 255.0
-    } as u8;
-    Color32::from_rgba_premultiplied(255 - blueness, 255 - blueness, 255, 50)
+        0.0
+    };
+
+    let closest = [start[0] + segment[0] * t, start[1] + segment[1] * t];
+    ((point[0] - closest[0]).powi(2) + (point[1] - closest[1]).powi(2)).sqrt()
+}
+
+/// Uncompressed and estimated compressed byte sizes of a page of `size` pixels scanned in `format`, when embedded
+/// as `image_encoding` does: 3 bytes per pixel for color, 1 byte for gray and 1 bit for lineart
+pub fn estimate_image_bytes(size: [usize; 2], format: PageFormat, compression: PdfCompression) -> (u64, u64) {
+    let raw = match format {
+        PageFormat::Color => size[0] * size[1] * 3,
+        PageFormat::Gray => size[0] * size[1],
+        // Each row starts on a byte boundary
+        PageFormat::Lineart => size[0].div_ceil(8) * size[1],
+    } as u64;
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    #[allow(clippy::cast_precision_loss)]
+    let compressed = (raw as f64 * compression.estimated_ratio()) as u64;
+    (raw, compressed)
+}
+
+/// Decodes an image file into 8-bit RGB pixel data and its dimensions
+pub fn decode_image_file(path: &Path) -> Result<([usize; 2], Vec<u8>), ::image::ImageError> {
+    let rgb = ::image::open(path)?.into_rgb8();
+    let size = [rgb.width() as usize, rgb.height() as usize];
+    Ok((size, rgb.into_raw()))
+}
+
+static SPILL_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Directory holding page data moved out of memory for this process
+pub fn spill_directory() -> PathBuf {
+    std::env::temp_dir().join(format!("slickscan-{}", std::process::id()))
+}
+
+/// Where a page's full-resolution pixel data currently lives
+pub enum PixelStorage {
+    Memory(Vec<u8>),
+    Disk(PathBuf),
 }
 
 pub struct ScanEntry {
-    pub pixels: Vec<u8>,
+    pixels: PixelStorage,
+    pub size: [usize; 2],
     pub texture_handle: TextureHandle,
     pub selected_as_page: Option<usize>,
     pub saved_to_file: bool,
-}
\ No newline at end of file
+    pub note: String,
+    pub page_breaks: Vec<usize>,
+    pub dewarp: Option<Dewarp>,
+    pub annotations: Vec<Annotation>,
+    pub flip: Flip,
+    /// When the page arrived from the scanner or was imported
+    pub scanned_at: SystemTime,
+    pub format: PageFormat,
+    /// Paper size and margins of an ID card page, which shows the card at actual size only on the paper it was laid out for
+    pub id_card_layout: Option<(PaperSize, Margins)>,
+}
+
+impl ScanEntry {
+    pub fn new(ctx: &Context, texture_name: String, page: DecodedPage) -> Self {
+        Self {
+            pixels: PixelStorage::Memory(page.pixels),
+            size: page.size,
+            texture_handle: ctx.load_texture(texture_name, page.image, TextureOptions::LINEAR),
+            selected_as_page: None,
+            saved_to_file: false,
+            note: String::new(),
+            page_breaks: Vec::new(),
+            dewarp: None,
+            annotations: Vec::new(),
+            flip: Flip::default(),
+            scanned_at: SystemTime::now(),
+            format: page.format,
+            id_card_layout: None,
+        }
+    }
+
+    /// An independent copy with its own pixel data held in memory. The preview texture is shared, as it shows the same pixels.
+    pub fn staged_copy(&self) -> io::Result<Self> {
+        Ok(Self {
+            pixels: PixelStorage::Memory(self.pixels()?.into_owned()),
+            size: self.size,
+            texture_handle: self.texture_handle.clone(),
+            selected_as_page: None,
+            saved_to_file: false,
+            note: self.note.clone(),
+            page_breaks: self.page_breaks.clone(),
+            dewarp: self.dewarp,
+            annotations: self.annotations.clone(),
+            flip: self.flip,
+            scanned_at: self.scanned_at,
+            format: self.format,
+            id_card_layout: self.id_card_layout,
+        })
+    }
+
+    /// Full-resolution pixel data, read back from disk if it was spilled
+    pub fn pixels(&self) -> io::Result<Cow<'_, [u8]>> {
+        match &self.pixels {
+            PixelStorage::Memory(pixels) => Ok(Cow::Borrowed(pixels)),
+            PixelStorage::Disk(path) => Ok(Cow::Owned(fs::read(path)?)),
+        }
+    }
+
+    pub fn spill_to_disk(&mut self) -> io::Result<()> {
+        if let PixelStorage::Memory(pixels) = &self.pixels {
+            let directory = spill_directory();
+            fs::create_dir_all(&directory)?;
+
+            let path = directory.join(format!("page-{}.rgb", SPILL_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)));
+            fs::write(&path, pixels)?;
+            self.pixels = PixelStorage::Disk(path);
+        }
+
+        Ok(())
+    }
+
+    pub fn load_into_memory(&mut self) -> io::Result<()> {
+        if let PixelStorage::Disk(path) = &self.pixels {
+            let pixels = fs::read(path)?;
+            let _ = fs::remove_file(path);
+            self.pixels = PixelStorage::Memory(pixels);
+        }
+
+        Ok(())
+    }
+
+    pub fn resident_bytes(&self) -> usize {
+        match &self.pixels {
+            PixelStorage::Memory(pixels) => pixels.len(),
+            PixelStorage::Disk(_) => 0,
+        }
+    }
+
+    pub fn is_spilled(&self) -> bool {
+        matches!(self.pixels, PixelStorage::Disk(_))
+    }
+
+    /// `pixels` with this page's flip, annotations and dewarping applied, in the order used for output
+    pub fn apply_edits<'a>(&self, pixels: Cow<'a, [u8]>) -> Cow<'a, [u8]> {
+        // Everything else on the page (annotations, breaks) was placed on the flipped preview
+        let pixels = if self.flip.is_identity() {
+            pixels
+        } else {
+            Cow::Owned(flip_pixels(&pixels, self.size, self.flip))
+        };
+        // Annotations are drawn before dewarping so they stay with the content they mark
+        let pixels = if self.annotations.is_empty() {
+            pixels
+        } else {
+            Cow::Owned(render_annotations(&pixels, self.size, &self.annotations))
+        };
+        match &self.dewarp {
+            Some(dewarp) => Cow::Owned(dewarp_pixels(&pixels, self.size, dewarp)),
+            None => pixels,
+        }
+    }
+
+    pub fn refresh_texture(&mut self, adjustments: &PixelAdjustments, max_width: usize) -> io::Result<()> {
+        let image = build_preview_image(self.size, &self.pixels()?, adjustments, max_width);
+        self.texture_handle.set(image, TextureOptions::LINEAR);
+        Ok(())
+    }
+}
+
+impl Drop for ScanEntry {
+    fn drop(&mut self) {
+        if let PixelStorage::Disk(path) = &self.pixels {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Page data decoded on the reading thread, ready to be uploaded as a texture on the UI thread
+pub struct DecodedPage {
+    /// Dimensions of the full-resolution `pixels`, which may be larger than the preview `image`
+    pub size: [usize; 2],
+    pub pixels: Vec<u8>,
+    pub image: ColorImage,
+    pub adjustments: PixelAdjustments,
+    pub preview_width: usize,
+    pub format: PageFormat,
+}
+
+/// How a page was scanned, which decides how it is embedded in PDFs even though pixels are always stored as RGB
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum PageFormat {
+    /// Also used for imported images and pages combined from different formats
+    #[default]
+    Color,
+    Gray,
+    /// One bit per pixel
+    Lineart,
+}
+
+impl PageFormat {
+    /// Format of a page combining pages of formats `self` and `other`
+    pub fn combine(self, other: Self) -> Self {
+        match (self, other) {
+            _ if self == other                          => self,
+            (Self::Color, _) | (_, Self::Color)         => Self::Color,
+            _                                           => Self::Gray,
+        }
+    }
+
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::Color     => text.format_color,
+            Self::Gray      => text.format_gray,
+            Self::Lineart   => text.format_lineart,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pixel(pixels: &[u8], width: usize, x: usize, y: usize) -> u8 {
+        pixels[(y * width + x) * 3]
+    }
+
+    #[test]
+    fn cleanup_drops_faint_specks_and_dark_borders_but_keeps_ink() {
+        // A 100x100 white page with a 20 pixel dark strip down the left edge, a faint speck and a dark dot of ink
+        let width = 100;
+        let mut pixels = vec![255; width * width * 3];
+        let mut paint = |x: usize, y: usize, value: u8| pixels[(y * width + x) * 3..(y * width + x) * 3 + 3].fill(value);
+        for (x, y) in (0..20).flat_map(|x| (0..width).map(move |y| (x, y))) {
+            paint(x, y, 30);
+        }
+        paint(60, 60, 235);
+        for (x, y) in (50..53).flat_map(|x| (30..33).map(move |y| (x, y))) {
+            paint(x, y, 40);
+        }
+
+        clean_up_document(&mut pixels, [width, width], DocumentCleanup::default());
+
+        assert_eq!(pixel(&pixels, width, 60, 60), 255);
+        assert_eq!(pixel(&pixels, width, 51, 31), 0);
+        // Inside the strip every neighbor is as dark, so only its edge stands out
+        assert_eq!(pixel(&pixels, width, 5, 50), 255);
+        assert_eq!(pixel(&pixels, width, 19, 50), 0);
+        assert_eq!(pixel(&pixels, width, 80, 80), 255);
+    }
+
+    #[test]
+    fn strokes_only_change_the_pixels_under_them() {
+        // A pen line across the middle of a white 100x100 page, half a pixel either side of y = 50
+        let stroke = Annotation { tool: AnnotationTool::Pen, points: vec![[0.2, 0.5], [0.8, 0.5]] };
+        let annotated = render_annotations(&[255; 100 * 100 * 3], [100, 100], &[stroke]);
+
+        let changed: Vec<(usize, usize)> = (0..100).flat_map(|y| (0..100).map(move |x| (x, y)))
+            .filter(|&(x, y)| pixel(&annotated, 100, x, y) != 255)
+            .collect();
+        let expected: Vec<(usize, usize)> = (49..51).flat_map(|y| (20..80).map(move |x| (x, y))).collect();
+        assert_eq!(changed, expected);
+        assert_eq!(annotated[(50 * 100 + 50) * 3..(50 * 100 + 50) * 3 + 3], [200, 0, 0]);
+    }
+
+    fn uniform(size: [usize; 2], value: u8) -> Vec<u8> {
+        vec![value; size[0] * size[1] * 3]
+    }
+
+    #[test]
+    fn side_by_side_merge_pads_the_shorter_page() {
+        let layout = MergeLayout::new([2, 3], [1, 2], MergeDirection::SideBySide, false);
+        assert_eq!((layout.size, layout.second_size, layout.second_offset), ([3, 3], [1, 2], [2, 0]));
+
+        let merged = merge_pixels(&uniform([2, 3], 10), [2, 3], &uniform([1, 2], 20), [1, 2], &layout).unwrap();
+        assert_eq!(merged.len(), 3 * 3 * 3);
+        for y in 0..3 {
+            assert_eq!(pixel(&merged, 3, 0, y), 10);
+            assert_eq!(pixel(&merged, 3, 1, y), 10);
+        }
+        assert_eq!(pixel(&merged, 3, 2, 0), 20);
+        assert_eq!(pixel(&merged, 3, 2, 1), 20);
+        assert_eq!(pixel(&merged, 3, 2, 2), 255);
+    }
+
+    #[test]
+    fn stacked_merge_scales_the_second_page_to_match() {
+        let layout = MergeLayout::new([4, 1], [2, 2], MergeDirection::Stacked, true);
+        assert_eq!((layout.size, layout.second_size, layout.second_offset), ([4, 5], [4, 4], [0, 1]));
+
+        let merged = merge_pixels(&uniform([4, 1], 10), [4, 1], &uniform([2, 2], 20), [2, 2], &layout).unwrap();
+        assert_eq!(merged.len(), 4 * 5 * 3);
+        assert!(merged[..4 * 3].iter().all(|&value| value == 10));
+        assert!(merged[4 * 3..].iter().all(|&value| value == 20));
+    }
+
+    #[test]
+    fn merge_rejects_sizes_that_do_not_match_the_data() {
+        let layout = MergeLayout::new([2, 2], [2, 2], MergeDirection::SideBySide, false);
+        assert!(merge_pixels(&uniform([2, 1], 10), [2, 2], &uniform([2, 2], 20), [2, 2], &layout).is_none());
+    }
+
+    #[test]
+    fn id_card_sides_are_centered_at_actual_size() {
+        // The front is a dark card on the scanner's white lid, the back fills its whole scan
+        let mut front = uniform([60, 40], 255);
+        for (x, y) in (5..35).flat_map(|x| (5..25).map(move |y| (x, y))) {
+            front[(y * 60 + x) * 3..(y * 60 + x) * 3 + 3].fill(10);
+        }
+        let back = [200, 0, 0].repeat(50 * 30);
+
+        // A one by half inch card on a two by three inch area, which is 300 by 150 pixels on a 600 by 900 pixel page
+        let card = IdCardSize { width_mm: 25.4, height_mm: 12.7 };
+        let (size, page) = id_card_pixels(&front, [60, 40], &back, [50, 30], card, (50.8, 76.2)).unwrap();
+        assert_eq!(size, [600, 900]);
+        assert_eq!(page.len(), 600 * 900 * 3);
+
+        // The 10 mm gap is 118 pixels, leaving (900 - 2 * 150 - 118) / 2 = 241 pixels above the front
+        let rgb = |x: usize, y: usize| &page[(y * 600 + x) * 3..(y * 600 + x) * 3 + 3];
+        for (x, y) in [(150, 241), (449, 390)] {
+            assert_eq!(rgb(x, y), [10, 10, 10]);
+        }
+        for (x, y) in [(150, 509), (449, 658)] {
+            assert_eq!(rgb(x, y), [200, 0, 0]);
+        }
+        for (x, y) in [(149, 241), (150, 240), (450, 390), (150, 391), (150, 508), (150, 659)] {
+            assert_eq!(rgb(x, y), [255, 255, 255]);
+        }
+    }
+
+    #[test]
+    fn size_estimate_follows_format_and_compression() {
+        let size = [101, 10];
+        assert_eq!(estimate_image_bytes(size, PageFormat::Color, PdfCompression::Off), (3030, 3030));
+        assert_eq!(estimate_image_bytes(size, PageFormat::Gray, PdfCompression::Off), (1010, 1010));
+        // 101 pixels take 13 bytes per row
+        assert_eq!(estimate_image_bytes(size, PageFormat::Lineart, PdfCompression::Off), (130, 130));
+
+        let (raw, compressed) = estimate_image_bytes(size, PageFormat::Color, PdfCompression::Fast);
+        let (_, smallest) = estimate_image_bytes(size, PageFormat::Color, PdfCompression::Smallest);
+        assert!(smallest < compressed && compressed < raw);
+    }
+}
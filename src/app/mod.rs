@@ -1,16 +1,29 @@
-use std::{sync::{Arc, Mutex}, thread::{JoinHandle, self}, path::PathBuf, fs::{File, self}, io::BufWriter};
+use std::{collections::HashMap, error::Error, fmt::{Debug, Display}, sync::{Arc, Mutex, mpsc::{self, Receiver, Sender}}, thread::{JoinHandle, self}, path::{Path, PathBuf}, fs, io, time::{Duration, Instant}, ops::{Range, RangeInclusive}};
 
-use eframe::{egui::{self, Response, Context, Sense, CollapsingHeader}, epaint::{Color32, ColorImage}};
-use printpdf::{PdfDocument, Mm, ImageXObject, Px, ColorSpace, ColorBits, Image, ImageTransform};
-use sane_scan::{self, Sane, Device, DeviceOptionValue, ValueType, OptionCapability, Frame};
-use tinyfiledialogs::{select_folder_dialog, MessageBoxIcon, message_box_ok, message_box_yes_no, YesNo};
+use eframe::{egui::{self, Response, Context, Sense, CollapsingHeader, Align2}, epaint::{Color32, FontId, Pos2, Rect, Stroke, TextureHandle, Vec2}};
+use printpdf::{PdfDocument, PdfDocumentReference, ImageXObject, Px, ColorSpace, Image, ImageTransform, BuiltinFont, IndirectFontRef};
+use serde::{Deserialize, Serialize};
+use sane_scan::{self, Sane, Device, DeviceOptionValue, ValueType, OptionCapability};
+use tinyfiledialogs::{select_folder_dialog, open_file_dialog, open_file_dialog_multi, save_file_dialog_with_filter, MessageBoxIcon, message_box_ok, message_box_yes_no, YesNo};
 
-use crate::{ERR_DIALOG_TITLE, util::{string_to_cstring, repeat_all_elements, insert_after_every, cstring_to_string, sane_fixed_to_float}, DEFAULT_FILE_NAME, LETTER_WIDTH_MM, LETTER_HEIGHT_MM, LETTER_WIDTH_IN, LETTER_HEIGHT_IN, commonvals::ValueCategory};
+use crate::{util::{cstring_to_string, format_byte_size, is_blank_page, split_at_separators, next_free_path, join_within, with_index_suffix, sane_fixed_to_float, sane_version_string, parse_backend_list}, DEFAULT_FILE_NAME, MM_PER_INCH, SCAN_DPI, commonvals::{ValueCategory, LengthUnit}};
 
-use self::{scanner::{ThDeviceHandle, EditingDeviceOptionValue, EditingDeviceOption}, image::{ScanEntry, scale_image_size, selection_tint_color}};
+use self::{scanner::{ThDeviceHandle, EditingDeviceOptionValue, EditingDeviceOption, ButtonPoller, ButtonTask, PrescanTask, PrescanPage, PrescanError, ScanArea, ScanSizeEstimate, FULL_SCAN_AREA, SCAN_AREA_OPTION_NAMES, SUMMARY_OPTION_NAMES, ResolutionOption, DuplexOption, ColorModeOption, ChannelOrder, PageReadError, RgbPage, read_shared_rgb_page, start_next_page, wait_while_paused, DeviceOpener, OpenProgress, is_sensor_option, is_resolution_option, is_source_option, is_mode_option, is_depth_option, restore_scan_settings, is_long_running_button}, image::{ScanEntry, DecodedPage, PageFormat, PixelAdjustments, scale_image_size, fit_image_size, selection_tint_color, find_partial_pages, build_preview_image, preview_width, resample_pixels, apply_adjustments, interval_breaks, segment_rows, split_at_rows, Dewarp, SpineSide, Annotation, AnnotationTool, MergeDirection, MergeLayout, merge_pixels, IdCardSize, id_card_pixels, estimate_image_bytes, decode_image_file, spill_directory}, errors::ErrorReport, export::{ImageFileFormat, ZipWriter, encode_page_image}, pdf::{note_annotation, image_encoding, compress_document, Corner, Margins, OutputFormat, PagePlacement, PaperSize, PdfCompression, Watermark}, pdfa::{convert_to_pdfa, empty_document}, pdfstream::StreamingPdf, notify::send_notification, tray::{TrayIcon, restore_window}, print::{available_printers, print_file}, session::{Session, SessionPage, page_directory}, settings::{Settings, DeviceScanSettings, OverwriteBehavior, SaveShortcut, ImageSmoothing, ScanProfile}, strings::{Language, Strings, fill}, testpattern::{test_pattern_device, is_test_pattern_device}};
 
 mod scanner;
 mod image;
+mod errors;
+mod export;
+mod pdf;
+mod pdfa;
+mod pdfstream;
+mod notify;
+mod print;
+mod session;
+mod settings;
+mod strings;
+mod testpattern;
+mod tray;
 
 pub struct App {
     // SANE backend objects
@@ -18,67 +31,218 @@ pub struct App {
     selected_scanner: usize,
     prev_selected_scanner: Option<usize>,
     selected_handle: Option<Arc<Mutex<ThDeviceHandle>>>,
+    device_opener: Option<DeviceOpener>,
     config_options: Vec<EditingDeviceOption>,
     sane_instance: Sane,
+    /// Backends enabled in the SANE configuration, read at startup for the diagnostics window
+    sane_backends: Option<Vec<String>>,
+    sensor_choices: Vec<(usize, String)>,
+    selected_sensor: usize,
+    button_scan_enabled: bool,
+    resolution_option: Option<ResolutionOption>,
+    duplex_option: Option<DuplexOption>,
+    color_mode_option: Option<ColorModeOption>,
+    /// The device has scan area options, so it can be reset to the whole bed
+    scan_area_available: bool,
+    /// Open while the scan area is being adjusted on a diagram of the bed
+    scan_area_diagram: Option<ScanAreaDiagram>,
+    button_task: Option<ButtonTask>,
+    prescan_task: Option<PrescanTask>,
 
     // UI state controls
-    ui_context: Arc<Mutex<Context>>,
+    ui_context: Context,
     search_network: bool,
+    no_devices_found: bool,
     scan_status: ScanStatus,
     image_max_x: f32,
+    /// Width that current preview textures were generated for, following `image_max_x`
+    preview_texture_width: usize,
+    /// When thumbnails were last resized with Ctrl+scroll, so textures are only regenerated once zooming stops
+    thumbnail_zoomed_at: Option<Instant>,
+    /// Page to scroll the page grid to on the next frame
+    scroll_to_page: Option<usize>,
+    /// Columns the page grid is pinned to, or `None` to fit as many thumbnails in each row as the window allows
+    thumbnail_columns: Option<usize>,
+    zoom_on_hover: bool,
     pages_selected: usize,
+    /// Where a drag-to-select marquee in the thumbnail grid was started, in screen coordinates
+    marquee_origin: Option<Pos2>,
+    low_resolution_warned: bool,
     dialog_status: DialogStatus,
+    /// Name typed in the profiles window for saving the current settings
+    profile_name: String,
+    /// Option to scroll into view the next time the configuration window is drawn
+    config_scroll_target: Option<i32>,
+    /// Errors not yet dismissed, oldest first
+    error_reports: Vec<ErrorReport>,
+    common_vals_unit: LengthUnit,
 
-    scanned_images: Arc<Mutex<Vec<ScanEntry>>>,
+    scanned_images: Vec<ScanEntry>,
+    /// Copies of pages collected across scans, to be saved together as one PDF
+    staged_pages: Vec<ScanEntry>,
     selected_page_indices: Vec<usize>,
     show_saved_images: bool,
+    pixel_adjustments: PixelAdjustments,
+    page_split: PageSplit,
+    page_order: PageOrder,
+    output_mode: OutputMode,
+    output_format: OutputFormat,
+    paper_size: PaperSize,
+    /// Resolution that pages are downsampled to when saving, if they exceed it
+    output_dpi: Option<u32>,
+    prescan: Option<Prescan>,
+    annotation_tool: AnnotationTool,
+    merge_options: MergeOptions,
+    print_options: PrintOptions,
+    /// Printers offered in the print window, found when it is opened
+    printers: Vec<String>,
+    /// Open the print window for the pages of the running scan once it finishes
+    print_after_scan: bool,
+    /// Page to replace with the single page of the running scan, keeping its place in the selection
+    rescan_slot: Option<usize>,
+    /// Pages kept by the last cancelled scan, reported until the next scan starts
+    cancelled_scan_pages: Option<usize>,
+    /// Write scanned pages straight to a PDF instead of the page list, so long scans don't fill memory
+    scan_to_pdf: bool,
+    /// File the running scan is being written to, in that mode
+    streaming_path: Option<PathBuf>,
 
     // UI Response references
     path_field: Option<Response>,
 
     // Threading resources
-    scan_thread_handle: Option<JoinHandle<()>>,
+    /// Ends with the error that stopped the scan, if any
+    scan_thread_handle: Option<JoinHandle<Option<ErrorReport>>>,
+    /// Scans in a row that ended in a read error, to detect a scanner stuck in a bad state
+    consecutive_scan_errors: u32,
     scan_cancelled: Arc<Mutex<bool>>,
+    scan_paused: Arc<Mutex<bool>>,
+    /// Updated by the reading thread as pages arrive and when pages are saved
+    scan_statistics: Arc<Mutex<ScanStatistics>>,
+    page_sender: Sender<DecodedPage>,
+    page_receiver: Receiver<DecodedPage>,
+    button_poller: Option<ButtonPoller>,
+    button_press_sender: Sender<()>,
+    button_press_receiver: Receiver<()>,
+    error_sender: Sender<ErrorReport>,
+    error_receiver: Receiver<ErrorReport>,
 
     // I/O state information
     root_location: Option<PathBuf>,
     file_save_path: String,
+    last_saved_paths: Vec<PathBuf>,
+    /// Combined size of the files at `last_saved_paths` when they were saved
+    last_saved_size: u64,
+
+    // Persisted preferences
+    settings: Settings,
 }
 
 impl App {
     pub fn new(cc: &eframe::CreationContext<'_>, sane_instance: Sane) -> Self {
+        let (page_sender, page_receiver) = mpsc::channel();
+        let (button_press_sender, button_press_receiver) = mpsc::channel();
+        let (error_sender, error_receiver) = mpsc::channel();
+        let settings: Settings = cc.storage.and_then(|storage| eframe::get_value(storage, eframe::APP_KEY)).unwrap_or_default();
+        let defaults = settings.defaults;
+
         Self {
             scanner_list: Vec::default(),
             selected_scanner: Default::default(),
             prev_selected_scanner: Option::default(),
             selected_handle: Option::default(),
+            device_opener: Option::default(),
             config_options: Vec::default(),
             sane_instance,
-            ui_context: Arc::new(Mutex::new(cc.egui_ctx.clone())),
+            sane_backends: configured_backends(),
+            sensor_choices: Vec::default(),
+            selected_sensor: Default::default(),
+            button_scan_enabled: Default::default(),
+            resolution_option: Option::default(),
+            duplex_option: Option::default(),
+            color_mode_option: Option::default(),
+            scan_area_available: false,
+            scan_area_diagram: None,
+            button_task: Option::default(),
+            prescan_task: Option::default(),
+            prescan: Option::default(),
+            annotation_tool: AnnotationTool::Pen,
+            merge_options: MergeOptions::default(),
+            print_options: PrintOptions::default(),
+            printers: Vec::new(),
+            print_after_scan: false,
+            rescan_slot: None,
+            cancelled_scan_pages: None,
+            scan_to_pdf: false,
+            streaming_path: None,
+            ui_context: cc.egui_ctx.clone(),
             search_network: Default::default(),
+            no_devices_found: Default::default(),
             scan_status: ScanStatus::Stopped,
             image_max_x: 200.0,
+            preview_texture_width: preview_width(200.0),
+            thumbnail_zoomed_at: None,
+            scroll_to_page: None,
+            thumbnail_columns: None,
+            zoom_on_hover: true,
             pages_selected: Default::default(),
+            marquee_origin: None,
+            low_resolution_warned: false,
             dialog_status: DialogStatus::default(),
-            scanned_images: Arc::default(),
+            profile_name: String::new(),
+            config_scroll_target: None,
+            error_reports: Vec::default(),
+            common_vals_unit: LengthUnit::Millimeters,
+            scanned_images: Vec::default(),
+            staged_pages: Vec::default(),
             selected_page_indices: Vec::default(),
             show_saved_images: Default::default(),
+            pixel_adjustments: PixelAdjustments::default(),
+            page_split: PageSplit::for_paper(defaults.paper_size),
+            page_order: PageOrder::AsSelected,
+            output_mode: OutputMode::SingleFile,
+            output_format: defaults.output_format,
+            paper_size: defaults.paper_size,
+            output_dpi: defaults.output_dpi,
             path_field: Option::default(),
             scan_thread_handle: Option::default(),
+            consecutive_scan_errors: 0,
             scan_cancelled: Arc::default(),
+            scan_paused: Arc::default(),
+            scan_statistics: Arc::default(),
+            page_sender,
+            page_receiver,
+            button_poller: Option::default(),
+            button_press_sender,
+            button_press_receiver,
+            error_sender,
+            error_receiver,
             root_location: Option::default(),
             file_save_path: String::default(),
+            last_saved_paths: Vec::default(),
+            last_saved_size: 0,
+            settings,
         }
     }
 
     fn refresh_devices(&mut self) {
+        let text = self.settings.language.strings();
         self.scanner_list = match self.sane_instance.get_devices(!self.search_network) {
-            Ok(devices) => devices,
+            Ok(devices) => {
+                self.no_devices_found = devices.is_empty();
+                devices
+            },
             Err(error) => {
-                message_box_ok(ERR_DIALOG_TITLE, &format!("Error refreshing device list: {error}"), MessageBoxIcon::Warning);
+                self.no_devices_found = false;
+                self.report_error(text.error_refreshing_devices, &error);
                 vec![]
             },
         };
+        // Listed last, so it doesn't take the place of a real scanner that was selected
+        if self.settings.test_pattern_scanner {
+            self.scanner_list.push(test_pattern_device());
+            self.no_devices_found = false;
+        }
         self.open_selected_device();
     }
 
@@ -90,30 +254,373 @@ impl App {
             }
         }
 
-        // Open new scanner, updating previous field and closing configuration panel
+        // Open new scanner, updating previous field and closing the open one along with its windows
+        self.close_device();
         self.prev_selected_scanner = Some(self.selected_scanner);
+
+        // Replaces (and so abandons) any device that is still opening
+        self.device_opener = self.scanner_list.get(self.selected_scanner)
+            .map(|device| DeviceOpener::start(device.clone(), self.ui_context.clone()));
+    }
+
+    fn poll_device_opening(&mut self) {
+        let Some(opener) = &self.device_opener else {
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        match opener.poll() {
+            OpenProgress::Pending => return,
+            OpenProgress::Opened(handle) => {
+                self.selected_handle = Some(Arc::new(Mutex::new(handle)));
+                self.restore_device_scan_settings();
+                self.load_quick_controls();
+                self.apply_preferred_color_mode();
+                self.load_device_options();
+            },
+            OpenProgress::Failed(error) => {
+                self.prev_selected_scanner = None;
+                self.report_error(text.error_opening_device, &error);
+            },
+            OpenProgress::TimedOut => {
+                self.prev_selected_scanner = None;
+                message_box_ok(text.error_title, text.error_open_timeout, MessageBoxIcon::Warning);
+            },
+        }
+
+        self.device_opener = None;
+    }
+
+    fn cancel_device_opening(&mut self) {
+        self.device_opener = None;
+        // Allow the same scanner to be opened again by refreshing
+        self.prev_selected_scanner = None;
+    }
+
+    fn disconnect_device(&mut self) {
+        self.close_device();
+        // Allow the same scanner to be opened again by refreshing
+        self.prev_selected_scanner = None;
+    }
+
+    /// Closes the open device along with the windows and controls tied to it
+    fn close_device(&mut self) {
         self.dialog_status.config = false;
         self.dialog_status.common_vals = false;
+        self.stop_button_polling();
+        self.button_scan_enabled = false;
+        self.sensor_choices.clear();
+        self.config_options.clear();
+        self.resolution_option = None;
+        self.duplex_option = None;
+        self.color_mode_option = None;
+        self.scan_area_available = false;
+        self.scan_area_diagram = None;
+        // A running prescan holds its own reference to the handle
+        self.prescan_task = None;
+        self.prescan = None;
 
-        if let Some(device) = self.scanner_list.get(self.selected_scanner) {
-            self.selected_handle = match device.open() {
-                Ok(handle) => Some(Arc::new(Mutex::new(ThDeviceHandle { handle }))),
-                Err(error) => {
-                    message_box_ok(ERR_DIALOG_TITLE, &format!("Failed to open device: {error}"), MessageBoxIcon::Error);
-                    None
-                },
-            };
+        // Dropping the last reference to the handle closes the device
+        self.selected_handle = None;
+    }
+
+    fn open_config_window(&mut self) {
+        if self.dialog_status.config {
+            return;
+        }
+        self.dialog_status.config = true;
+
+        // Start from the device's values rather than edits left from last time
+        self.config_options.clear();
+        self.load_device_options();
+    }
+
+    /// Finds the options behind the resolution, duplex, color mode and scan area controls in the top panel
+    fn load_quick_controls(&mut self) {
+        self.resolution_option = None;
+        self.duplex_option = None;
+        self.color_mode_option = None;
+        self.scan_area_available = false;
+        self.scan_area_diagram = None;
+
+        let Some(handle) = &self.selected_handle else {
+            return;
+        };
+        let mut device = handle.lock().unwrap();
+
+        // A missing option just means there's no quick control for it, so errors are only logged
+        let options = match device.handle.get_options() {
+            Ok(options) => options,
+            Err(error) => {
+                println!("Failed to retrieve options for quick controls: {error}");
+                return;
+            },
+        };
+
+        self.scan_area_available = ScanArea::find(options.clone()).is_some();
+
+        let mut mode_option = None;
+        let mut depth_option = None;
+        for option in options {
+            if is_mode_option(&option) {
+                mode_option = Some(option);
+            } else if is_depth_option(&option) {
+                depth_option = Some(option);
+            } else if is_resolution_option(&option) {
+                if self.resolution_option.is_none() {
+                    match device.handle.get_option(&option) {
+                        Ok(value) => self.resolution_option = ResolutionOption::new(option, &value),
+                        Err(error) => println!("Failed to read resolution: {error}"),
+                    }
+                }
+            } else if self.duplex_option.is_none() {
+                // Reading every option's value would be slow on some backends, so only likely candidates are read
+                if !DuplexOption::may_control_duplex(&option) {
+                    continue;
+                }
+                match device.handle.get_option(&option) {
+                    Ok(value) => self.duplex_option = DuplexOption::new(option, &value),
+                    Err(error) => println!("Failed to read duplex setting: {error}"),
+                }
+            }
+        }
+
+        let Some(mode_option) = mode_option else {
+            return;
+        };
+        let depth = depth_option.and_then(|option| match device.handle.get_option(&option) {
+            Ok(value) => Some((option, value)),
+            Err(error) => {
+                println!("Failed to read bit depth: {error}");
+                None
+            },
+        });
+        match device.handle.get_option(&mode_option) {
+            Ok(value) => self.color_mode_option = ColorModeOption::new(mode_option, &value, depth.as_ref().map(|(option, value)| (option, value))),
+            Err(error) => println!("Failed to read color mode: {error}"),
+        }
+    }
+
+    /// Applies the resolution and source last scanned with on the selected device, if remembered
+    fn restore_device_scan_settings(&mut self) {
+        if !self.settings.remember_device_settings {
+            return;
+        }
+        let (Some(handle), Some(name)) = (&self.selected_handle, self.selected_device_name()) else {
+            return;
+        };
+        let Some(remembered) = self.settings.device_scan_settings.get(&name) else {
+            return;
+        };
+
+        // The device works with its current settings regardless, so failures are only logged
+        if let Err(error) = restore_scan_settings(handle.lock().unwrap().handle.as_mut(), remembered.resolution, remembered.source.as_deref()) {
+            println!("Failed to restore the remembered resolution and source: {error}");
+        }
+    }
+
+    /// Remembers the selected device's current resolution and source for the next time it is opened
+    fn remember_device_scan_settings(&mut self) {
+        if !self.settings.remember_device_settings {
+            return;
+        }
+        let Some(name) = self.selected_device_name() else {
+            return;
+        };
+
+        let remembered = DeviceScanSettings {
+            resolution: self.resolution_option.as_ref().map(|resolution| resolution.current),
+            source: self.config_options.iter()
+                .find(|option| is_source_option(&option.base_option) && !option.base_option.cap.contains(OptionCapability::INACTIVE))
+                .and_then(EditingDeviceOption::value_text),
+        };
+        if remembered != DeviceScanSettings::default() {
+            self.settings.device_scan_settings.insert(name, remembered);
+        }
+    }
+
+    /// Saves the open device's settable options and the output settings under `name`, replacing any profile of that name
+    fn save_profile(&mut self, name: &str) {
+        let profile = ScanProfile {
+            name: String::from(name),
+            device_options: self.config_options.iter()
+                .filter_map(|option| Some((cstring_to_string(&option.base_option.name, "option name"), option.profile_value()?.clone())))
+                .collect(),
+            paper_size: self.paper_size,
+            output_dpi: self.output_dpi,
+            output_format: self.output_format,
+            pixel_adjustments: self.pixel_adjustments,
+            pdf_compression: self.settings.pdf_compression,
+            export_image_format: self.settings.export_image_format,
+        };
+
+        match self.settings.scan_profiles.iter_mut().find(|existing| existing.name == name) {
+            Some(existing) => *existing = profile,
+            None => self.settings.scan_profiles.push(profile),
+        }
+    }
+
+    fn apply_profile(&mut self, profile: &ScanProfile) {
+        self.paper_size = profile.paper_size;
+        self.output_dpi = profile.output_dpi;
+        self.output_format = profile.output_format;
+        self.settings.pdf_compression = profile.pdf_compression;
+        self.settings.export_image_format = profile.export_image_format;
+        if self.pixel_adjustments != profile.pixel_adjustments {
+            self.pixel_adjustments = profile.pixel_adjustments;
+            self.refresh_all_textures();
+        }
+
+        if self.selected_handle.is_none() || self.scan_status != ScanStatus::Stopped || self.device_busy() {
+            return;
+        }
+        // A second pass sets options that only become active with the values of others, such as bit depth with the mode
+        for _ in 0..2 {
+            let mut staged = false;
+            for option in &mut self.config_options {
+                let name = cstring_to_string(&option.base_option.name, "option name");
+                if let Some((_, value)) = profile.device_options.iter().find(|(option_name, _)| *option_name == name) {
+                    staged |= option.stage_profile_value(value);
+                }
+            }
+            if !staged {
+                break;
+            }
+            self.apply_config_changes();
+        }
+    }
+
+    /// Switches a newly opened device to the preferred color mode, if it offers it
+    fn apply_preferred_color_mode(&mut self) {
+        let (Some(handle), Some(color_mode), Some(preferred)) = (&self.selected_handle, &self.color_mode_option, self.settings.defaults.color_mode) else {
+            return;
+        };
+        if color_mode.current == Some(preferred) || !color_mode.formats().any(|format| format == preferred) {
+            return;
+        }
+
+        // The device works in its own default mode regardless, so failures are only logged
+        if let Err(error) = color_mode.apply(handle.lock().unwrap().handle.as_mut(), preferred) {
+            println!("Failed to apply the preferred color mode: {error}");
+        }
+        self.load_quick_controls();
+    }
+
+    fn apply_resolution(&mut self, dpi: i32) {
+        let (Some(handle), Some(resolution)) = (&self.selected_handle, &self.resolution_option) else {
+            return;
+        };
+
+        if let Err(error) = handle.lock().unwrap().handle.set_option(&resolution.option, resolution.value_for(dpi)) {
+            let text = self.settings.language.strings();
+            self.report_error(text.error_setting_resolution, &error);
+        }
+
+        // The device may adjust the value or other options in response
+        self.load_quick_controls();
+        self.load_device_options();
+    }
+
+    fn apply_duplex(&mut self, enabled: bool) {
+        let (Some(handle), Some(duplex)) = (&self.selected_handle, &self.duplex_option) else {
+            return;
+        };
+
+        if let Err(error) = handle.lock().unwrap().handle.set_option(&duplex.option, duplex.value_for(enabled)) {
+            let text = self.settings.language.strings();
+            self.report_error(text.error_setting_duplex, &error);
+        }
+
+        // Switching the source often changes the scan area and other options
+        self.load_quick_controls();
+        self.load_device_options();
+    }
+
+    fn apply_color_mode(&mut self, format: PageFormat) {
+        let (Some(handle), Some(color_mode)) = (&self.selected_handle, &self.color_mode_option) else {
+            return;
+        };
+
+        if let Err(error) = color_mode.apply(handle.lock().unwrap().handle.as_mut(), format) {
+            let text = self.settings.language.strings();
+            self.report_error(text.error_setting_color_mode, &error);
+        }
+
+        // The mode decides which depths and other options are available
+        self.load_quick_controls();
+        self.load_device_options();
+    }
+
+    fn load_sensor_choices(&mut self) {
+        let text = self.settings.language.strings();
+        self.sensor_choices.clear();
+        self.selected_sensor = 0;
+
+        if let Some(handle) = &self.selected_handle {
+            match handle.lock().unwrap().handle.get_options() {
+                Ok(options) => self.sensor_choices = options.iter().enumerate()
+                    .filter(|(_, option)| is_sensor_option(option))
+                    .map(|(i, option)| (i, cstring_to_string(&option.title, "option title")))
+                    .collect(),
+                Err(error) => self.report_error(text.error_retrieving_options, &error),
+            }
+        }
+    }
+
+    fn start_button_polling(&mut self) {
+        self.stop_button_polling();
+        let text = self.settings.language.strings();
+
+        let (Some(handle), Some((option_position, _))) = (&self.selected_handle, self.sensor_choices.get(self.selected_sensor)) else {
+            return;
+        };
+
+        let sensor = match handle.lock().unwrap().handle.get_options() {
+            Ok(options) => options.into_iter().nth(*option_position),
+            Err(error) => {
+                self.report_error(text.error_retrieving_options, &error);
+                None
+            },
+        };
+
+        if let Some(sensor) = sensor {
+            self.button_poller = Some(ButtonPoller::start(handle.clone(), sensor, self.ui_context.clone(), self.button_press_sender.clone()));
+        }
+    }
+
+    fn stop_button_polling(&mut self) {
+        if let Some(poller) = self.button_poller.take() {
+            poller.stop();
+        }
+    }
+
+    fn handle_button_presses(&mut self) {
+        if let Some(poller) = &self.button_poller {
+            poller.set_paused(self.scan_status != ScanStatus::Stopped);
+        }
+
+        let mut pressed = false;
+        while self.button_press_receiver.try_recv().is_ok() {
+            pressed = true;
+        }
+
+        if pressed && self.button_scan_enabled && self.scan_status == ScanStatus::Stopped && !self.device_busy() {
+            self.start_scan();
         }
     }
 
+    /// Reloads option values from the device. Options still being edited keep their unapplied values.
     fn load_device_options(&mut self) {
-        self.config_options.clear();
+        let mut previous_options: HashMap<i32, EditingDeviceOption> = self.config_options.drain(..)
+            .map(|option| (option.base_option.option_idx, option))
+            .collect();
+        let text = self.settings.language.strings();
 
         if let Some(handle) = &self.selected_handle {
             let device_options = match handle.lock().unwrap().handle.get_options() {
                 Ok(options) => options,
                 Err(error) => {
-                    message_box_ok(ERR_DIALOG_TITLE, &format!("Failed to retrieve options: {error}"), MessageBoxIcon::Warning);
+                    self.report_error(text.error_retrieving_options, &error);
                     vec![]
                 },
             };
@@ -125,414 +632,3253 @@ impl App {
                     _ => {
                         match handle.lock().unwrap().handle.get_option(&option) {
                             Ok(opt) => opt,
-                            Err(error) => DeviceOptionValue::String(string_to_cstring("ERROR: ".to_owned() + &error.to_string())),
+                            Err(error) => {
+                                self.config_options.push(EditingDeviceOption::with_load_error(option, error.to_string()));
+                                continue;
+                            },
                         }
                     },
                 };
-                self.config_options.push(EditingDeviceOption::new(option, option_value));
+
+                let mut editing_option = EditingDeviceOption::new(option, option_value);
+                if let Some(previous) = previous_options.remove(&editing_option.base_option.option_idx) {
+                    editing_option.keep_edits_from(previous);
+                }
+                self.config_options.push(editing_option);
             }
         }
     }
 
     fn apply_config_changes(&mut self) {
+        let text = self.settings.language.strings();
         if let Some(handle) = &self.selected_handle {
-            for option in &mut self.config_options {
+            let previously_inactive: HashMap<i32, bool> = self.config_options.iter()
+                .map(|option| (option.base_option.option_idx, option.base_option.cap.contains(OptionCapability::INACTIVE)))
+                .collect();
+
+            let mut long_running_buttons = Vec::new();
+            // Edits that failed to apply stay pending through the reload below so they can be corrected
+            let mut applied = Vec::new();
+            for option in &self.config_options {
                 if !option.is_edited {
                     continue;
                 }
 
-                if let EditingDeviceOptionValue::Button = option.editing_value {
-                    if let Err(error) = handle.lock().unwrap().handle.set_option_auto(&option.base_option) {
-                        message_box_ok(ERR_DIALOG_TITLE, &format!("Error applying configuration: {error}"), MessageBoxIcon::Error);
+                if is_long_running_button(&option.base_option) {
+                    long_running_buttons.push(option.base_option.option_idx);
+                    applied.push(option.base_option.option_idx);
+                } else if option.set_auto || matches!(option.editing_value, EditingDeviceOptionValue::Button) {
+                    match handle.lock().unwrap().handle.set_option_auto(&option.base_option) {
+                        Ok(()) => applied.push(option.base_option.option_idx),
+                        Err(error) => self.report_error(text.error_applying_config, &error),
                     }
                 } else if let Ok(opt_val) = TryInto::<DeviceOptionValue>::try_into(&option.editing_value) {
-                    if let Err(error) = handle.lock().unwrap().handle.set_option(&option.base_option, opt_val) {
-                        message_box_ok(ERR_DIALOG_TITLE, &format!("Error applying configuration: {error}"), MessageBoxIcon::Error);
+                    match handle.lock().unwrap().handle.set_option(&option.base_option, opt_val) {
+                        Ok(()) => applied.push(option.base_option.option_idx),
+                        Err(error) => self.report_error(text.error_applying_config, &error),
                     }
                 } else {
-                    message_box_ok(ERR_DIALOG_TITLE, "Error converting from editor value", MessageBoxIcon::Error);
+                    message_box_ok(text.error_title, text.error_converting_value, MessageBoxIcon::Error);
                 }
             }
 
+            for option in &mut self.config_options {
+                if applied.contains(&option.base_option.option_idx) {
+                    option.is_edited = false;
+                }
+            }
             self.load_device_options();
+            self.load_quick_controls();
+
+            // Flag options that one of the applied changes activated or deactivated
+            for option in &mut self.config_options {
+                option.activity_changed = previously_inactive.get(&option.base_option.option_idx)
+                    .is_some_and(|was_inactive| *was_inactive != option.base_option.cap.contains(OptionCapability::INACTIVE));
+            }
+
+            // Slow buttons run last, in the background, since they hold the device until they finish
+            if !long_running_buttons.is_empty() {
+                if let Some(handle) = &self.selected_handle {
+                    self.button_task = Some(ButtonTask::start(handle.clone(), long_running_buttons, self.ui_context.clone()));
+                }
+            }
         } else {
-            message_box_ok(ERR_DIALOG_TITLE, "Not attached to a device handle!", MessageBoxIcon::Error);
+            message_box_ok(text.error_title, text.error_no_device_handle, MessageBoxIcon::Error);
         }
     }
 
-    fn start_scan(&mut self) {
-        if let Some(handle) = self.selected_handle.as_mut() {
-            self.scan_status = ScanStatus::Running;
-            if let Err(error) = handle.lock().unwrap().handle.start_scan() {
-                message_box_ok(ERR_DIALOG_TITLE, &format!("Error occurred while initiating scan: {error}"), MessageBoxIcon::Error);
-                self.scan_status = ScanStatus::Stopped;
-                return;
-            }
+    fn poll_button_task(&mut self) {
+        if !self.button_task.as_ref().is_some_and(ButtonTask::is_finished) {
+            return;
+        }
 
-            *self.scan_cancelled.lock().unwrap() = false;
-            self.start_reading_thread();
+        if let Some(Err(error)) = self.button_task.take().map(ButtonTask::finish) {
+            let text = self.settings.language.strings();
+            self.report_error(text.error_applying_config, &error);
         }
+
+        // The action may have changed other options
+        self.load_device_options();
+        self.load_quick_controls();
     }
 
-    fn start_reading_thread(&mut self) {
-        if let Some(handle) = &self.selected_handle {
-            let handle = handle.clone();
-            let image_buf = self.scanned_images.clone();
-            let ctx = self.ui_context.clone();
-            let interrupt = self.scan_cancelled.clone();
+    fn device_busy(&self) -> bool {
+        self.button_task.is_some() || self.prescan_task.is_some()
+    }
 
-            self.clear_selection();
-            self.scan_thread_handle = Some(thread::spawn(move || {
-                let mut queue_index: usize = 0;
-                image_buf.lock().unwrap().clear();
-
-                loop {
-                    let scanned_pixels = match handle.lock().unwrap().handle.read_to_vec() {
-                        Ok(image) => image,
-                        Err(error) => {
-                            message_box_ok(ERR_DIALOG_TITLE, &format!("Error reading image data: {error}"), MessageBoxIcon::Error);
-                            return
-                        },
-                    };
+    /// A rescanned page replaces the one at its index, so pages can't be added or removed until it arrives
+    fn pages_locked(&self) -> bool {
+        self.rescan_slot.is_some()
+    }
 
-                    let parameters = match handle.lock().unwrap().handle.get_parameters() {
-                        Ok(params) => params,
-                        Err(error) => {
-                            message_box_ok(ERR_DIALOG_TITLE, &format!("Error retrieving scan parameters: {error}"), MessageBoxIcon::Error);
-                            return
-                        },
-                    };
+    fn selected_device_name(&self) -> Option<String> {
+        self.scanner_list.get(self.selected_scanner).map(|device| cstring_to_string(&device.name, "device name"))
+    }
+
+    fn channel_order(&self) -> ChannelOrder {
+        match self.selected_device_name() {
+            Some(name) if self.settings.bgr_devices.contains(&name) => ChannelOrder::Bgr,
+            _ => ChannelOrder::Rgb,
+        }
+    }
 
-                    let bytes_per_line = TryInto::<usize>::try_into(parameters.bytes_per_line).expect("Failed to convert `bytes_per_line` to unsigned");
-                    let lines = scanned_pixels.len() / bytes_per_line;
+    fn set_channel_order(&mut self, order: ChannelOrder) {
+        let Some(name) = self.selected_device_name() else {
+            return;
+        };
 
-                    let pixels_per_line = match parameters.format {
-                        Frame::Rgb => bytes_per_line / 3,
-                        _ => bytes_per_line,
-                    };
+        self.settings.bgr_devices.retain(|device| *device != name);
+        if order == ChannelOrder::Bgr {
+            self.settings.bgr_devices.push(name);
+        }
+    }
 
-                    let pixels = match parameters.format {
-                        Frame::Rgb => scanned_pixels,
-                        _ => repeat_all_elements(scanned_pixels, 3),
-                    };
+    fn start_prescan(&mut self) {
+        if let Some(handle) = &self.selected_handle {
+            self.prescan_task = Some(PrescanTask::start(handle.clone(), self.channel_order(), self.ui_context.clone()));
+        }
+    }
 
-                    let pixels_with_alpha = insert_after_every(pixels.clone(), 3, 255);
+    fn poll_prescan_task(&mut self, ctx: &Context) {
+        if !self.prescan_task.as_ref().is_some_and(PrescanTask::is_finished) {
+            return;
+        }
+        let Some(task) = self.prescan_task.take() else {
+            return;
+        };
 
-                    let image = ColorImage::from_rgba_unmultiplied([pixels_per_line, lines], &pixels_with_alpha);
+        let text = self.settings.language.strings();
+        match task.finish() {
+            Ok(PrescanPage { page: RgbPage { size, pixels, .. }, previous_area }) => {
+                let image = build_preview_image(size, &pixels, &self.pixel_adjustments, size[0]);
+                self.prescan = Some(Prescan {
+                    texture_handle: ctx.load_texture("prescan", image, egui::TextureOptions::LINEAR),
+                    region: None,
+                    drag_origin: None,
+                    previous_area,
+                });
+            },
+            Err(PrescanError::NoScanArea) => message_box_ok(text.error_title, text.error_no_scan_area, MessageBoxIcon::Warning),
+            Err(PrescanError::Failed(error)) => self.report_error(text.error_prescan, &error),
+        }
 
-                    let scanned_image = ScanEntry {
-                        pixels,
-                        texture_handle: ctx.lock().unwrap().load_texture(queue_index.to_string(), image, egui::TextureOptions::LINEAR),
-                        selected_as_page: None,
-                        saved_to_file: false,
-                    };
+        // The prescan changed the scan area and briefly the resolution
+        self.load_device_options();
+        self.load_quick_controls();
+    }
 
-                    image_buf.lock().unwrap().push(scanned_image);
+    /// Sets the device's scan area to `region`, given as `[left, top, right, bottom]` fractions of the scan bed
+    fn apply_scan_area(&mut self, region: [f64; 4]) {
+        let Some(handle) = &self.selected_handle else {
+            return;
+        };
 
-                    ctx.lock().unwrap().request_repaint();
+        let result = (|| {
+            let mut device = handle.lock().unwrap();
+            let Some(area) = ScanArea::find(device.handle.get_options()?) else {
+                return Ok(());
+            };
+            for (option, value) in area.values_for(region) {
+                device.handle.set_option(option, value)?;
+            }
+            Ok::<(), sane_scan::Error>(())
+        })();
 
-                    queue_index += 1;
-                    if *interrupt.lock().unwrap() || handle.lock().unwrap().handle.start_scan().is_err() {
-                        break;
-                    }
-                }
-            }));
+        if let Err(error) = result {
+            let text = self.settings.language.strings();
+            self.report_error(text.error_setting_scan_area, &error);
+        }
+
+        self.load_device_options();
+        // The device may have rounded the area to positions it supports
+        if self.scan_area_diagram.is_some() {
+            self.load_scan_area_diagram();
         }
     }
-    fn stop_reading_thread(&mut self) {
-        *self.scan_cancelled.lock().unwrap() = true;
-        if let Some(handle) = self.scan_thread_handle.take() {
-            if let Err(error) = handle.join() {
-                message_box_ok(ERR_DIALOG_TITLE, "Error occurred while stopping scan (see console for details)", MessageBoxIcon::Error);
-                println!("Error occurred while stopping scan: {error:?}");
-            }
+
+    /// Opens or refreshes the scan area diagram with the device's bed size and current area
+    fn load_scan_area_diagram(&mut self) {
+        let Some(handle) = &self.selected_handle else {
+            return;
+        };
+
+        let result = (|| {
+            let mut device = handle.lock().unwrap();
+            let Some(area) = ScanArea::find(device.handle.get_options()?) else {
+                return Ok(None);
+            };
+            Ok::<_, sane_scan::Error>(area.current_region(device.handle.as_mut())?.map(|region| (area.bed_size(), region)))
+        })();
+
+        match result {
+            #[allow(clippy::cast_possible_truncation)]
+            Ok(Some(((width, height), [left, top, right, bottom]))) => self.scan_area_diagram = Some(ScanAreaDiagram {
+                bed_size: Vec2::new(width as f32, height as f32),
+                region: Rect::from_min_max(Pos2::new(left as f32, top as f32), Pos2::new(right as f32, bottom as f32)),
+                drag: None,
+            }),
+            Ok(None) => self.scan_area_diagram = None,
+            Err(error) => {
+                self.scan_area_diagram = None;
+                let text = self.settings.language.strings();
+                self.report_error(text.error_reading_scan_area, &error);
+            },
         }
     }
 
-    fn cancel_scan(&mut self) {
-        self.stop_reading_thread();
-        self.scan_status = ScanStatus::Stopped;
+    fn scan_in_progress(&self) -> bool {
+        self.scan_thread_handle.as_ref().is_some_and(|handle| !handle.is_finished())
     }
 
-    fn clear_selection_from(&mut self, index: usize) {
-        for n in (index..self.selected_page_indices.len()).rev() {
-            self.scanned_images.lock().unwrap()[self.selected_page_indices[n]]
-                .selected_as_page = None;
-            self.selected_page_indices.pop();
+    /// Warns (once per session) if the device is set below the OCR resolution threshold. Returns whether to go ahead.
+    fn confirm_low_resolution(&mut self) -> bool {
+        if !self.settings.warn_low_resolution || self.low_resolution_warned {
+            return true;
         }
 
-        self.pages_selected = index;
-    }
+        let current_dpi = self.config_options.iter().find_map(EditingDeviceOption::resolution_dpi)
+            .or(self.resolution_option.as_ref().map(|resolution| resolution.current));
+        let Some(dpi) = current_dpi.filter(|dpi| *dpi < self.settings.low_resolution_threshold) else {
+            return true;
+        };
 
-    fn clear_selection(&mut self) {
-        self.clear_selection_from(0);
+        self.low_resolution_warned = true;
+        let text = self.settings.language.strings();
+        let prompt = fill(&fill(text.low_resolution_prompt, dpi), self.settings.low_resolution_threshold);
+        matches!(message_box_yes_no(text.low_resolution_title, &prompt, MessageBoxIcon::Warning, YesNo::Yes), YesNo::Yes)
     }
 
-    fn mark_selection_saved(&mut self) {
-        for n in (0..self.selected_page_indices.len()).rev() {
-            self.scanned_images.lock().unwrap()[self.selected_page_indices[n]]
-                .saved_to_file = true;
+    /// Warns if each page would come to more than the size limit, offering to lower the resolution to the highest choice within it.
+    /// Returns whether to go ahead.
+    fn confirm_large_scan(&mut self) -> bool {
+        if !self.settings.warn_large_scans {
+            return true;
+        }
+        let limit = u64::from(self.settings.large_scan_limit_mb) * 1024 * 1024;
+        let Some(estimate) = ScanSizeEstimate::from_options(&self.config_options).filter(|estimate| estimate.bytes() > limit) else {
+            return true;
+        };
+
+        let text = self.settings.language.strings();
+        let prompt = fill(&fill(&fill(text.large_scan_prompt, estimate.dpi), format_byte_size(estimate.bytes())), format_byte_size(limit));
+        let lower_dpi = self.resolution_option.as_ref().and_then(|resolution| resolution.choices.iter().copied()
+            .filter(|&dpi| dpi < estimate.dpi && estimate.bytes_at(dpi) <= limit)
+            .max());
+
+        let Some(lower_dpi) = lower_dpi else {
+            return matches!(message_box_yes_no(text.large_scan_title, &format!("{prompt}\n\n{}", text.large_scan_anyway),
+                MessageBoxIcon::Warning, YesNo::No), YesNo::Yes);
+        };
+        let lower_prompt = fill(&fill(text.large_scan_lower, lower_dpi), format_byte_size(estimate.bytes_at(lower_dpi)));
+        if let YesNo::Yes = message_box_yes_no(text.large_scan_title, &format!("{prompt}\n\n{lower_prompt}"), MessageBoxIcon::Warning, YesNo::Yes) {
+            self.apply_resolution(lower_dpi);
         }
+
+        true
     }
 
-    fn write_pdf(&mut self) -> Result<SaveStatus, Box<dyn std::error::Error>> {
-        if self.selected_page_indices.is_empty() {
-            return Err("No pages selected".to_owned().into());
+    fn start_scan(&mut self) {
+        // Ignore re-entry (e.g. a fast double-click) while the previous reading thread is still alive
+        if self.scan_in_progress() {
+            return;
         }
+        self.reap_scan_thread();
 
-        if let Some(root_path) = &self.root_location {
-            let file_path = if self.file_save_path.trim().is_empty() { DEFAULT_FILE_NAME } else { &(self.file_save_path.clone() + ".pdf") };
-            let saving_path = root_path.join(file_path);
+        if !self.confirm_low_resolution() || !self.confirm_large_scan() {
+            return;
+        }
+        self.remember_device_scan_settings();
 
-            if let Some(p) = saving_path.parent() {
-                if !p.exists() {
-                    if let YesNo::No = message_box_yes_no("Create directory?", &format!("The location {} does not exist. Create it?", p.to_string_lossy()), MessageBoxIcon::Question, YesNo::Yes) {
-                        return Ok(SaveStatus::Cancelled);
-                    }
-                    fs::create_dir_all(p)?;
-                }
-            };
+        // A rescan replaces a page in the list, so it never goes straight to a file
+        let stream = if self.scan_to_pdf && self.rescan_slot.is_none() {
+            match self.create_streaming_pdf() {
+                Ok(Some(stream)) => Some(stream),
+                Ok(None) => return,
+                Err(error) => {
+                    let text = self.settings.language.strings();
+                    self.report_error(text.error_saving_pdf, &error);
+                    return;
+                },
+            }
+        } else {
+            None
+        };
 
-            if saving_path.exists() {
-                if let YesNo::No = message_box_yes_no("Overwrite file?", "A file with that name already exists. Overwrite?", MessageBoxIcon::Question, YesNo::No) {
-                    return Ok(SaveStatus::Cancelled);
-                }
-            }
-
-            let doc = PdfDocument::empty("");
-
-            for i in &self.selected_page_indices {
-                let (new_page, new_layer) = doc.add_page(Mm(LETTER_WIDTH_MM), Mm(LETTER_HEIGHT_MM), "Layer 1");
-                let current_layer = doc.get_page(new_page).get_layer(new_layer);
-    
-                let images_mutex = self.scanned_images.lock().unwrap();
-                let scanned_image = images_mutex.get(*i).ok_or("Page index exceeded size of image vector")?;
-    
-                let image = Image::from(ImageXObject {
-                    width: Px(scanned_image.texture_handle.size()[0]),
-                    height: Px(scanned_image.texture_handle.size()[1]),
-                    color_space: ColorSpace::Rgb,
-                    bits_per_component: ColorBits::Bit8,
-                    interpolate: true,
-                    image_data: scanned_image.pixels.clone(),
-                    image_filter: None,
-                    clipping_bbox: None,
-                    smask: None,
-                });
-    
-                #[allow(clippy::cast_precision_loss)]
-                let inches_unscaled_x = scanned_image.texture_handle.size()[0] as f32 / 300.0;
-                #[allow(clippy::cast_precision_loss)]
-                let inches_unscaled_y = scanned_image.texture_handle.size()[1] as f32 / 300.0;
-    
-                let scale_factor_x = LETTER_WIDTH_IN / inches_unscaled_x;
-                let scale_factor_y = LETTER_HEIGHT_IN / inches_unscaled_y;
-    
-                image.add_to_layer(current_layer, ImageTransform {
-                    translate_x: None,
-                    translate_y: None,
-                    rotate: None,
-                    scale_x: Some(scale_factor_x),
-                    scale_y: Some(scale_factor_y),
-                    dpi: None,
-                });
+        if let Some(handle) = &self.selected_handle {
+            self.scan_status = ScanStatus::Running;
+            if let Err(error) = handle.lock().unwrap().handle.start_scan() {
+                let text = self.settings.language.strings();
+                self.report_error(text.error_starting_scan, &error);
+                self.scan_status = ScanStatus::Stopped;
+                if let Some(path) = self.streaming_path.take() {
+                    let _ = fs::remove_file(path);
+                }
+                return;
             }
 
-            doc.save(&mut BufWriter::new(File::create(saving_path)?))?;
+            *self.scan_cancelled.lock().unwrap() = false;
+            *self.scan_paused.lock().unwrap() = false;
+            self.cancelled_scan_pages = None;
+            self.start_reading_thread(stream);
 
-            Ok(SaveStatus::Completed)
-        } else {
-            Err("No root save location selected".to_owned().into())
+            if self.settings.background_scanning {
+                self.ui_context.send_viewport_cmd(egui::ViewportCommand::Minimized(true));
+            }
         }
     }
 
-    fn draw_top_panel(&mut self, ctx: &Context) {
-        egui::TopBottomPanel::top("MainUI-TopPanel").show(ctx, |ui| {
-            ui.horizontal_wrapped(|ui| {
-                if ui.button("↻").on_hover_text_at_pointer("Refresh the device list").clicked() {
-                    self.refresh_devices();
-                };
+    /// Creates the file for a scan written straight to PDF where pages would be saved, handling a missing directory
+    /// or an existing file as saving does. `None` if the user declined either.
+    fn create_streaming_pdf(&mut self) -> Result<Option<StreamingPdf>, Box<dyn std::error::Error>> {
+        let saving_path = self.saving_path()?;
+        if !self.create_save_directory(&saving_path, false)? {
+            return Ok(None);
+        }
 
-                ui.checkbox(&mut self.search_network, "Search the network for devices");
+        let path = if saving_path.exists() {
+            match self.existing_file_handling(false) {
+                None => return Ok(None),
+                Some(OverwriteBehavior::AppendSuffix) => next_free_path(&saving_path),
+                Some(_) => saving_path,
+            }
+        } else {
+            saving_path
+        };
+
+        let stream = StreamingPdf::create(&path, self.settings.pdf_compression)?;
+        self.streaming_path = Some(path);
+        Ok(Some(stream))
+    }
+
+    /// Reads pages until the scan ends, sending them to the page list, or writing them to `stream` if one is given
+    fn start_reading_thread(&mut self, mut stream: Option<StreamingPdf>) {
+        if let Some(handle) = &self.selected_handle {
+            let handle = handle.clone();
+            let page_sender = self.page_sender.clone();
+            let ctx = self.ui_context.clone();
+            let interrupt = self.scan_cancelled.clone();
+            let pause = self.scan_paused.clone();
+            let statistics = self.scan_statistics.clone();
+            let adjustments = self.pixel_adjustments;
+            let preview_width = self.preview_texture_width;
+            let channel_order = self.channel_order();
+            let feed_retries = self.settings.feed_retries;
+            let feed_retry_delay = Duration::from_millis(self.settings.feed_retry_delay_ms);
+            let text = self.settings.language.strings();
+            let single_page = self.rescan_slot.is_some();
+            let output_dpi = self.output_dpi;
+            let paper = self.paper_size;
+            let smoothing = self.settings.image_smoothing;
+            let margins = self.settings.page_margins;
+            let notify = self.settings.background_scanning;
+            let stream_path = self.streaming_path.clone();
+
+            // A rescan keeps the other pages, since it only replaces one of them
+            if !single_page {
+                self.clear_selection();
+                self.scanned_images.clear();
+                self.close_page_dialogs();
+            }
+            // Owned by the thread, so the icon leaves the tray as soon as the scan ends even if the UI isn't updating
+            let tray_icon = if notify {
+                TrayIcon::show(ctx.clone()).map_err(|error| println!("Failed to show a tray icon: {error}")).ok()
+            } else {
+                None
+            };
+            self.scan_thread_handle = Some(thread::spawn(move || {
+                let mut pages_read = 0;
+                // Errors are returned rather than sent, so the UI thread can tell how often they recur
+                let error = loop {
+                    let page_started = Instant::now();
+                    let read_result = read_shared_rgb_page(&handle, channel_order, &interrupt);
+                    // The format is kept per page, since some scanners change it partway through a feed
+                    let RgbPage { size, pixels, format, .. } = match read_result {
+                        // A page cut short by a cancel is dropped, so every page kept is whole
+                        Ok(page) if !page.complete && *interrupt.lock().unwrap() => break None,
+                        Ok(page) => page,
+                        // A cancelled read fails by design, so there's nothing to report
+                        Err(_) if *interrupt.lock().unwrap() => break None,
+                        Err(PageReadError::Reading(error)) => break Some(ErrorReport::new(text.error_reading_image, &error)),
+                        Err(PageReadError::Parameters(error)) => break Some(ErrorReport::new(text.error_scan_parameters, &error)),
+                    };
+
+                    if let Some(stream) = &mut stream {
+                        // The page is written as it would be saved, then dropped
+                        let pixels = apply_adjustments(&pixels, size, &adjustments);
+                        let written = match downsample_scale(output_dpi, size, paper) {
+                            Some(scale) => downsample(&pixels, size, scale)
+                                .and_then(|(size, pixels)| stream.add_page(&pixels, size, format, paper, margins, smoothing)),
+                            None => stream.add_page(&pixels, size, format, paper, margins, smoothing),
+                        };
+                        if let Err(error) = written {
+                            break Some(ErrorReport::new(text.error_saving_pdf, &error));
+                        }
+
+                        let mut statistics = statistics.lock().unwrap();
+                        statistics.record_page(page_started.elapsed());
+                        statistics.pages_saved += 1;
+                    } else {
+                        let image = build_preview_image(size, &pixels, &adjustments, preview_width);
+                        statistics.lock().unwrap().record_page(page_started.elapsed());
+
+                        // Textures are created on the UI thread once the page is received
+                        if page_sender.send(DecodedPage { size, pixels, image, adjustments, preview_width, format }).is_err() {
+                            break None;
+                        }
+                    }
+
+                    pages_read += 1;
+                    ctx.request_repaint();
+
+                    // Ends the batch so a feeder doesn't keep the rest of its pages waiting
+                    if single_page {
+                        handle.lock().unwrap().handle.cancel();
+                        break None;
+                    }
+
+                    wait_while_paused(&pause, &interrupt);
+                    if !start_next_page(&handle, feed_retries, feed_retry_delay, &interrupt) {
+                        break None;
+                    }
+                };
+
+                // The pages written before an error are still completed into a readable file
+                let finished = match stream {
+                    Some(stream) if pages_read > 0 => stream.finish(),
+                    // Like a scan that fails to start, one that wrote no pages leaves no file behind
+                    Some(stream) => {
+                        drop(stream);
+                        if let Some(path) = &stream_path {
+                            let _ = fs::remove_file(path);
+                        }
+                        Ok(())
+                    },
+                    None => Ok(()),
+                };
+
+                let error = match finished {
+                    Err(finish_error) if error.is_none() => Some(ErrorReport::new(text.error_saving_pdf, &finish_error)),
+                    _ => error,
+                };
+
+                // Sent from here since the UI doesn't update while minimized. A cancelled scan has nothing to announce.
+                if notify && !*interrupt.lock().unwrap() {
+                    match &error {
+                        Some(report) => send_notification(text.notify_scan_error, &report.summary),
+                        None => send_notification(text.notify_scan_finished, &fill(text.notify_scan_pages, pages_read)),
+                    }
+                }
+
+                if notify {
+                    drop(tray_icon);
+                    restore_window(&ctx);
+                }
+
+                // Wake the UI so it notices the thread has finished
+                ctx.request_repaint();
+                error
+            }));
+        }
+    }
+
+    fn receive_decoded_pages(&mut self, ctx: &Context) {
+        while let Ok(page) = self.page_receiver.try_recv() {
+            let stale_preview = page.adjustments != self.pixel_adjustments || page.preview_width != self.preview_texture_width;
+            let slot = self.rescan_slot.filter(|&slot| slot < self.scanned_images.len());
+            let texture_name = slot.map_or_else(|| self.scanned_images.len().to_string(), |slot| format!("rescan-{slot}"));
+            let mut entry = ScanEntry::new(ctx, texture_name, page);
+
+            // Adjustments or the preview size may have changed while this page was being decoded
+            if stale_preview {
+                if let Err(error) = entry.refresh_texture(&self.pixel_adjustments, self.preview_texture_width) {
+                    println!("Failed to refresh page preview: {error}");
+                }
+            }
+            if self.settings.spill_idle_pages {
+                if let Err(error) = entry.spill_to_disk() {
+                    println!("Failed to move page data to disk: {error}");
+                }
+            }
+
+            // Edits made to the old page don't fit the new one, but its note and place in the selection still apply
+            if let Some(slot) = slot {
+                let replaced = &self.scanned_images[slot];
+                entry.selected_as_page = replaced.selected_as_page;
+                entry.note = replaced.note.clone();
+                self.scanned_images[slot] = entry;
+                self.close_page_dialogs();
+            } else {
+                self.scanned_images.push(entry);
+            }
+        }
+    }
+
+    fn import_images(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let Some(paths) = open_file_dialog_multi(text.import_title, "", Some((&["*.png", "*.jpg", "*.jpeg", "*.tif", "*.tiff"], text.image_files))) else {
+            return;
+        };
+
+        let mut failures = Vec::new();
+        for path in paths {
+            match decode_image_file(&PathBuf::from(&path)) {
+                Ok((size, pixels)) => {
+                    let image = build_preview_image(size, &pixels, &self.pixel_adjustments, self.preview_texture_width);
+                    let texture_name = self.scanned_images.len().to_string();
+                    self.scanned_images.push(ScanEntry::new(ctx, texture_name, DecodedPage {
+                        size, pixels, image, adjustments: self.pixel_adjustments, preview_width: self.preview_texture_width, format: PageFormat::Color,
+                    }));
+                },
+                Err(error) => failures.push(format!("{path}: {error}")),
+            }
+        }
+
+        if !failures.is_empty() {
+            message_box_ok(text.error_title, &format!("{}\n{}", text.error_importing_images, failures.join("\n")), MessageBoxIcon::Warning);
+        }
+    }
+
+    fn save_session(&mut self) {
+        let text = self.settings.language.strings();
+        let Some(path) = save_file_dialog_with_filter(text.save_session_title, "session.json", &["*.json"], text.session_files) else {
+            return;
+        };
+
+        if let Err(error) = self.write_session(&PathBuf::from(path)) {
+            self.report_error(text.error_saving_session, &error);
+        }
+    }
+
+    fn write_session(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        let directory = page_directory(path);
+        fs::create_dir_all(&directory)?;
+
+        let pages = self.scanned_images.iter().enumerate()
+            .map(|(i, image)| SessionPage::from_entry(image, i, &directory))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Session::new(pages, self.selected_page_indices.clone(), self.pixel_adjustments, self.page_split,
+            self.page_order, self.root_location.clone(), self.file_save_path.clone()).save(path)
+    }
+
+    fn load_session(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let Some(path) = open_file_dialog(text.load_session_title, "", Some((&["*.json"], text.session_files))) else {
+            return;
+        };
+
+        if !self.scanned_images.is_empty() {
+            if let YesNo::No = message_box_yes_no(text.load_session_title, text.replace_pages_prompt, MessageBoxIcon::Question, YesNo::No) {
+                return;
+            }
+        }
+
+        if let Err(error) = self.read_session(ctx, &PathBuf::from(path)) {
+            self.report_error(text.error_loading_session, &error);
+        }
+    }
+
+    fn read_session(&mut self, ctx: &Context, path: &Path) -> Result<(), Box<dyn Error>> {
+        let session = Session::load(path)?;
+        let directory = page_directory(path);
+
+        // Decode every page before replacing anything, so a failed load leaves the current pages intact
+        let mut entries = Vec::with_capacity(session.pages.len());
+        for page in session.pages {
+            let (size, pixels) = decode_image_file(&directory.join(&page.image_file))?;
+            let image = build_preview_image(size, &pixels, &session.pixel_adjustments, self.preview_texture_width);
+            let mut entry = ScanEntry::new(ctx, entries.len().to_string(), DecodedPage {
+                size, pixels, image, adjustments: session.pixel_adjustments, preview_width: self.preview_texture_width, format: page.format,
+            });
+
+            if let Some(scanned_at) = page.scan_time() {
+                entry.scanned_at = scanned_at;
+            }
+            entry.saved_to_file = page.saved_to_file;
+            entry.note = page.note;
+            entry.page_breaks = page.page_breaks;
+            entry.dewarp = page.dewarp;
+            entry.annotations = page.annotations;
+            entry.flip = page.flip;
+            entry.id_card_layout = page.id_card_layout;
+            if self.settings.spill_idle_pages {
+                entry.spill_to_disk()?;
+            }
+            entries.push(entry);
+        }
+
+        self.clear_selection();
+        self.close_page_dialogs();
+        self.scanned_images = entries;
+
+        for i in session.selected_page_indices {
+            if let Some(image) = self.scanned_images.get_mut(i) {
+                if image.selected_as_page.is_none() {
+                    image.selected_as_page = Some(self.selected_page_indices.len());
+                    self.selected_page_indices.push(i);
+                }
+            }
+        }
+        self.pages_selected = self.selected_page_indices.len();
+
+        self.pixel_adjustments = session.pixel_adjustments;
+        self.page_split = session.page_split;
+        self.page_order = session.page_order;
+        self.root_location = session.root_location;
+        self.file_save_path = session.file_save_path;
+
+        Ok(())
+    }
+
+    fn refresh_all_textures(&mut self) {
+        let failures = self.scanned_images.iter_mut().chain(&mut self.staged_pages)
+            .filter_map(|image| image.refresh_texture(&self.pixel_adjustments, self.preview_texture_width).err())
+            .count();
+
+        if failures > 0 {
+            let text = self.settings.language.strings();
+            message_box_ok(text.error_title, &fill(text.error_refreshing_previews, failures), MessageBoxIcon::Warning);
+        }
+    }
+
+    fn apply_spill_setting(&mut self) {
+        let spill = self.settings.spill_idle_pages;
+        let result: io::Result<()> = self.scanned_images.iter_mut().chain(&mut self.staged_pages)
+            .try_for_each(|image| if spill { image.spill_to_disk() } else { image.load_into_memory() });
+
+        if let Err(error) = result {
+            let text = self.settings.language.strings();
+            self.report_error(text.error_moving_page_data, &error);
+        }
+    }
+
+    fn page_memory_usage(&self) -> (usize, usize) {
+        self.scanned_images.iter().chain(&self.staged_pages).fold((0, 0), |(memory, disk), image| {
+            if image.is_spilled() {
+                (memory, disk + image.size[0] * image.size[1] * 3)
+            } else {
+                (memory + image.resident_bytes(), disk)
+            }
+        })
+    }
+
+    fn reap_scan_thread(&mut self) {
+        if let Some(handle) = self.scan_thread_handle.take() {
+            match handle.join() {
+                Ok(error) => self.record_scan_result(error),
+                Err(error) => println!("Scan thread terminated abnormally: {error:?}"),
+            }
+
+            // Pages the thread sent belong to its batch, so they mustn't be left for the next scan to receive
+            let ctx = self.ui_context.clone();
+            self.receive_decoded_pages(&ctx);
+        }
+    }
+
+    /// Reports a scan's read error. After several scans in a row fail, the scanner is closed so that
+    /// scans started by its buttons stop too, and a single report offers to reopen it.
+    fn record_scan_result(&mut self, error: Option<ErrorReport>) {
+        let Some(report) = error else {
+            self.consecutive_scan_errors = 0;
+            return;
+        };
+
+        self.consecutive_scan_errors += 1;
+        if self.consecutive_scan_errors < MAX_CONSECUTIVE_SCAN_ERRORS {
+            let _ = self.error_sender.send(report);
+            return;
+        }
+
+        self.consecutive_scan_errors = 0;
+        self.disconnect_device();
+        let text = self.settings.language.strings();
+        let _ = self.error_sender.send(ErrorReport::device_closed(&fill(text.error_scanner_closed, MAX_CONSECUTIVE_SCAN_ERRORS), &report));
+    }
+
+    /// Opens the selected scanner again after it was closed
+    fn reset_device(&mut self) {
+        self.prev_selected_scanner = None;
+        self.open_selected_device();
+    }
+
+    fn poll_scan_completion(&mut self) {
+        if self.scan_status != ScanStatus::Stopped && self.scan_thread_handle.as_ref().is_some_and(JoinHandle::is_finished) {
+            if self.scan_status == ScanStatus::Cancelling {
+                self.finish_cancelled_scan();
+                return;
+            }
+
+            self.reap_scan_thread();
+            self.scan_status = ScanStatus::Stopped;
+
+            // The pages went straight to a file, so there are none in the list for the actions below
+            if let Some(path) = self.streaming_path.take() {
+                // A scan that wrote no pages removed its file
+                if path.exists() {
+                    self.set_last_saved_paths(vec![path.canonicalize().unwrap_or(path)]);
+                    if self.settings.copy_saved_path {
+                        let ctx = self.ui_context.clone();
+                        self.copy_saved_paths(&ctx);
+                    }
+                }
+                return;
+            }
+
+            // A rescan only replaced a page, so it isn't a new batch for the actions below
+            if self.rescan_slot.take().is_some() {
+                return;
+            }
+
+            // The print window keeps its own list of pages, so auto-saving clearing the selection doesn't affect it
+            if std::mem::take(&mut self.print_after_scan) {
+                self.select_finished_scan();
+                if !self.selected_page_indices.is_empty() {
+                    self.open_print_window();
+                }
+            }
+            if self.settings.auto_save_scans {
+                self.auto_save_scan();
+            }
+        }
+    }
+
+    /// Selects every unsaved page of the finished scan
+    fn select_finished_scan(&mut self) {
+        // The last page may have arrived after this frame's pages were received
+        let ctx = self.ui_context.clone();
+        self.receive_decoded_pages(&ctx);
+
+        self.clear_selection();
+        for i in 0..self.scanned_images.len() {
+            if !self.scanned_images[i].saved_to_file {
+                self.select_page(i);
+            }
+        }
+    }
+
+    /// Selects every unsaved page of the finished scan and saves it without prompting
+    fn auto_save_scan(&mut self) {
+        self.select_finished_scan();
+
+        if !self.selected_page_indices.is_empty() {
+            let ctx = self.ui_context.clone();
+            self.save_pdf(&ctx, PageSource::Selection, true);
+        }
+    }
+
+    /// Scans, then opens the print window for the scanned pages. Nothing is scanned if no printer is set up.
+    fn scan_to_print(&mut self) {
+        if available_printers().is_empty() {
+            let text = self.settings.language.strings();
+            message_box_ok(text.error_title, text.error_no_printer, MessageBoxIcon::Warning);
+            return;
+        }
+
+        self.start_scan();
+        self.print_after_scan = self.scan_status == ScanStatus::Running;
+    }
+
+    /// Opens the print window for the selected pages in output order, or explains that there is no printer to use
+    fn open_print_window(&mut self) {
+        self.printers = available_printers();
+        if self.printers.is_empty() {
+            let text = self.settings.language.strings();
+            message_box_ok(text.error_title, text.error_no_printer, MessageBoxIcon::Warning);
+            return;
+        }
+
+        if !self.printers.contains(&self.print_options.printer) {
+            self.print_options.printer = self.printers[0].clone();
+        }
+        self.dialog_status.print = Some(self.ordered_page_indices());
+    }
+
+    /// Prints the pages through a temporary PDF, written the same way as when saving
+    fn print_pages(&self, indices: &[usize]) -> Result<(), Box<dyn std::error::Error>> {
+        let pages: Vec<&ScanEntry> = indices.iter()
+            .map(|i| self.scanned_images.get(*i).ok_or("Page index exceeded size of image vector"))
+            .collect::<Result<_, _>>()?;
+
+        let path = std::env::temp_dir().join(format!("slickscan-print-{}.pdf", std::process::id()));
+        let result = self.write_document(&pages, &path)
+            .and_then(|()| print_file(&path, &self.print_options.printer, self.print_options.copies));
+        let _ = fs::remove_file(&path);
+
+        result
+    }
+
+    fn stop_reading_thread(&mut self) {
+        *self.scan_cancelled.lock().unwrap() = true;
+        if let Some(handle) = self.scan_thread_handle.take() {
+            match handle.join() {
+                Ok(error) => self.record_scan_result(error),
+                Err(error) => {
+                    let text = self.settings.language.strings();
+                    message_box_ok(text.error_title, text.error_stopping_scan, MessageBoxIcon::Error);
+                    println!("Error occurred while stopping scan: {error:?}");
+                },
+            }
+        }
+    }
+
+    /// Pausing takes effect once the page being read is complete, and leaves the device mid-scan so resuming continues the feed
+    fn set_scan_paused(&mut self, paused: bool) {
+        *self.scan_paused.lock().unwrap() = paused;
+        self.scan_status = if paused { ScanStatus::Paused } else { ScanStatus::Running };
+    }
+
+    /// Stops the scan without blocking the UI. A blocking read holds the handle until its chunk arrives,
+    /// in which case the reading thread cancels the device itself; `finish_cancelled_scan` runs once it stops.
+    fn cancel_scan(&mut self) {
+        *self.scan_cancelled.lock().unwrap() = true;
+        self.print_after_scan = false;
+        if let (Some(handle), Some(_)) = (&self.selected_handle, &self.scan_thread_handle) {
+            if let Ok(mut handle) = handle.try_lock() {
+                handle.handle.cancel();
+            }
+        }
+        self.scan_status = ScanStatus::Cancelling;
+        if self.scan_thread_handle.as_ref().is_none_or(JoinHandle::is_finished) {
+            self.finish_cancelled_scan();
+        }
+    }
+
+    fn finish_cancelled_scan(&mut self) {
+        self.stop_reading_thread();
+        self.scan_status = ScanStatus::Stopped;
+
+        // Pages finished before the cancel may still be waiting to be received
+        let ctx = self.ui_context.clone();
+        self.receive_decoded_pages(&ctx);
+        // The reading thread finished the file before it was joined
+        self.streaming_path = None;
+        // A cancelled rescan leaves the page it was replacing, so there is no partial batch to report
+        if self.rescan_slot.take().is_none() {
+            self.cancelled_scan_pages = Some(self.scanned_images.len());
+        }
+    }
+
+    fn clear_selection_from(&mut self, index: usize) {
+        for n in (index..self.selected_page_indices.len()).rev() {
+            self.scanned_images[self.selected_page_indices[n]]
+                .selected_as_page = None;
+            self.selected_page_indices.pop();
+        }
+
+        self.pages_selected = index;
+    }
+
+    fn select_page(&mut self, index: usize) {
+        let image = &mut self.scanned_images[index];
+        if image.selected_as_page.is_none() {
+            image.selected_as_page = Some(self.pages_selected);
+            self.selected_page_indices.push(index);
+            self.pages_selected += 1;
+        }
+    }
+
+    /// Selects or deselects a single page, keeping the rest of the selection and renumbering the pages after it
+    fn toggle_page(&mut self, index: usize) {
+        let Some(position) = self.scanned_images[index].selected_as_page else {
+            self.select_page(index);
+            return;
+        };
+
+        self.scanned_images[index].selected_as_page = None;
+        self.selected_page_indices.remove(position);
+        for (page, &image_index) in self.selected_page_indices.iter().enumerate().skip(position) {
+            self.scanned_images[image_index].selected_as_page = Some(page);
+        }
+        self.pages_selected -= 1;
+    }
+
+    /// Selects every visible page from the most recently selected one through `index`, in that direction
+    fn select_range_to(&mut self, index: usize) {
+        let Some(&anchor) = self.selected_page_indices.last() else {
+            self.select_page(index);
+            return;
+        };
+
+        let range: Vec<usize> = if anchor <= index { (anchor..=index).collect() } else { (index..=anchor).rev().collect() };
+        for page in range {
+            if self.is_page_visible(&self.scanned_images[page]) {
+                self.select_page(page);
+            }
+        }
+    }
+
+    /// Moves a page within the selection sequence, renumbering the pages in between
+    fn move_selected_page(&mut self, from: usize, to: usize) {
+        let index = self.selected_page_indices.remove(from);
+        self.selected_page_indices.insert(to, index);
+        for page in from.min(to)..=from.max(to) {
+            self.scanned_images[self.selected_page_indices[page]].selected_as_page = Some(page);
+        }
+    }
+
+    fn clear_selection(&mut self) {
+        self.clear_selection_from(0);
+    }
+
+    fn copy_saved_paths(&self, ctx: &Context) {
+        let paths: Vec<_> = self.last_saved_paths.iter().map(|path| path.to_string_lossy()).collect();
+        ctx.output_mut(|o| o.copied_text = paths.join("\n"));
+    }
+
+    fn has_unsaved_scans(&self) -> bool {
+        !self.staged_pages.is_empty() || self.scanned_images.iter().any(|image| !image.saved_to_file)
+    }
+
+    /// Copies the selected pages, in output order, to the end of the staging tray
+    fn stage_selection(&mut self) {
+        self.stage_pages(&self.ordered_page_indices());
+        self.clear_selection();
+    }
+
+    fn stage_pages(&mut self, indices: &[usize]) {
+        let text = self.settings.language.strings();
+        let copies: io::Result<Vec<ScanEntry>> = indices.iter()
+            .map(|&i| self.scanned_images[i].staged_copy())
+            .collect();
+        let mut copies = match copies {
+            Ok(copies) => copies,
+            Err(error) => {
+                self.report_error(text.error_staging_pages, &error);
+                return;
+            },
+        };
+
+        if self.settings.spill_idle_pages {
+            if let Err(error) = copies.iter_mut().try_for_each(ScanEntry::spill_to_disk) {
+                self.report_error(text.error_moving_page_data, &error);
+            }
+        }
+
+        self.staged_pages.append(&mut copies);
+    }
+
+    fn apply_page_action(&mut self, index: usize, action: PageAction) {
+        match action {
+            PageAction::InsertPageBreaks => self.dialog_status.page_breaks = Some(index),
+            PageAction::Annotate => self.dialog_status.annotate = Some(index),
+            PageAction::Duplicate => self.duplicate_page(index),
+            PageAction::MergeWithNext => self.dialog_status.merge = Some(index),
+            PageAction::AddToTray => self.stage_pages(&[index]),
+            PageAction::Rescan => self.rescan_page(index),
+            PageAction::Delete => {
+                let text = self.settings.language.strings();
+                if let YesNo::Yes = message_box_yes_no(text.delete_page_title, text.delete_page_prompt, MessageBoxIcon::Question, YesNo::No) {
+                    self.remove_scan_entries(&[index]);
+                    self.close_page_dialogs();
+                }
+            },
+        }
+    }
+
+    /// Scans a single page to replace the one at `index`
+    fn rescan_page(&mut self, index: usize) {
+        self.rescan_slot = Some(index);
+        self.start_scan();
+        if self.scan_status == ScanStatus::Stopped {
+            self.rescan_slot = None;
+        }
+    }
+
+    /// Inserts an independent copy of the page right after it
+    fn duplicate_page(&mut self, index: usize) {
+        let mut copy = match self.scanned_images[index].staged_copy() {
+            Ok(copy) => copy,
+            Err(error) => {
+                let text = self.settings.language.strings();
+                self.report_error(text.error_moving_page_data, &error);
+                return;
+            },
+        };
+        copy.saved_to_file = self.scanned_images[index].saved_to_file;
+        if self.settings.spill_idle_pages {
+            if let Err(error) = copy.spill_to_disk() {
+                let text = self.settings.language.strings();
+                self.report_error(text.error_moving_page_data, &error);
+            }
+        }
+
+        self.scanned_images.insert(index + 1, copy);
+        for i in &mut self.selected_page_indices {
+            if *i > index {
+                *i += 1;
+            }
+        }
+        self.close_page_dialogs();
+    }
+
+    /// Replaces the page at `index` and the one after it with a single page joining the two.
+    /// Each page's flip, annotations and dewarping are drawn into the merged pixels.
+    fn merge_with_next(&mut self, ctx: &Context, index: usize) -> Result<(), Box<dyn Error>> {
+        let (first, second) = (&self.scanned_images[index], &self.scanned_images[index + 1]);
+        let layout = MergeLayout::new(first.size, second.size, self.merge_options.direction, self.merge_options.scale_to_match);
+        let pixels = merge_pixels(&first.apply_edits(first.pixels()?), first.size, &second.apply_edits(second.pixels()?), second.size, &layout)
+            .ok_or("Page data does not match its dimensions")?;
+
+        let image = build_preview_image(layout.size, &pixels, &self.pixel_adjustments, self.preview_texture_width);
+        let mut merged = ScanEntry::new(ctx, format!("merged-{index}"), DecodedPage {
+            size: layout.size, pixels, image, adjustments: self.pixel_adjustments, preview_width: self.preview_texture_width,
+            format: first.format.combine(second.format),
+        });
+        merged.saved_to_file = first.saved_to_file && second.saved_to_file;
+        merged.scanned_at = first.scanned_at;
+        merged.note = [first.note.trim(), second.note.trim()].into_iter()
+            .filter(|note| !note.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        // Breaks only keep their meaning when the pages are stacked
+        if self.merge_options.direction == MergeDirection::Stacked {
+            #[allow(clippy::cast_precision_loss)]
+            let second_scale = layout.second_size[1] as f64 / second.size[1].max(1) as f64;
+            #[allow(clippy::cast_possible_truncation)]
+            #[allow(clippy::cast_precision_loss)]
+            #[allow(clippy::cast_sign_loss)]
+            let second_breaks = second.page_breaks.iter().map(|row| layout.second_offset[1] + (*row as f64 * second_scale).round() as usize);
+            merged.page_breaks = first.page_breaks.iter().copied().chain(second_breaks).collect();
+        }
+        if self.settings.spill_idle_pages {
+            merged.spill_to_disk()?;
+        }
+
+        self.remove_scan_entries(&[index, index + 1]);
+        self.scanned_images.insert(index, merged);
+        for i in &mut self.selected_page_indices {
+            if *i >= index {
+                *i += 1;
+            }
+        }
+        self.close_page_dialogs();
+
+        Ok(())
+    }
+
+    /// Replaces the two selected pages, the front and back of a card in selection order, with one page showing both sides at actual size
+    fn make_id_card_page(&mut self, ctx: &Context) -> Result<(), Box<dyn Error>> {
+        let [front_index, back_index] = self.selected_page_indices[..] else {
+            return Err("Exactly two pages must be selected".into());
+        };
+        let (front, back) = (&self.scanned_images[front_index], &self.scanned_images[back_index]);
+        let (paper_width, paper_height) = self.paper_size.size_mm();
+        let margins = self.settings.page_margins;
+        let area = ((paper_width - margins.left - margins.right).max(1.0), (paper_height - margins.top - margins.bottom).max(1.0));
+        let (size, pixels) = id_card_pixels(&front.apply_edits(front.pixels()?), front.size, &back.apply_edits(back.pixels()?), back.size,
+            self.settings.id_card_size, area).ok_or("Page data does not match its dimensions")?;
+
+        let index = front_index.min(back_index);
+        let image = build_preview_image(size, &pixels, &self.pixel_adjustments, self.preview_texture_width);
+        let mut card = ScanEntry::new(ctx, format!("id-card-{index}"), DecodedPage {
+            size, pixels, image, adjustments: self.pixel_adjustments, preview_width: self.preview_texture_width,
+            format: front.format.combine(back.format),
+        });
+        card.saved_to_file = front.saved_to_file && back.saved_to_file;
+        card.scanned_at = front.scanned_at;
+        card.id_card_layout = Some((self.paper_size, margins));
+        card.note = [front.note.trim(), back.note.trim()].into_iter()
+            .filter(|note| !note.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if self.settings.spill_idle_pages {
+            card.spill_to_disk()?;
+        }
+
+        self.remove_scan_entries(&[front_index, back_index]);
+        self.scanned_images.insert(index, card);
+        self.select_page(index);
+        self.close_page_dialogs();
+
+        Ok(())
+    }
+
+    /// Closes windows tied to a page index, for when indices shift
+    fn close_page_dialogs(&mut self) {
+        self.dialog_status.page_breaks = None;
+        self.dialog_status.annotate = None;
+        self.dialog_status.merge = None;
+        self.dialog_status.print = None;
+    }
+
+    fn clear_staged_pages(&mut self) {
+        let text = self.settings.language.strings();
+        if let YesNo::Yes = message_box_yes_no(text.clear_tray_title, &fill(text.clear_tray_prompt, self.staged_pages.len()),
+            MessageBoxIcon::Question, YesNo::No) {
+            self.staged_pages.clear();
+        }
+    }
+
+    fn confirm_quit(&mut self, ctx: &Context) {
+        if !ctx.input(|i| i.viewport().close_requested()) || !self.has_unsaved_scans() {
+            return;
+        }
+
+        let text = self.settings.language.strings();
+        if let YesNo::No = message_box_yes_no(text.quit_title, text.quit_prompt, MessageBoxIcon::Question, YesNo::No) {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        }
+    }
+
+    fn mark_selection_saved(&mut self) {
+        let newly_saved = self.selected_page_indices.iter().filter(|&&i| !self.scanned_images[i].saved_to_file).count();
+        self.scan_statistics.lock().unwrap().pages_saved += newly_saved;
+
+        for n in (0..self.selected_page_indices.len()).rev() {
+            self.scanned_images[self.selected_page_indices[n]]
+                .saved_to_file = true;
+        }
+    }
+
+    fn partial_page_indices(&self) -> Vec<usize> {
+        let heights: Vec<usize> = self.scanned_images.iter()
+            .map(|image| image.size[1])
+            .collect();
+
+        find_partial_pages(&heights)
+    }
+
+    fn remove_scan_entries(&mut self, indices: &[usize]) {
+        let images = &mut self.scanned_images;
+
+        let mut removing = indices.to_vec();
+        removing.sort_unstable();
+        removing.dedup();
+        for i in removing.iter().rev() {
+            if *i < images.len() {
+                images.remove(*i);
+            }
+        }
+
+        // Drop removed pages from the selection and shift the remaining indices down
+        self.selected_page_indices = self.selected_page_indices.iter()
+            .filter(|i| removing.binary_search(i).is_err())
+            .map(|i| i - removing.iter().filter(|r| *r < i).count())
+            .collect();
+
+        for (page, i) in self.selected_page_indices.iter().enumerate() {
+            images[*i].selected_as_page = Some(page);
+        }
+        self.pages_selected = self.selected_page_indices.len();
+    }
+
+    fn remove_partial_pages(&mut self) {
+        let partial_pages = self.partial_page_indices();
+        if partial_pages.is_empty() {
+            return;
+        }
+
+        let text = self.settings.language.strings();
+        if let YesNo::Yes = message_box_yes_no(text.remove_partial_title,
+            &fill(text.remove_partial_prompt, partial_pages.len()),
+            MessageBoxIcon::Question, YesNo::No) {
+            self.remove_scan_entries(&partial_pages);
+        }
+    }
+
+    fn estimated_output_size(&self) -> (u64, u64) {
+        self.selected_page_indices.iter()
+            .filter_map(|i| self.scanned_images.get(*i))
+            .map(|image| estimate_image_bytes(output_size(image.size, self.output_dpi, self.paper_size), image.format, self.settings.pdf_compression))
+            .fold((0, 0), |(raw, compressed), (r, c)| (raw + r, compressed + c))
+    }
+
+    fn ordered_page_indices(&self) -> Vec<usize> {
+        let mut indices = self.selected_page_indices.clone();
+        match self.page_order {
+            PageOrder::AsSelected => (),
+            PageOrder::Reverse => indices.reverse(),
+            PageOrder::ScanOrder => indices.sort_unstable(),
+        }
+
+        indices
+    }
+
+    /// Saves the pages from `source`. When `unattended`, nothing is asked: missing directories are created and existing files are kept.
+    fn save_pdf(&mut self, ctx: &Context, source: PageSource, unattended: bool) {
+        let text = self.settings.language.strings();
+        match self.write_pdf(source, unattended) {
+            Ok(SaveStatus::Completed(paths)) => {
+                match source {
+                    PageSource::Selection => {
+                        self.mark_selection_saved();
+                        self.clear_selection();
+                    },
+                    PageSource::Staged => self.staged_pages.clear(),
+                }
+
+                self.set_last_saved_paths(paths);
+                if self.settings.copy_saved_path {
+                    self.copy_saved_paths(ctx);
+                }
+            },
+            Ok(SaveStatus::Cancelled) => {},
+            // Nothing is cleared on failure, so the same pages can be saved again once the location is fixed
+            Err(error) if is_permission_denied(error.as_ref()) => {
+                let location = self.saving_path().ok().and_then(|path| path.parent().map(|parent| parent.to_string_lossy().into_owned())).unwrap_or_default();
+                self.report_error(&fill(text.error_save_permission_denied, location), &error);
+            },
+            Err(error) => self.report_error(text.error_saving_pdf, &error),
+        }
+    }
+
+    fn set_last_saved_paths(&mut self, paths: Vec<PathBuf>) {
+        self.last_saved_size = paths.iter()
+            .filter_map(|path| fs::metadata(path).ok())
+            .map(|metadata| metadata.len())
+            .sum();
+        self.last_saved_paths = paths;
+    }
+
+    fn write_pdf(&self, source: PageSource, unattended: bool) -> Result<SaveStatus, Box<dyn std::error::Error>> {
+        let text = self.settings.language.strings();
+        let pages: Vec<&ScanEntry> = match source {
+            PageSource::Selection => self.ordered_page_indices().into_iter()
+                .map(|i| self.scanned_images.get(i).ok_or("Page index exceeded size of image vector"))
+                .collect::<Result<_, _>>()?,
+            PageSource::Staged => self.staged_pages.iter().collect(),
+        };
+        if pages.is_empty() {
+            return Err(text.error_no_pages_selected.into());
+        }
+
+        let current_layout = (self.paper_size, self.settings.page_margins);
+        let moved_cards = pages.iter().filter(|page| page.id_card_layout.is_some_and(|layout| layout != current_layout)).count();
+        if moved_cards > 0 && !unattended {
+            if let YesNo::No = message_box_yes_no(text.id_card_layout_changed_title, &fill(text.id_card_layout_changed_prompt, moved_cards), MessageBoxIcon::Warning, YesNo::No) {
+                return Ok(SaveStatus::Cancelled);
+            }
+        }
+
+        let saving_path = self.saving_path()?;
+        if !self.create_save_directory(&saving_path, unattended)? {
+            return Ok(SaveStatus::Cancelled);
+        }
+
+        // Each output file with the scans it contains. Staged pages are always combined into one document.
+        let outputs: Vec<(PathBuf, Vec<&ScanEntry>)> = match (source, self.output_mode) {
+            (PageSource::Staged, _) | (_, OutputMode::SingleFile) => vec![(saving_path, pages)],
+            (PageSource::Selection, OutputMode::FilePerPage) => pages.into_iter().enumerate()
+                .map(|(n, page)| (with_index_suffix(&saving_path, n + 1), vec![page]))
+                .collect(),
+            (PageSource::Selection, OutputMode::SplitOnBlankPages) => self.split_on_blank_pages(pages)?.into_iter().enumerate()
+                .map(|(n, document)| (with_index_suffix(&saving_path, n + 1), document))
+                .collect(),
+        };
+        if outputs.is_empty() {
+            return Err(text.error_only_blank_pages.into());
+        }
+
+        // Existing files are handled once for the whole batch rather than per file
+        let outputs = if outputs.iter().any(|(path, _)| path.exists()) {
+            match self.existing_file_handling(unattended) {
+                None => return Ok(SaveStatus::Cancelled),
+                Some(OverwriteBehavior::AppendSuffix) => outputs.into_iter()
+                    .map(|(path, pages)| (next_free_path(&path), pages))
+                    .collect(),
+                Some(_) => outputs,
+            }
+        } else {
+            outputs
+        };
+
+        let mut saved_paths = Vec::with_capacity(outputs.len());
+        for (path, pages) in outputs {
+            self.write_document(&pages, &path)?;
+            saved_paths.push(path.canonicalize().unwrap_or(path));
+        }
+
+        Ok(SaveStatus::Completed(saved_paths))
+    }
+
+    /// Creates the missing directories above `path`, all levels together after a single confirmation unless `unattended`.
+    /// False if the user declined.
+    fn create_save_directory(&self, path: &Path, unattended: bool) -> io::Result<bool> {
+        let Some(parent) = path.parent().filter(|parent| !parent.exists()) else {
+            return Ok(true);
+        };
+
+        let text = self.settings.language.strings();
+        let confirmed = unattended || matches!(message_box_yes_no(text.create_directory_title, &fill(text.create_directory_prompt, parent.to_string_lossy()), MessageBoxIcon::Question, YesNo::Yes), YesNo::Yes);
+        if confirmed {
+            fs::create_dir_all(parent)?;
+        }
+        Ok(confirmed)
+    }
+
+    /// How to handle existing files in the way of a save, asking first if the settings say to. Unattended saves keep
+    /// the existing files. `None` if the user declined to overwrite them.
+    fn existing_file_handling(&self, unattended: bool) -> Option<OverwriteBehavior> {
+        if unattended {
+            return Some(OverwriteBehavior::AppendSuffix);
+        }
+
+        match self.settings.overwrite_behavior {
+            OverwriteBehavior::Prompt => {
+                let text = self.settings.language.strings();
+                matches!(message_box_yes_no(text.overwrite_title, text.overwrite_prompt, MessageBoxIcon::Question, YesNo::No), YesNo::Yes)
+                    .then_some(OverwriteBehavior::Overwrite)
+            },
+            behavior => Some(behavior),
+        }
+    }
+
+    /// Saves the selected pages as image files in a ZIP archive, named after the file name field and numbered in output order
+    fn export_zip(&mut self) {
+        let text = self.settings.language.strings();
+        let stem = self.saving_path().ok()
+            .and_then(|path| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| String::from(Path::new(DEFAULT_FILE_NAME).file_stem().unwrap_or_default().to_string_lossy()));
+        let Some(path) = save_file_dialog_with_filter(text.export_zip_title, &format!("{stem}.zip"), &["*.zip"], text.zip_files) else {
+            return;
+        };
+
+        let path = PathBuf::from(path);
+        if let Err(error) = self.write_zip_export(&stem, &path) {
+            // Pages are written as they are encoded, so a failure leaves an incomplete archive
+            let _ = fs::remove_file(&path);
+            self.report_error(text.error_exporting_zip, &error);
+        }
+    }
+
+    fn write_zip_export(&self, stem: &str, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let file_format = self.settings.export_image_format;
+        let mut zip = ZipWriter::create(path)?;
+        for (n, i) in self.ordered_page_indices().into_iter().enumerate() {
+            let page = self.scanned_images.get(i).ok_or("Page index exceeded size of image vector")?;
+            // Exported as they would be saved, without the layout applied to PDF pages
+            let stored_pixels = page.pixels()?;
+            let pixels = page.apply_edits(apply_adjustments(&stored_pixels, page.size, &self.pixel_adjustments));
+            let name = with_index_suffix(Path::new(&format!("{stem}.{}", file_format.extension())), n + 1);
+            zip.add_file(&name.to_string_lossy(), &encode_page_image(&pixels, page.size, page.format, file_format)?)?;
+        }
+
+        zip.finish()
+    }
+
+    /// Where the file name field says to save. With the setting on, an absolute file name is used as is instead of being put in the save location.
+    fn saving_path(&self) -> Result<PathBuf, &'static str> {
+        let text = self.settings.language.strings();
+        let file_path = if self.file_save_path.trim().is_empty() { String::from(DEFAULT_FILE_NAME) } else { self.file_save_path.clone() + ".pdf" };
+        if self.settings.absolute_file_names && Path::new(&file_path).is_absolute() {
+            return Ok(PathBuf::from(file_path));
+        }
+
+        let root_path = self.root_location.as_ref().ok_or(text.error_no_save_location)?;
+        // The file name may contain subdirectories, but must not lead out of the root location
+        join_within(root_path, Path::new(&file_path)).ok_or(text.error_path_outside_root)
+    }
+
+    /// Groups pages into the documents separated by blank pages, leaving the blank pages out
+    fn split_on_blank_pages<'a>(&self, pages: Vec<&'a ScanEntry>) -> Result<Vec<Vec<&'a ScanEntry>>, Box<dyn std::error::Error>> {
+        // Blankness is judged on the pixels as they would be saved
+        let blank: Vec<bool> = pages.iter()
+            .map(|page| page.pixels().map(|pixels| is_blank_page(&apply_adjustments(&pixels, page.size, &self.pixel_adjustments), BLANK_PAGE_MAX_INK)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(split_at_separators(pages.into_iter().zip(blank), |(_, blank)| *blank).into_iter()
+            .map(|document| document.into_iter().map(|(page, _)| page).collect())
+            .collect())
+    }
+
+    /// Writes `pages` to a single PDF file at `path` in the chosen output format
+    fn write_document(&self, pages: &[&ScanEntry], path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let doc = match self.output_format {
+            OutputFormat::Pdf => PdfDocument::empty(""),
+            OutputFormat::PdfA => empty_document(),
+        };
+        // The watermark uses a built-in font, and PDF/A only allows embedded ones
+        let watermark_font = if self.settings.watermark.enabled && self.output_format == OutputFormat::Pdf {
+            Some(doc.add_builtin_font(BuiltinFont::Courier)?)
+        } else {
+            None
+        };
+        for page in pages {
+            self.add_scan_to_pdf(&doc, page, watermark_font.as_ref())?;
+        }
+        let bytes = compress_document(&doc.save_to_bytes()?, self.settings.pdf_compression)?;
+        match self.output_format {
+            OutputFormat::Pdf => fs::write(path, bytes)?,
+            OutputFormat::PdfA => fs::write(path, convert_to_pdfa(&bytes)?)?,
+        }
+
+        Ok(())
+    }
+
+    /// Rows at which a scan is split into separate output pages, or `None` if it is output as a single whole page.
+    /// Manual page breaks take precedence over fixed-interval splitting. ID card pages are never split, as they are laid out to fit one page.
+    fn output_breaks(&self, scanned_image: &ScanEntry) -> Option<Vec<usize>> {
+        if scanned_image.id_card_layout.is_some() {
+            None
+        } else if !scanned_image.page_breaks.is_empty() {
+            Some(scanned_image.page_breaks.clone())
+        } else if self.page_split.enabled {
+            Some(interval_breaks(scanned_image.size[1], self.page_split.rows_per_segment()))
+        } else {
+            None
+        }
+    }
+
+    /// Adds the scan to the document as one or more pages, stamping each with the watermark when `watermark_font` is given
+    fn add_scan_to_pdf(&self, doc: &PdfDocumentReference, scanned_image: &ScanEntry, watermark_font: Option<&IndirectFontRef>) -> Result<(), Box<dyn std::error::Error>> {
+        let stored_pixels = scanned_image.pixels()?;
+        let pixels = scanned_image.apply_edits(apply_adjustments(&stored_pixels, scanned_image.size, &self.pixel_adjustments));
+
+        let breaks = self.output_breaks(scanned_image);
+        let segmented = breaks.is_some();
+        let segments = match &breaks {
+            Some(breaks) => split_at_rows(&pixels, scanned_image.size, breaks),
+            None => vec![(scanned_image.size, &pixels[..])],
+        };
+
+        let watermark_text = self.settings.watermark.text(scanned_image.scanned_at);
+
+        let downsample_scale = downsample_scale(self.output_dpi, scanned_image.size, self.paper_size);
+
+        for (segment, (size, data)) in segments.into_iter().enumerate() {
+            // The scale factors below are derived from the pixel size, so a downsampled segment covers the same area
+            let resampled;
+            let (size, data) = match downsample_scale {
+                Some(scale) => {
+                    let (new_size, pixels) = downsample(data, size, scale)?;
+                    resampled = pixels;
+                    (new_size, &resampled[..])
+                },
+                None => (size, data),
+            };
+
+            let (page_width, page_height) = self.paper_size.size();
+            let (new_page, new_layer) = doc.add_page(page_width, page_height, "Layer 1");
+            let current_layer = doc.get_page(new_page).get_layer(new_layer);
+
+            if segment == 0 && !scanned_image.note.trim().is_empty() {
+                doc.get_page(new_page).extend_with(note_annotation(scanned_image.note.trim(), page_height));
+            }
+
+            let (color_space, bits_per_component, image_data) = image_encoding(data, size, scanned_image.format);
+
+            let image = Image::from(ImageXObject {
+                width: Px(size[0]),
+                height: Px(size[1]),
+                color_space,
+                bits_per_component,
+                interpolate: self.settings.image_smoothing.applies(matches!(color_space, ColorSpace::Greyscale)),
+                image_data,
+                image_filter: None,
+                clipping_bbox: None,
+                smask: None,
+            });
+
+            let placement = PagePlacement::new(size, segmented, self.paper_size, self.settings.page_margins);
+            image.add_to_layer(current_layer.clone(), ImageTransform {
+                translate_x: Some(placement.left),
+                translate_y: Some(placement.bottom),
+                rotate: None,
+                scale_x: Some(placement.scale_x),
+                scale_y: Some(placement.scale_y),
+                dpi: None,
+            });
+
+            // Drawn over the image on the same layer, leaving the stored pixels untouched
+            if let Some(font) = watermark_font {
+                self.settings.watermark.draw(&current_layer, font, &watermark_text, (page_width, page_height));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_top_panel(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        egui::TopBottomPanel::top("MainUI-TopPanel").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                if ui.button("↻").on_hover_text_at_pointer(text.refresh_devices_hover).clicked() {
+                    self.refresh_devices();
+                };
+
+                ui.checkbox(&mut self.search_network, text.search_network);
 
                 ui.add_enabled_ui(!self.scanner_list.is_empty(), |ui| {
-                    if egui::ComboBox::from_label(" is the selected scanner.")
+                    if egui::ComboBox::from_label(text.selected_scanner)
                         .show_index(ui, &mut self.selected_scanner, self.scanner_list.len(),
                         |i| match self.scanner_list.get(i) {
                             Some(device) => format!("{} — {}",
                                 cstring_to_string(&device.name, "device name"),
                                 cstring_to_string(&device.model, "device model")),
-                            None => String::from("(None)"),
+                            None => String::from(text.no_scanner),
                         })
-                    .on_disabled_hover_text("No scanner available — try clicking refresh")
+                    .on_disabled_hover_text(text.no_scanner_hover)
+                    .changed() {
+                        self.open_selected_device();
+                    };
+                });
+
+                ui.add_enabled_ui(self.scan_status == ScanStatus::Stopped && !self.device_busy(), |ui| {
+                    let mut applied = None;
+                    egui::ComboBox::from_id_source("scan_profile").selected_text(text.profile).show_ui(ui, |ui| {
+                        for profile in &self.settings.scan_profiles {
+                            if ui.selectable_label(false, &profile.name).clicked() {
+                                applied = Some(profile.clone());
+                            }
+                        }
+                        if self.settings.scan_profiles.is_empty() {
+                            ui.weak(text.no_profiles);
+                        }
+                        ui.separator();
+                        if ui.selectable_label(false, text.manage_profiles).clicked() {
+                            self.dialog_status.profiles = true;
+                        }
+                    }).response.on_hover_text(text.profile_hover);
+
+                    if let Some(profile) = applied {
+                        self.apply_profile(&profile);
+                    }
+                });
+
+                if self.device_opener.is_some() {
+                    ui.spinner();
+                    ui.label(text.opening_device);
+                    if ui.button(text.cancel).on_hover_text(text.cancel_opening_hover).clicked() {
+                        self.cancel_device_opening();
+                    }
+                }
+
+                if self.prescan_task.is_some() {
+                    ui.spinner();
+                    ui.label(text.prescanning);
+                }
+
+                ui.add_enabled_ui(self.selected_handle.is_some() && self.scan_status == ScanStatus::Stopped && !self.device_busy(), |ui| {
+                    if ui.button(text.configure_scanner).clicked() {
+                        self.open_config_window();
+                    }
+
+                    if let Some(resolution) = &self.resolution_option {
+                        let mut selected_dpi = resolution.current;
+                        egui::ComboBox::from_id_source("quick_resolution")
+                            .selected_text(format!("{selected_dpi} DPI"))
+                            .show_ui(ui, |ui| {
+                                for &dpi in &resolution.choices {
+                                    ui.selectable_value(&mut selected_dpi, dpi, format!("{dpi} DPI"));
+                                }
+                            }).response.on_hover_text(text.resolution_hover);
+
+                        if selected_dpi != resolution.current {
+                            self.apply_resolution(selected_dpi);
+                        }
+                    }
+
+                    if let Some(duplex) = &self.duplex_option {
+                        let mut enabled = duplex.enabled;
+                        if ui.toggle_value(&mut enabled, text.duplex).on_hover_text(text.duplex_hover).changed() {
+                            self.apply_duplex(enabled);
+                        }
+                    }
+
+                    if let Some(color_mode) = &self.color_mode_option {
+                        let mut selected_format = color_mode.current;
+                        let selected_text = selected_format.map_or(color_mode.current_mode.as_str(), |format| format.label(text));
+                        egui::ComboBox::from_id_source("quick_color_mode")
+                            .selected_text(selected_text)
+                            .show_ui(ui, |ui| {
+                                for format in color_mode.formats() {
+                                    ui.selectable_value(&mut selected_format, Some(format), format.label(text));
+                                }
+                            }).response.on_hover_text(text.color_mode_hover);
+
+                        if let Some(format) = selected_format.filter(|&format| color_mode.current != Some(format)) {
+                            self.apply_color_mode(format);
+                        }
+                    }
+
+                    if self.scan_area_available && ui.button(text.max_scan_area).on_hover_text(text.max_scan_area_hover).clicked() {
+                        self.apply_scan_area(FULL_SCAN_AREA);
+                    }
+
+                    if self.scan_area_available && ui.button(text.scan_area_diagram).on_hover_text(text.scan_area_diagram_hover).clicked() {
+                        self.load_scan_area_diagram();
+                    }
+
+                    if ui.button(text.start_scanning).clicked() {
+                        self.start_scan();
+                    }
+
+                    if ui.button(text.scan_to_print).on_hover_text(text.scan_to_print_hover).clicked() {
+                        self.scan_to_print();
+                    }
+
+                    ui.checkbox(&mut self.scan_to_pdf, text.scan_to_pdf).on_hover_text(text.scan_to_pdf_hover);
+
+                    if ui.button(text.prescan).on_hover_text(text.prescan_hover).clicked() {
+                        self.start_prescan();
+                    }
+
+                    if ui.button(text.disconnect_scanner).on_hover_text(text.disconnect_scanner_hover).clicked() {
+                        self.disconnect_device();
+                    }
+                });
+
+                ui.add_enabled_ui(self.scan_status == ScanStatus::Stopped, |ui| {
+                    if ui.button(text.import_images).on_hover_text(text.import_images_hover).clicked() {
+                        self.import_images(ctx);
+                    }
+
+                    if ui.button(text.load_session).on_hover_text(text.load_session_hover).clicked() {
+                        self.load_session(ctx);
+                    }
+                });
+
+                ui.add_enabled_ui(!self.scanned_images.is_empty() && self.scan_status == ScanStatus::Stopped, |ui| {
+                    if ui.button(text.save_session).on_hover_text(text.save_session_hover).clicked() {
+                        self.save_session();
+                    }
+                });
+
+                if ui.button(text.settings).clicked() {
+                    self.dialog_status.settings = !self.dialog_status.settings;
+                }
+
+                if ui.button(text.statistics).clicked() {
+                    self.dialog_status.statistics = !self.dialog_status.statistics;
+                }
+
+                if ui.button(text.about).clicked() {
+                    self.dialog_status.about = !self.dialog_status.about;
+                }
+
+                ui.add_enabled_ui(self.selected_handle.is_some() && matches!(self.scan_status, ScanStatus::Running | ScanStatus::Paused), |ui| {
+                    if ui.button(text.cancel_scan).clicked() {
+                        self.cancel_scan();
+                    }
+
+                    if self.scan_status == ScanStatus::Paused {
+                        if ui.button(text.resume_scan).clicked() {
+                            self.set_scan_paused(false);
+                        }
+                        ui.label(text.scan_paused);
+                    } else if ui.button(text.pause_scan).on_hover_text(text.pause_scan_hover).clicked() {
+                        self.set_scan_paused(true);
+                    }
+                });
+
+                if self.scan_status == ScanStatus::Cancelling {
+                    ui.label(text.scan_cancelling);
+                }
+                if let Some(pages) = self.cancelled_scan_pages {
+                    ui.label(fill(text.scan_cancelled_summary, pages));
+                }
+
+                if let Some(path) = &self.streaming_path {
+                    ui.colored_label(Color32::GOLD, fill(text.scanning_to_pdf, path.to_string_lossy()));
+                }
+
+                ui.add_enabled_ui(self.selected_handle.is_some() && !self.device_busy(), |ui| {
+                    if ui.checkbox(&mut self.button_scan_enabled, text.scan_on_button)
+                        .on_hover_text(text.scan_on_button_hover)
+                        .changed() {
+                        if self.button_scan_enabled {
+                            self.load_sensor_choices();
+                            self.start_button_polling();
+                        } else {
+                            self.stop_button_polling();
+                        }
+                    }
+
+                    if self.button_scan_enabled {
+                        if self.sensor_choices.is_empty() {
+                            ui.colored_label(Color32::GOLD, text.no_sensors);
+                        } else if egui::ComboBox::from_id_source("button_sensor")
+                            .show_index(ui, &mut self.selected_sensor, self.sensor_choices.len(),
+                                |i| self.sensor_choices.get(i).map_or(String::new(), |(_, title)| title.clone()))
+                            .changed() {
+                            self.start_button_polling();
+                        }
+                    }
+                });
+            });
+
+            if self.no_devices_found {
+                ui.horizontal_wrapped(|ui| {
+                    ui.colored_label(Color32::GOLD, text.no_scanners_found)
+                        .on_hover_text(text.no_scanners_found_hover);
+                    if ui.button(text.retry).clicked() {
+                        self.refresh_devices();
+                    }
+                });
+            }
+        });
+    }
+
+    /// Shows the current values of a few key scanner options; clicking one reveals it in the configuration window
+    fn draw_summary_bar(&mut self, ctx: &Context) {
+        if self.selected_handle.is_none() {
+            return;
+        }
+
+        let summary = summarize_options(&self.config_options);
+        if summary.is_empty() {
+            return;
+        }
+
+        let text = self.settings.language.strings();
+        let mut jump_to = None;
+        egui::TopBottomPanel::top("MainUI-SummaryBar").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                for (index, item) in summary.into_iter().enumerate() {
+                    if index > 0 {
+                        ui.separator();
+                    }
+
+                    let label = match item.title {
+                        Some(title) => format!("{title}: {}", item.value),
+                        None => format!("{}: {}", text.scan_area, item.value),
+                    };
+                    if ui.add(egui::Label::new(label).sense(Sense::click())).on_hover_text(text.summary_hover).clicked() {
+                        jump_to = Some(item.option_idx);
+                    }
+                }
+            });
+        });
+
+        if let Some(option_idx) = jump_to {
+            self.open_config_window();
+            self.config_scroll_target = Some(option_idx);
+        }
+    }
+
+    fn draw_bottom_panel(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        egui::TopBottomPanel::bottom("MainUI-BottomPanel").show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                let preview_slider = ui.add(egui::Slider::new(&mut self.image_max_x, THUMBNAIL_SIZE_RANGE).text(text.preview_size))
+                    .on_hover_text(text.preview_size_hover);
+
+                // Regenerate previews at the new resolution once the slider or Ctrl+scroll zooming settles
+                let zoom_settled = self.thumbnail_zoomed_at.is_none_or(|zoomed_at| zoomed_at.elapsed() >= THUMBNAIL_ZOOM_SETTLE_TIME);
+                if !preview_slider.dragged() && zoom_settled && preview_width(self.image_max_x) != self.preview_texture_width {
+                    self.preview_texture_width = preview_width(self.image_max_x);
+                    self.refresh_all_textures();
+                }
+                ui.checkbox(&mut self.zoom_on_hover, text.zoom_on_hover)
+                    .on_hover_text(text.zoom_on_hover_hover);
+
+                let columns_label = |columns: Option<usize>| columns.map_or_else(|| String::from(text.columns_fit_window), |columns| columns.to_string());
+                egui::ComboBox::from_label(text.thumbnail_columns).selected_text(columns_label(self.thumbnail_columns)).show_ui(ui, |ui| {
+                    for columns in [None].into_iter().chain((1..=MAX_THUMBNAIL_COLUMNS).map(Some)) {
+                        ui.selectable_value(&mut self.thumbnail_columns, columns, columns_label(columns));
+                    }
+                }).response.on_hover_text(text.thumbnail_columns_hover);
+
+                if ui.add_enabled(self.first_unselected_page().is_some(), egui::Button::new(text.next_unselected))
+                    .on_hover_text(text.next_unselected_hover)
+                    .clicked() {
+                    self.jump_to_unselected_page();
+                }
+
+                if ui.button(text.select_location).clicked() {
+                    if let Some(path) = select_folder_dialog(text.select_location_title, self.root_location.as_ref().unwrap_or(&PathBuf::new()).to_str().unwrap_or("")) {
+                        self.root_location = Some(PathBuf::from(path));
+                    }
+                }
+
+                if let Some(path) = &self.root_location {
+                    ui.colored_label(Color32::GREEN, (*path.canonicalize().unwrap_or_default().to_string_lossy()).to_owned() + std::path::MAIN_SEPARATOR.to_string().as_str());
+                } else {
+                    ui.colored_label(Color32::RED, text.no_save_location);
+                }
+
+                ui.label(text.file_name);
+
+                self.path_field = Some(ui.add(egui::TextEdit::singleline(&mut self.file_save_path).hint_text(DEFAULT_FILE_NAME).cursor_at_end(false)));
+
+                // Spells out where an absolute file name or one that can't be used would actually save
+                let absolute_override = self.settings.absolute_file_names && Path::new(&self.file_save_path).is_absolute();
+                match self.saving_path() {
+                    Ok(path) if absolute_override => { ui.colored_label(Color32::GOLD, fill(text.saving_to, path.display())); },
+                    Err(error) if self.root_location.is_some() => { ui.colored_label(Color32::RED, error); },
+                    _ => {},
+                }
+
+                // Enter always ends editing; saving from the keyboard depends on the chosen shortcut
+                let can_save = !self.selected_page_indices.is_empty();
+                let shortcut = self.settings.save_shortcut;
+                let shortcut_pressed = can_save && self.path_field.as_ref().is_some_and(|field| field.lost_focus())
+                    && ctx.input(|i| i.key_pressed(egui::Key::Enter) && shortcut.matches(i.modifiers));
+
+                let save_clicked = ui.add_enabled(can_save, egui::Button::new(text.save_pdf))
+                    .on_hover_text(fill(text.save_pdf_hover, shortcut.label(text)))
+                    .on_disabled_hover_text(text.save_needs_selection_hover)
+                    .clicked();
+                if save_clicked || shortcut_pressed {
+                    self.save_pdf(ctx, PageSource::Selection, false);
+                }
+                if !can_save {
+                    ui.weak(text.save_needs_selection);
+                }
+
+                if ui.add_enabled(!self.selected_page_indices.is_empty(), egui::Button::new(text.export_zip))
+                    .on_hover_text(fill(text.export_zip_hover, self.settings.export_image_format.label(text)))
+                    .clicked() {
+                    self.export_zip();
+                }
+
+                if ui.add_enabled(self.selected_page_indices.len() == 2 && !self.pages_locked(), egui::Button::new(text.id_card_page))
+                    .on_hover_text(text.id_card_page_hover)
+                    .on_disabled_hover_text(if self.pages_locked() { text.pages_locked_hover } else { text.id_card_needs_two_pages })
+                    .clicked() {
+                    if let Err(error) = self.make_id_card_page(ctx) {
+                        self.report_error(text.error_making_id_card, &error);
+                    }
+                }
+
+                if ui.add_enabled(!self.selected_page_indices.is_empty(), egui::Button::new(text.print_pages))
+                    .on_hover_text(text.print_pages_hover)
+                    .clicked() {
+                    self.open_print_window();
+                }
+
+                if ui.add_enabled(!self.selected_page_indices.is_empty(), egui::Button::new(text.preview_layout))
+                    .on_hover_text(text.preview_layout_hover)
+                    .clicked() {
+                    self.dialog_status.pdf_preview = !self.dialog_status.pdf_preview;
+                }
+
+                if self.settings.auto_save_scans {
+                    let color = if self.root_location.is_some() { Color32::GREEN } else { Color32::RED };
+                    ui.colored_label(color, text.auto_save_active).on_hover_text(text.auto_save_hover);
+                }
+
+                if !self.last_saved_paths.is_empty() {
+                    ui.label(fill(text.saved_size, format_byte_size(self.last_saved_size)))
+                        .on_hover_text(fill(text.saved_size_hover, self.last_saved_paths.len()));
+                    if ui.button(text.copy_saved_path).on_hover_text(text.copy_saved_path_hover).clicked() {
+                        self.copy_saved_paths(ctx);
+                    }
+                }
+
+                ui.add_enabled_ui(self.selected_page_indices.len() > 1, |ui| {
+                    if ui.button(text.reorder_selection).on_hover_text(text.reorder_selection_hover).clicked() {
+                        self.dialog_status.selection_order = !self.dialog_status.selection_order;
+                    }
+                });
+
+                ui.add_enabled_ui(!self.selected_page_indices.is_empty(), |ui| {
+                    if ui.button(text.stage_selection).on_hover_text(text.stage_selection_hover).clicked() {
+                        self.stage_selection();
+                    }
+                });
+
+                if !self.selected_page_indices.is_empty() {
+                    let (raw, compressed) = self.estimated_output_size();
+                    ui.label(fill(&fill(text.selected_pages, self.selected_page_indices.len()), format_byte_size(compressed)))
+                        .on_hover_text(fill(&fill(text.estimated_size_hover, format_byte_size(raw)), format_byte_size(compressed)));
+                }
+
+                if !self.scanned_images.is_empty() {
+                    let (memory, disk) = self.page_memory_usage();
+                    ui.label(fill(text.page_data_memory, format_byte_size(memory as u64)))
+                        .on_hover_text(fill(text.page_data_disk_hover, format_byte_size(disk as u64)));
+                }
+
+                ui.checkbox(&mut self.show_saved_images, text.show_saved)
+                    .on_hover_text(text.show_saved_hover);
+
+                if ui.checkbox(&mut self.pixel_adjustments.invert, text.invert_colors)
+                    .on_hover_text(text.invert_colors_hover)
+                    .changed() {
+                    self.refresh_all_textures();
+                }
+
+                let cleanup_toggled = ui.checkbox(&mut self.pixel_adjustments.cleanup, text.document_cleanup)
+                    .on_hover_text(text.document_cleanup_hover)
+                    .changed();
+                // Every page is filtered again for its preview, so changes apply once a drag ends
+                let mut cleanup_adjusted = false;
+                if self.pixel_adjustments.cleanup {
+                    let settings = &mut self.pixel_adjustments.cleanup_settings;
+                    for (value, range, suffix, hover) in [
+                        (&mut settings.neighborhood_percent, 1..=20, text.cleanup_neighborhood_suffix, text.cleanup_neighborhood_hover),
+                        (&mut settings.threshold_percent, 1..=50, text.cleanup_threshold_suffix, text.cleanup_threshold_hover),
+                    ] {
+                        let response = ui.add(egui::DragValue::new(value).clamp_range(range).suffix(suffix)).on_hover_text(hover);
+                        cleanup_adjusted |= response.drag_stopped() || (response.changed() && !response.dragged());
+                    }
+                }
+                if cleanup_toggled || cleanup_adjusted {
+                    self.refresh_all_textures();
+                }
+
+                egui::ComboBox::from_label(text.page_order).selected_text(self.page_order.label(text)).show_ui(ui, |ui| {
+                    for order in [PageOrder::AsSelected, PageOrder::Reverse, PageOrder::ScanOrder] {
+                        ui.selectable_value(&mut self.page_order, order, order.label(text));
+                    }
+                }).response.on_hover_text(text.page_order_hover);
+
+                egui::ComboBox::from_id_source("output_mode").selected_text(self.output_mode.label(text)).show_ui(ui, |ui| {
+                    for mode in [OutputMode::SingleFile, OutputMode::FilePerPage, OutputMode::SplitOnBlankPages] {
+                        ui.selectable_value(&mut self.output_mode, mode, mode.label(text));
+                    }
+                }).response.on_hover_text(text.output_mode_hover);
+
+                egui::ComboBox::from_id_source("output_format").selected_text(self.output_format.label(text)).show_ui(ui, |ui| {
+                    for format in [OutputFormat::Pdf, OutputFormat::PdfA] {
+                        ui.selectable_value(&mut self.output_format, format, format.label(text));
+                    }
+                }).response.on_hover_text(text.output_format_hover);
+                if self.output_format == OutputFormat::PdfA && self.settings.watermark.enabled {
+                    ui.colored_label(Color32::GOLD, text.watermark_not_in_pdfa);
+                }
+
+                egui::ComboBox::from_id_source("paper_size").selected_text(self.paper_size.label(text)).show_ui(ui, |ui| {
+                    for paper in [PaperSize::Letter, PaperSize::Legal, PaperSize::A4] {
+                        ui.selectable_value(&mut self.paper_size, paper, paper.label(text));
+                    }
+                }).response.on_hover_text(text.paper_size_hover);
+
+                let dpi_label = |dpi: Option<u32>| dpi.map_or_else(|| String::from(text.output_full_resolution), |dpi| format!("{dpi} DPI"));
+                egui::ComboBox::from_id_source("output_dpi").selected_text(dpi_label(self.output_dpi)).show_ui(ui, |ui| {
+                    for dpi in [None].into_iter().chain(OUTPUT_DPI_CHOICES.map(Some)) {
+                        ui.selectable_value(&mut self.output_dpi, dpi, dpi_label(dpi));
+                    }
+                }).response.on_hover_text(text.output_resolution_hover);
+
+                ui.checkbox(&mut self.page_split.enabled, text.split_pages)
+                    .on_hover_text(text.split_pages_hover);
+                ui.add_enabled(self.page_split.enabled, egui::DragValue::new(&mut self.page_split.interval_mm)
+                    .clamp_range(10.0..=2000.0).suffix(" mm"));
+
+                let partial_pages = self.partial_page_indices();
+                ui.add_enabled_ui(!partial_pages.is_empty() && self.scan_status == ScanStatus::Stopped, |ui| {
+                    if ui.button(fill(text.remove_partial, partial_pages.len()))
+                        .on_hover_text(text.remove_partial_hover)
+                        .clicked() {
+                        self.remove_partial_pages();
+                    }
+                });
+            });
+        });
+    }
+
+    fn draw_staging_tray(&mut self, ctx: &Context) {
+        if self.staged_pages.is_empty() {
+            return;
+        }
+
+        let text = self.settings.language.strings();
+        let mut save = false;
+        let mut clear = false;
+        let mut removed_page = None;
+        egui::TopBottomPanel::bottom("MainUI-StagingTray").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(fill(text.staging_tray, self.staged_pages.len()));
+                save = ui.button(text.save_staged).on_hover_text(text.save_staged_hover).clicked();
+                clear = ui.button(text.clear_tray).clicked();
+            });
+
+            egui::ScrollArea::horizontal().show(ui, |ui| {
+                ui.horizontal(|ui| {
+                    for (i, page) in self.staged_pages.iter().enumerate() {
+                        let response = ui.add(egui::Image::new(&page.texture_handle).uv(page.flip.uv())
+                            .fit_to_exact_size(scale_image_size(page.texture_handle.size_vec2(), STAGED_THUMBNAIL_WIDTH))
+                            .sense(Sense::click()));
+                        paint_annotations(ui.painter(), response.rect, &page.annotations);
+
+                        response.on_hover_text_at_pointer(fill(text.page_number, i + 1)).context_menu(|ui| {
+                            if ui.button(text.remove_from_tray).clicked() {
+                                removed_page = Some(i);
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                });
+            });
+        });
+
+        if let Some(index) = removed_page {
+            self.staged_pages.remove(index);
+        }
+        if clear {
+            self.clear_staged_pages();
+        }
+        if save {
+            self.save_pdf(ctx, PageSource::Staged, false);
+        }
+    }
+
+    fn draw_center_panel(&mut self, ctx: &Context) {
+        let mut clicked_page: Option<usize> = None;
+        let mut page_action: Option<(usize, PageAction)> = None;
+        let mut thumbnail_rects: Vec<(usize, Rect)> = Vec::new();
+        let mut marquee: Option<Rect> = None;
+        let partial_pages = self.partial_page_indices();
+        let page_count = self.scanned_images.len();
+        let can_rescan = self.selected_handle.is_some() && self.scan_status == ScanStatus::Stopped && !self.device_busy();
+        let pages_locked = self.pages_locked();
+        let visible_pages: Vec<bool> = self.scanned_images.iter().map(|image| self.is_page_visible(image)).collect();
+        let hidden_pages = visible_pages.iter().filter(|visible| !**visible).count();
+        let text = self.settings.language.strings();
+
+        egui::CentralPanel::default().show(ctx, |ui| {
+            // Thumbnails only sense clicks, so drags anywhere in the panel reach this
+            let background = ui.interact(ui.max_rect(), ui.id().with("marquee"), Sense::drag());
+
+            // Saved pages otherwise seem to disappear
+            if hidden_pages > 0 {
+                ui.horizontal(|ui| {
+                    ui.label(fill(text.saved_pages_hidden, hidden_pages));
+                    if ui.button(text.show_saved).clicked() {
+                        self.show_saved_images = true;
+                    }
+                });
+            }
+
+            // Pinned columns are laid out in a grid, whose rows never wrap, so they scroll sideways when wider than the window
+            let columns = self.thumbnail_columns;
+            let scroll_area = if columns.is_some() { egui::ScrollArea::both() } else { egui::ScrollArea::vertical() };
+            scroll_area.drag_to_scroll(false).show(ui, |ui| {
+                let draw_thumbnails = |ui: &mut egui::Ui| {
+                    let mut shown = 0;
+                    for (i, image) in self.scanned_images.iter_mut().enumerate() {
+                        if !visible_pages[i] {
+                            continue;
+                        }
+
+                        let mut hover_text = if let Some(page) = image.selected_as_page {fill(text.page_number, page+1)} else {fill(text.selecting_page, self.pages_selected+1)};
+                        if !image.note.trim().is_empty() {
+                            hover_text += &format!("\n{}", fill(text.page_note, image.note.trim()));
+                        }
+                        let (_, saved_bytes) = estimate_image_bytes(output_size(image.size, self.output_dpi, self.paper_size), image.format, self.settings.pdf_compression);
+                        hover_text += &format!("\n{} × {} px, {}", image.size[0], image.size[1], format_byte_size(saved_bytes));
+                        #[allow(clippy::cast_precision_loss)]
+                        let output_dpi = self.output_dpi.map_or(page_dpi(image.size, self.paper_size), |dpi| page_dpi(image.size, self.paper_size).min(dpi as f32));
+                        hover_text += &format!("\n{}", fill(text.output_dpi, output_dpi.round()));
+                        if image.dewarp.is_some() {
+                            hover_text += &format!("\n{}", text.dewarp_applied);
+                        }
+                        if !image.annotations.is_empty() {
+                            hover_text += &format!("\n{}", text.annotated);
+                        }
+                        if !image.flip.is_identity() {
+                            hover_text += &format!("\n{}", text.flipped);
+                        }
+                        if partial_pages.contains(&i) {
+                            hover_text += &format!("\n{}", text.partial_page_warning);
+                        }
+
+                        let response = ui.add(egui::Image::new(&image.texture_handle).uv(image.flip.uv())
+                            .fit_to_exact_size(scale_image_size(image.texture_handle.size_vec2(), self.image_max_x))
+                            .show_loading_spinner(true)
+                            .tint(if let Some(n) = image.selected_as_page {selection_tint_color(n, self.pages_selected, self.settings.selection_color, self.settings.selection_alpha)} else {Color32::WHITE})
+                            .sense(Sense::click()));
+
+                        thumbnail_rects.push((i, response.rect));
+
+                        shown += 1;
+                        if columns.is_some_and(|columns| shown % columns == 0) {
+                            ui.end_row();
+                        }
+
+                        if self.scroll_to_page == Some(i) {
+                            response.scroll_to_me(Some(egui::Align::Center));
+                            response.request_focus();
+                        }
+
+                        if partial_pages.contains(&i) {
+                            ui.painter().rect_stroke(response.rect, 0.0, Stroke::new(2.0, Color32::RED));
+                        }
+
+                        paint_annotations(ui.painter(), response.rect, &image.annotations);
+
+                        for row in &image.page_breaks {
+                            #[allow(clippy::cast_precision_loss)]
+                            let y = response.rect.top() + response.rect.height() * *row as f32 / image.size[1] as f32;
+                            ui.painter().hline(response.rect.x_range(), y, Stroke::new(2.0, Color32::RED));
+                        }
+
+                        if image.page_breaks.is_empty() && self.page_split.enabled {
+                            #[allow(clippy::cast_precision_loss)]
+                            let line_spacing = response.rect.height() * self.page_split.rows_per_segment() as f32 / image.size[1] as f32;
+                            let mut y = response.rect.top() + line_spacing;
+                            while y < response.rect.bottom() {
+                                ui.painter().hline(response.rect.x_range(), y, Stroke::new(1.0, Color32::GOLD));
+                                y += line_spacing;
+                            }
+                        }
+
+                        let response = if self.zoom_on_hover {
+                            let zoom_size = fit_image_size(image.texture_handle.size_vec2(),
+                                Vec2::new(ui.spacing().tooltip_width, ctx.screen_rect().height() * ZOOM_PREVIEW_MAX_HEIGHT));
+                            response.on_hover_ui_at_pointer(|ui| {
+                                ui.label(hover_text);
+                                ui.add(egui::Image::new(&image.texture_handle).uv(image.flip.uv()).fit_to_exact_size(zoom_size));
+                            })
+                        } else {
+                            response.on_hover_text_at_pointer(hover_text)
+                        };
+                        // Actions that affect more than this page are carried out after the grid is drawn
+                        response.context_menu(|ui| {
+                            ui.label(text.note_editor);
+                            ui.text_edit_multiline(&mut image.note);
+                            if ui.button(text.done).clicked() {
+                                ui.close_menu();
+                            }
+
+                            ui.separator();
+                            let actions = [
+                                (PageAction::InsertPageBreaks, text.insert_page_breaks, text.insert_page_breaks_hover),
+                                (PageAction::Annotate, text.annotate, text.annotate_hover),
+                                (PageAction::Duplicate, text.duplicate_page, text.duplicate_page_hover),
+                                (PageAction::MergeWithNext, text.merge_with_next, text.merge_with_next_hover),
+                                (PageAction::AddToTray, text.add_page_to_tray, text.add_page_to_tray_hover),
+                                (PageAction::Rescan, text.rescan_page, text.rescan_page_hover),
+                                (PageAction::Delete, text.delete_page, text.delete_page_hover),
+                            ];
+                            for (action, label, hover) in actions {
+                                let available = match action {
+                                    PageAction::MergeWithNext => !pages_locked && i + 1 < page_count,
+                                    PageAction::Duplicate | PageAction::Delete => !pages_locked,
+                                    // Only pages in the selection have a slot worth keeping
+                                    PageAction::Rescan => can_rescan && image.selected_as_page.is_some(),
+                                    _ => true,
+                                };
+                                let button = ui.add_enabled(available, egui::Button::new(label)).on_hover_text(hover);
+                                let button = if pages_locked { button.on_disabled_hover_text(text.pages_locked_hover) } else { button };
+                                if button.clicked() {
+                                    page_action = Some((i, action));
+                                    ui.close_menu();
+                                }
+                            }
+
+                            ui.separator();
+                            ui.checkbox(&mut image.flip.horizontal, text.flip_horizontal);
+                            ui.checkbox(&mut image.flip.vertical, text.flip_vertical);
+
+                            ui.separator();
+                            let mut dewarp_enabled = image.dewarp.is_some();
+                            if ui.checkbox(&mut dewarp_enabled, text.dewarp).on_hover_text(text.dewarp_hover).changed() {
+                                image.dewarp = dewarp_enabled.then(Dewarp::default);
+                            }
+                            if let Some(dewarp) = &mut image.dewarp {
+                                ui.horizontal(|ui| {
+                                    ui.label(text.spine_side);
+                                    ui.selectable_value(&mut dewarp.spine, SpineSide::Left, text.spine_left);
+                                    ui.selectable_value(&mut dewarp.spine, SpineSide::Right, text.spine_right);
+                                });
+                                ui.add(egui::Slider::new(&mut dewarp.strength, 0.0..=0.3).text(text.dewarp_strength));
+                                ui.add(egui::Slider::new(&mut dewarp.width, 0.05..=0.5).text(text.dewarp_width));
+                            }
+                        });
+
+                        if response.clicked() {
+                            clicked_page = Some(i);
+
+                            if let Some(resp) = &self.path_field {
+                                resp.request_focus();
+                            }
+                        };
+                    }
+                };
+
+                if columns.is_some() {
+                    egui::Grid::new("thumbnail_grid").show(ui, draw_thumbnails);
+                } else {
+                    ui.horizontal_wrapped(draw_thumbnails);
+                }
+            });
+
+            if let (true, Some(pos)) = (background.drag_started(), background.interact_pointer_pos()) {
+                self.marquee_origin = Some(pos);
+            }
+            if let (Some(origin), Some(pos)) = (self.marquee_origin, ctx.pointer_latest_pos()) {
+                let rect = Rect::from_two_pos(origin, pos);
+                let selection = ui.visuals().selection;
+                ui.painter().rect(rect, 0.0, selection.bg_fill.gamma_multiply(0.3), selection.stroke);
+                if background.drag_stopped() {
+                    marquee = Some(rect);
+                }
+            }
+            if background.drag_stopped() {
+                self.marquee_origin = None;
+            }
+
+            // egui reports Ctrl+scroll as zooming rather than scrolling
+            let zoom = ctx.input(|i| i.zoom_delta());
+            if zoom != 1.0 && ui.ui_contains_pointer() {
+                self.image_max_x = (self.image_max_x * zoom).clamp(*THUMBNAIL_SIZE_RANGE.start(), *THUMBNAIL_SIZE_RANGE.end());
+                self.thumbnail_zoomed_at = Some(Instant::now());
+                ctx.request_repaint_after(THUMBNAIL_ZOOM_SETTLE_TIME);
+            }
+        });
+        self.scroll_to_page = None;
+
+        // Thumbnails are laid out in rows, so this selects in row-major order
+        if let Some(marquee) = marquee {
+            for (index, _) in thumbnail_rects.into_iter().filter(|(_, rect)| rect.intersects(marquee)) {
+                self.select_page(index);
+            }
+        }
+
+        if let Some(index) = clicked_page {
+            let modifiers = ctx.input(|i| i.modifiers);
+            if modifiers.shift {
+                self.select_range_to(index);
+            } else if modifiers.command {
+                self.toggle_page(index);
+            } else if let Some(position) = self.scanned_images[index].selected_as_page {
+                self.clear_selection_from(position);
+            } else {
+                self.select_page(index);
+            }
+        }
+
+        // Last, as it may shift page indices
+        if let Some((index, action)) = page_action {
+            self.apply_page_action(index, action);
+        }
+    }
+
+    fn show_config_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        egui::Window::new(text.config_title).id(egui::Id::new("config_window")).default_size([680.0, 500.0]).show(ctx, |ui| {
+            egui::TopBottomPanel::bottom("close_panel")
+            .resizable(false)
+            .show_inside(ui, |ui| {
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button(text.close).clicked() {
+                        self.dialog_status.config = false;
+                        self.dialog_status.common_vals = false;
+                    }
+
+                    if ui.add_enabled(!self.device_busy(), egui::Button::new(text.apply)).clicked() {
+                        self.apply_config_changes();
+                    }
+
+                    if self.device_busy() {
+                        ui.label(text.device_busy);
+                        ui.spinner();
+                    }
+
+                    if ui.button(text.common_values).clicked() {
+                        self.dialog_status.common_vals = !self.dialog_status.common_vals;
+                    }
+
+                    let mut swap_red_blue = self.channel_order() == ChannelOrder::Bgr;
+                    if ui.checkbox(&mut swap_red_blue, text.swap_red_blue).on_hover_text(text.swap_red_blue_hover).changed() {
+                        self.set_channel_order(if swap_red_blue { ChannelOrder::Bgr } else { ChannelOrder::Rgb });
+                    }
+                });
+            });
+
+            egui::CentralPanel::default().show_inside(ui, |ui| {
+                egui::ScrollArea::both().show(ui, |ui| {
+                    egui::Grid::new("device_config").striped(true).max_col_width(160.0).show(ui, |ui| {
+                        let text_markers = self.settings.text_state_markers;
+                        for option in &mut self.config_options {
+
+                            if let ValueType::Group = option.base_option.type_ {
+                                // Group titles get a special label and no controls (column 1)
+                                let group_title = cstring_to_string(&option.base_option.title, "group title");
+                                ui.colored_label(Color32::LIGHT_BLUE,
+                                    if text_markers { format!("{} {group_title}", text.group_marker) } else { group_title });
+                            } else {
+                                // Draw the option item's label (column 1)
+                                let option_title = cstring_to_string(&option.base_option.title, "option title");
+                                let label_row = ui.horizontal(|ui| {
+                                    ui.label(option_title).on_hover_text(cstring_to_string(&option.base_option.desc, "option description"));
+
+                                    if option.activity_changed {
+                                        let (marker, hover) = if option.base_option.cap.contains(OptionCapability::INACTIVE) {
+                                            (text.now_inactive, text.now_inactive_hover)
+                                        } else {
+                                            (text.now_active, text.now_active_hover)
+                                        };
+                                        ui.colored_label(Color32::LIGHT_GREEN, marker).on_hover_text(hover);
+                                    }
+                                });
+
+                                if self.config_scroll_target == Some(option.base_option.option_idx) {
+                                    label_row.response.scroll_to_me(Some(egui::Align::Center));
+                                    label_row.response.highlight();
+                                    self.config_scroll_target = None;
+                                }
+                            }
+
+                            // Draw the option value controls (column 2)
+                            ui.add_enabled_ui(option.base_option.cap.contains(OptionCapability::SOFT_SELECT), |ui| {
+                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
+                                    render_device_option_controls(ui, option, text_markers, text);
+                                }).response.on_disabled_hover_text(text.hardware_only_hover);
+                            });
+
+                            ui.end_row();
+                        }
+                    });
+                });
+            });
+        });
+    }
+
+    fn show_page_breaks_window(&mut self, ctx: &Context, index: usize) {
+        let Some(image) = self.scanned_images.get_mut(index) else {
+            self.dialog_status.page_breaks = None;
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        egui::Window::new(text.page_breaks_title).id(egui::Id::new("page_breaks_window")).default_size([600.0, 700.0]).show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(fill(text.page_breaks_help, image.page_breaks.len() + 1));
+
+                if ui.button(text.clear_breaks).clicked() {
+                    image.page_breaks.clear();
+                }
+
+                if ui.button(text.done).clicked() {
+                    self.dialog_status.page_breaks = None;
+                }
+            });
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let response = ui.add(egui::Image::new(&image.texture_handle).uv(image.flip.uv())
+                    .fit_to_exact_size(scale_image_size(image.texture_handle.size_vec2(), ui.available_width()))
+                    .sense(Sense::click()));
+
+                #[allow(clippy::cast_precision_loss)]
+                let rows_per_point = image.size[1] as f32 / response.rect.height();
+
+                for row in &image.page_breaks {
+                    #[allow(clippy::cast_precision_loss)]
+                    let y = response.rect.top() + *row as f32 / rows_per_point;
+                    ui.painter().hline(response.rect.x_range(), y, Stroke::new(2.0, Color32::RED));
+                }
+
+                if let (true, Some(pos)) = (response.clicked(), response.interact_pointer_pos()) {
+                    #[allow(clippy::cast_possible_truncation)]
+                    #[allow(clippy::cast_sign_loss)]
+                    let clicked_row = ((pos.y - response.rect.top()) * rows_per_point) as usize;
+
+                    #[allow(clippy::cast_precision_loss)]
+                    let near_break = image.page_breaks.iter()
+                        .position(|row| (*row as f32 - clicked_row as f32).abs() / rows_per_point < PAGE_BREAK_HIT_DISTANCE);
+
+                    if let Some(existing) = near_break {
+                        image.page_breaks.remove(existing);
+                    } else if clicked_row > 0 && clicked_row < image.size[1] {
+                        image.page_breaks.push(clicked_row);
+                        image.page_breaks.sort_unstable();
+                    }
+                }
+            });
+        });
+    }
+
+    fn show_merge_window(&mut self, ctx: &Context, index: usize) {
+        let (Some(first), Some(second)) = (self.scanned_images.get(index), self.scanned_images.get(index + 1)) else {
+            self.dialog_status.merge = None;
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        let pages_locked = self.pages_locked();
+        let mut confirmed = false;
+        let mut open = true;
+        egui::Window::new(text.merge_title).id(egui::Id::new("merge_window")).open(&mut open).default_size([500.0, 600.0]).show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.selectable_value(&mut self.merge_options.direction, MergeDirection::SideBySide, text.merge_side_by_side);
+                ui.selectable_value(&mut self.merge_options.direction, MergeDirection::Stacked, text.merge_stacked);
+                ui.checkbox(&mut self.merge_options.scale_to_match, text.merge_scale_to_match)
+                    .on_hover_text(text.merge_scale_to_match_hover);
+            });
+            ui.label(text.merge_note);
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!pages_locked, egui::Button::new(text.merge_pages))
+                    .on_disabled_hover_text(text.pages_locked_hover)
+                    .clicked() {
+                    confirmed = true;
+                }
+                if ui.button(text.cancel).clicked() {
+                    self.dialog_status.merge = None;
+                }
+            });
+            ui.separator();
+
+            // Preview from the page textures, placed as the merged pixels will be
+            let layout = MergeLayout::new(first.size, second.size, self.merge_options.direction, self.merge_options.scale_to_match);
+            #[allow(clippy::cast_precision_loss)]
+            let to_points = |size: [usize; 2]| Vec2::new(size[0] as f32, size[1] as f32);
+            let preview_size = fit_image_size(to_points(layout.size), ui.available_size());
+            let (response, painter) = ui.allocate_painter(preview_size, Sense::hover());
+            let scale = preview_size.x / to_points(layout.size).x;
+            painter.rect_filled(response.rect, 0.0, Color32::WHITE);
+            for (image, size, offset) in [(first, first.size, [0, 0]), (second, layout.second_size, layout.second_offset)] {
+                let rect = Rect::from_min_size(response.rect.min + to_points(offset) * scale, to_points(size) * scale);
+                painter.image(image.texture_handle.id(), rect, image.flip.uv(), Color32::WHITE);
+                paint_annotations(&painter, rect, &image.annotations);
+            }
+        });
+
+        if confirmed {
+            if let Err(error) = self.merge_with_next(ctx, index) {
+                self.report_error(text.error_merging_pages, &error);
+            }
+            self.dialog_status.merge = None;
+        }
+        if !open {
+            self.dialog_status.merge = None;
+        }
+    }
+
+    fn show_print_window(&mut self, ctx: &Context, indices: &[usize]) {
+        let text = self.settings.language.strings();
+        let mut confirmed = false;
+        let mut open = true;
+        egui::Window::new(text.print_title).id(egui::Id::new("print_window")).open(&mut open).resizable(false).show(ctx, |ui| {
+            ui.label(fill(text.print_page_count, indices.len()));
+
+            egui::Grid::new("print_options").num_columns(2).show(ui, |ui| {
+                ui.label(text.printer);
+                egui::ComboBox::from_id_source("printer").selected_text(&self.print_options.printer).show_ui(ui, |ui| {
+                    for printer in &self.printers {
+                        ui.selectable_value(&mut self.print_options.printer, printer.clone(), printer);
+                    }
+                });
+                ui.end_row();
+
+                ui.label(text.copies);
+                ui.add(egui::DragValue::new(&mut self.print_options.copies).clamp_range(1..=99));
+                ui.end_row();
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button(text.print).clicked() {
+                    confirmed = true;
+                }
+                if ui.button(text.cancel).clicked() {
+                    self.dialog_status.print = None;
+                }
+            });
+        });
+
+        if confirmed {
+            // The window stays open after a failure so printing can be retried
+            match self.print_pages(indices) {
+                Ok(()) => self.dialog_status.print = None,
+                Err(error) => self.report_error(text.error_printing, &error),
+            }
+        }
+        if !open {
+            self.dialog_status.print = None;
+        }
+    }
+
+    fn show_pdf_preview_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let watermark = (self.settings.watermark.enabled && self.output_format == OutputFormat::Pdf).then_some(&self.settings.watermark);
+        let mut open = true;
+        egui::Window::new(text.preview_layout_title).id(egui::Id::new("pdf_preview_window")).open(&mut open).default_size([600.0, 600.0]).show(ctx, |ui| {
+            ui.label(text.preview_layout_note);
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                ui.horizontal_wrapped(|ui| {
+                    let mut page_number = 0;
+                    for index in self.ordered_page_indices() {
+                        let Some(image) = self.scanned_images.get(index) else { continue };
+                        let breaks = self.output_breaks(image);
+                        let segments = segment_rows(image.size[1], breaks.as_deref().unwrap_or_default());
+                        let watermark_text = watermark.map(|watermark| watermark.text(image.scanned_at));
+
+                        for rows in segments {
+                            page_number += 1;
+                            ui.vertical(|ui| {
+                                ui.label(fill(text.page_number, page_number));
+                                let (paper_width, paper_height) = self.paper_size.size_mm();
+                                let page_size = Vec2::new(PDF_PREVIEW_PAGE_WIDTH, PDF_PREVIEW_PAGE_WIDTH * paper_height / paper_width);
+                                let (response, painter) = ui.allocate_painter(page_size, Sense::hover());
+                                paint_page_layout(&painter.with_clip_rect(response.rect), response.rect, image, rows, breaks.is_some(), self.paper_size, self.settings.page_margins);
+
+                                if let (Some(watermark), Some(watermark_text)) = (watermark, &watermark_text) {
+                                    paint_watermark(&painter, response.rect, watermark, watermark_text, self.paper_size);
+                                }
+                            });
+                        }
+                    }
+                });
+            });
+        });
+
+        if !open || self.selected_page_indices.is_empty() {
+            self.dialog_status.pdf_preview = false;
+        }
+    }
+
+    fn show_selection_order_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let mut moved = None;
+        let mut open = true;
+        egui::Window::new(text.selection_order_title).id(egui::Id::new("selection_order_window")).open(&mut open).default_height(400.0).show(ctx, |ui| {
+            ui.label(text.selection_order_note);
+            ui.separator();
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let count = self.selected_page_indices.len();
+                for (position, &index) in self.selected_page_indices.iter().enumerate() {
+                    let image = &self.scanned_images[index];
+                    ui.horizontal(|ui| {
+                        ui.label(fill(text.page_number, position + 1));
+                        ui.add(egui::Image::new(&image.texture_handle).uv(image.flip.uv())
+                            .fit_to_exact_size(scale_image_size(image.texture_handle.size_vec2(), SELECTION_ORDER_THUMBNAIL_WIDTH)));
+
+                        if ui.add_enabled(position > 0, egui::Button::new("⏶")).on_hover_text(text.move_up).clicked() {
+                            moved = Some((position, position - 1));
+                        }
+                        if ui.add_enabled(position + 1 < count, egui::Button::new("⏷")).on_hover_text(text.move_down).clicked() {
+                            moved = Some((position, position + 1));
+                        }
+                    });
+                }
+            });
+        });
+
+        if let Some((from, to)) = moved {
+            self.move_selected_page(from, to);
+        }
+        if !open || self.selected_page_indices.len() < 2 {
+            self.dialog_status.selection_order = false;
+        }
+    }
+
+    fn show_annotation_window(&mut self, ctx: &Context, index: usize) {
+        let Some(image) = self.scanned_images.get_mut(index) else {
+            self.dialog_status.annotate = None;
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        egui::Window::new(text.annotate_title).id(egui::Id::new("annotation_window")).default_size([600.0, 700.0]).show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(text.annotate_help);
+                ui.selectable_value(&mut self.annotation_tool, AnnotationTool::Pen, text.pen);
+                ui.selectable_value(&mut self.annotation_tool, AnnotationTool::Highlighter, text.highlighter);
+
+                ui.add_enabled_ui(!image.annotations.is_empty(), |ui| {
+                    if ui.button(text.undo).clicked() {
+                        image.annotations.pop();
+                    }
+                    if ui.button(text.clear_annotations).clicked() {
+                        image.annotations.clear();
+                    }
+                });
+
+                if ui.button(text.done).clicked() {
+                    self.dialog_status.annotate = None;
+                }
+            });
+
+            egui::ScrollArea::vertical().drag_to_scroll(false).show(ui, |ui| {
+                let response = ui.add(egui::Image::new(&image.texture_handle).uv(image.flip.uv())
+                    .fit_to_exact_size(scale_image_size(image.texture_handle.size_vec2(), ui.available_width()))
+                    .sense(Sense::drag()));
+
+                if let Some(pos) = response.interact_pointer_pos() {
+                    let point = ((pos - response.rect.min) / response.rect.size()).clamp(Vec2::ZERO, Vec2::splat(1.0));
+                    if response.drag_started() {
+                        image.annotations.push(Annotation { tool: self.annotation_tool, points: vec![[point.x, point.y]] });
+                    } else if response.dragged() {
+                        if let Some(annotation) = image.annotations.last_mut() {
+                            annotation.points.push([point.x, point.y]);
+                        }
+                    }
+                }
+
+                paint_annotations(ui.painter(), response.rect, &image.annotations);
+            });
+        });
+    }
+
+    fn show_scan_area_window(&mut self, ctx: &Context) {
+        // Every button changes the device's scan area, closing included, so none can be used while the device is working
+        let device_free = self.scan_status == ScanStatus::Stopped && !self.device_busy();
+        let Some(prescan) = &mut self.prescan else {
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        let mut chosen_region = None;
+        let mut open = true;
+        egui::Window::new(text.scan_area_title).id(egui::Id::new("scan_area_window")).default_size([500.0, 650.0]).show(ctx, |ui| {
+            ui.horizontal_wrapped(|ui| {
+                ui.label(text.scan_area_help);
+
+                ui.add_enabled_ui(device_free, |ui| {
+                    ui.add_enabled_ui(prescan.region.is_some(), |ui| {
+                        if ui.button(text.use_selected_area).clicked() {
+                            chosen_region = prescan.region.map(|region| [region.min.x, region.min.y, region.max.x, region.max.y].map(f64::from));
+                        }
+                    });
+
+                    if ui.button(text.use_whole_bed).clicked() {
+                        prescan.region = None;
+                        chosen_region = Some(FULL_SCAN_AREA);
+                    }
+
+                    if ui.button(text.close).clicked() {
+                        open = false;
+                    }
+                });
+                if !device_free {
+                    ui.weak(text.device_busy);
+                }
+            });
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                let response = ui.add(egui::Image::new(&prescan.texture_handle)
+                    .fit_to_exact_size(scale_image_size(prescan.texture_handle.size_vec2(), ui.available_width()))
+                    .sense(Sense::drag()));
+
+                // Positions are kept as fractions of the prescan, which covers the whole bed
+                let to_fraction = |pos: Pos2| ((pos - response.rect.min) / response.rect.size()).clamp(Vec2::ZERO, Vec2::splat(1.0)).to_pos2();
+
+                if let (true, Some(pos)) = (response.drag_started(), response.interact_pointer_pos()) {
+                    prescan.drag_origin = Some(to_fraction(pos));
+                }
+                if let (Some(origin), Some(pos)) = (prescan.drag_origin, response.interact_pointer_pos()) {
+                    if response.dragged() {
+                        prescan.region = Some(Rect::from_two_pos(origin, to_fraction(pos)));
+                    }
+                }
+                if response.drag_stopped() {
+                    prescan.drag_origin = None;
+                }
+
+                if let Some(region) = prescan.region {
+                    let on_screen = Rect::from_min_max(
+                        response.rect.min + region.min.to_vec2() * response.rect.size(),
+                        response.rect.min + region.max.to_vec2() * response.rect.size(),
+                    );
+                    ui.painter().rect_stroke(on_screen, 0.0, Stroke::new(2.0, Color32::RED));
+                }
+            });
+        });
+
+        if let Some(region) = chosen_region {
+            prescan.previous_area = None;
+            self.apply_scan_area(region);
+        }
+        // Closing without picking an area puts back the one the prescan replaced
+        if !open {
+            if let Some(area) = self.prescan.take().and_then(|prescan| prescan.previous_area) {
+                self.apply_scan_area(area);
+            }
+        }
+    }
+
+    fn show_scan_area_diagram_window(&mut self, ctx: &Context) {
+        let device_free = self.scan_status == ScanStatus::Stopped && !self.device_busy();
+        let Some(diagram) = &mut self.scan_area_diagram else {
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        let mut chosen_region = None;
+        let mut open = true;
+        egui::Window::new(text.scan_area_diagram_title).id(egui::Id::new("scan_area_diagram_window")).resizable(false).show(ctx, |ui| {
+            ui.label(text.scan_area_diagram_help);
+            if !device_free {
+                ui.weak(text.device_busy);
+                diagram.drag = None;
+            }
+
+            let size = Vec2::new(SCAN_AREA_DIAGRAM_WIDTH, SCAN_AREA_DIAGRAM_WIDTH * diagram.bed_size.y / diagram.bed_size.x.max(f32::EPSILON));
+            let (response, painter) = ui.allocate_painter(size, if device_free { Sense::drag() } else { Sense::hover() });
+            let bed = response.rect;
+            let to_fraction = |pos: Pos2| ((pos - bed.min) / bed.size()).clamp(Vec2::ZERO, Vec2::splat(1.0)).to_pos2();
+            let to_screen = |fraction: Pos2| bed.min + fraction.to_vec2() * bed.size();
+
+            if let (true, Some(pos)) = (response.drag_started(), response.interact_pointer_pos()) {
+                let region = diagram.region;
+                // A corner is grabbed by its handle, and resizing keeps the opposite corner in place
+                let corners = [region.left_top(), region.right_top(), region.left_bottom(), region.right_bottom()];
+                diagram.drag = Some(match corners.iter().position(|corner| to_screen(*corner).distance(pos) <= SCAN_AREA_HANDLE_RADIUS) {
+                    Some(corner) => ScanAreaDrag::Resize { anchor: corners[3 - corner] },
+                    None if region.contains(to_fraction(pos)) => ScanAreaDrag::Move { grab_offset: to_fraction(pos) - region.min },
+                    None => ScanAreaDrag::Draw { origin: to_fraction(pos) },
+                });
+            }
+            if let (Some(drag), Some(pos), true) = (diagram.drag, response.interact_pointer_pos(), response.dragged()) {
+                let pos = to_fraction(pos);
+                diagram.region = match drag {
+                    ScanAreaDrag::Resize { anchor } => Rect::from_two_pos(anchor, pos),
+                    ScanAreaDrag::Move { grab_offset } => {
+                        let size = diagram.region.size();
+                        Rect::from_min_size((pos - grab_offset).clamp(Pos2::ZERO, (Vec2::splat(1.0) - size).to_pos2()), size)
+                    },
+                    ScanAreaDrag::Draw { origin } => Rect::from_two_pos(origin, pos),
+                };
+            }
+            if response.drag_stopped() && diagram.drag.take().is_some() {
+                let region = diagram.region;
+                chosen_region = Some([region.min.x, region.min.y, region.max.x, region.max.y].map(f64::from));
+            }
+
+            let on_screen = Rect::from_min_max(to_screen(diagram.region.min), to_screen(diagram.region.max));
+            painter.rect_filled(bed, 0.0, Color32::from_gray(60));
+            painter.rect(on_screen, 0.0, Color32::from_rgba_unmultiplied(255, 255, 255, 40), Stroke::new(2.0, Color32::LIGHT_BLUE));
+            for corner in [on_screen.left_top(), on_screen.right_top(), on_screen.left_bottom(), on_screen.right_bottom()] {
+                painter.circle_filled(corner, SCAN_AREA_HANDLE_RADIUS / 2.0, Color32::LIGHT_BLUE);
+            }
+
+            let area_size = diagram.region.size() * diagram.bed_size;
+            ui.label(fill(&fill(text.scan_area_size, format!("{:.1}", area_size.x)), format!("{:.1}", area_size.y)));
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(device_free, egui::Button::new(text.use_whole_bed)).clicked() {
+                    chosen_region = Some(FULL_SCAN_AREA);
+                }
+                if ui.button(text.close).clicked() {
+                    open = false;
+                }
+            });
+        });
+
+        if !open {
+            self.scan_area_diagram = None;
+        }
+        if let Some(region) = chosen_region {
+            self.apply_scan_area(region);
+        }
+    }
+
+    /// Queues an error for the error window. Reports go through the same channel as the background threads' ones.
+    fn report_error(&self, context: &str, error: &(impl Display + Debug + ?Sized)) {
+        let _ = self.error_sender.send(ErrorReport::new(context, error));
+        self.ui_context.request_repaint();
+    }
+
+    fn receive_error_reports(&mut self) {
+        while let Ok(report) = self.error_receiver.try_recv() {
+            self.error_reports.push(report);
+        }
+    }
+
+    fn show_error_window(&mut self, ctx: &Context) {
+        let Some(report) = self.error_reports.first() else {
+            return;
+        };
+
+        let text = self.settings.language.strings();
+        let mut dismissed = false;
+        let mut reset = false;
+        egui::Window::new(text.error_title).id(egui::Id::new("error_window")).collapsible(false).resizable(false).show(ctx, |ui| {
+            ui.label(&report.summary);
+
+            CollapsingHeader::new(text.error_details).show(ui, |ui| {
+                egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    ui.label(egui::RichText::new(&report.details).monospace());
+                });
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button(text.copy).clicked() {
+                    ctx.output_mut(|output| output.copied_text = report.full_text());
+                }
+
+                if ui.button(text.ok).clicked() {
+                    dismissed = true;
+                }
+
+                if report.offer_device_reset && ui.button(text.reset_scanner).on_hover_text(text.reset_scanner_hover).clicked() {
+                    reset = true;
+                }
+
+                if self.error_reports.len() > 1 {
+                    ui.label(fill(text.more_errors, self.error_reports.len() - 1));
+                }
+            });
+        });
+
+        if dismissed || reset {
+            self.error_reports.remove(0);
+        }
+        if reset {
+            self.reset_device();
+        }
+    }
+
+    fn show_settings_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        egui::Window::new(text.settings_title).id(egui::Id::new("settings_window")).default_size([400.0, 300.0]).show(ctx, |ui| {
+            egui::Grid::new("settings").striped(true).show(ui, |ui| {
+                ui.label(text.language);
+                egui::ComboBox::from_id_source("language").selected_text(self.settings.language.as_str()).show_ui(ui, |ui| {
+                    for language in Language::ALL {
+                        ui.selectable_value(&mut self.settings.language, language, language.as_str());
+                    }
+                });
+                ui.end_row();
+
+                ui.label(text.file_exists);
+                egui::ComboBox::from_id_source("overwrite_behavior").selected_text(self.settings.overwrite_behavior.label(text)).show_ui(ui, |ui| {
+                    for behavior in [OverwriteBehavior::Prompt, OverwriteBehavior::Overwrite, OverwriteBehavior::AppendSuffix] {
+                        ui.selectable_value(&mut self.settings.overwrite_behavior, behavior, behavior.label(text));
+                    }
+                });
+                ui.end_row();
+
+                ui.label(text.save_shortcut);
+                egui::ComboBox::from_id_source("save_shortcut").selected_text(self.settings.save_shortcut.label(text)).show_ui(ui, |ui| {
+                    for shortcut in [SaveShortcut::CtrlEnter, SaveShortcut::Enter] {
+                        ui.selectable_value(&mut self.settings.save_shortcut, shortcut, shortcut.label(text));
+                    }
+                }).response.on_hover_text(text.save_shortcut_hover);
+                ui.end_row();
+
+                ui.label(text.selection_highlight);
+                ui.horizontal(|ui| {
+                    ui.color_edit_button_srgb(&mut self.settings.selection_color);
+                    ui.add(egui::Slider::new(&mut self.settings.selection_alpha, 0..=255).text(text.strength));
+                    if ui.button(text.reset).clicked() {
+                        let defaults = Settings::default();
+                        self.settings.selection_color = defaults.selection_color;
+                        self.settings.selection_alpha = defaults.selection_alpha;
+                    }
+                });
+                ui.end_row();
+
+                ui.label(text.page_data);
+                if ui.checkbox(&mut self.settings.spill_idle_pages, text.spill_pages)
+                    .on_hover_text(text.spill_pages_hover)
                     .changed() {
-                        self.open_selected_device();
-                    };
+                    self.apply_spill_setting();
+                }
+                ui.end_row();
+
+                ui.label(text.scanning);
+                ui.vertical(|ui| {
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.settings.warn_low_resolution, text.warn_low_resolution);
+                        ui.add_enabled(self.settings.warn_low_resolution, egui::DragValue::new(&mut self.settings.low_resolution_threshold)
+                            .clamp_range(50..=1200).suffix(" DPI"));
+                    }).response.on_hover_text(text.warn_low_resolution_hover);
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.settings.warn_large_scans, text.warn_large_scans);
+                        ui.add_enabled(self.settings.warn_large_scans, egui::DragValue::new(&mut self.settings.large_scan_limit_mb)
+                            .clamp_range(10..=10_000).speed(10.0).suffix(" MB"));
+                    }).response.on_hover_text(text.warn_large_scans_hover);
                 });
+                ui.end_row();
+
+                ui.label(text.device_settings);
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.settings.remember_device_settings, text.remember_device_settings);
+                    if ui.add_enabled(!self.settings.device_scan_settings.is_empty(), egui::Button::new(text.forget_device_settings)).clicked() {
+                        self.settings.device_scan_settings.clear();
+                    }
+                }).response.on_hover_text(text.remember_device_settings_hover);
+                ui.end_row();
 
-                ui.add_enabled_ui(self.selected_handle.is_some() && self.scan_status == ScanStatus::Stopped, |ui| {
-                    if ui.button("Configure scanner...").clicked() {
-                        self.dialog_status.config = true;
+                ui.label(text.virtual_scanner);
+                if ui.checkbox(&mut self.settings.test_pattern_scanner, text.test_pattern_scanner)
+                    .on_hover_text(text.test_pattern_scanner_hover)
+                    .changed() {
+                    // Closes the test pattern scanner if it is the one being removed from the list
+                    if self.scanner_list.get(self.selected_scanner).is_some_and(is_test_pattern_device) {
+                        self.disconnect_device();
+                    }
+                    self.refresh_devices();
+                }
+                ui.end_row();
 
-                        self.load_device_options();
+                let defaults = &mut self.settings.defaults;
+                ui.label(text.preferred_color_mode);
+                let preferred_text = defaults.color_mode.map_or(text.device_default_mode, |format| format.label(text));
+                egui::ComboBox::from_id_source("preferred_color_mode").selected_text(preferred_text).show_ui(ui, |ui| {
+                    ui.selectable_value(&mut defaults.color_mode, None, text.device_default_mode);
+                    for format in [PageFormat::Lineart, PageFormat::Gray, PageFormat::Color] {
+                        ui.selectable_value(&mut defaults.color_mode, Some(format), format.label(text));
                     }
+                }).response.on_hover_text(text.preferred_color_mode_hover);
+                ui.end_row();
 
-                    if ui.button("Start scanning").clicked() {
-                        self.start_scan();
+                // The rest apply from the next start, so they don't override choices made in the main window
+                ui.label(text.default_output);
+                ui.horizontal(|ui| {
+                    egui::ComboBox::from_id_source("default_paper_size").selected_text(defaults.paper_size.label(text)).show_ui(ui, |ui| {
+                        for paper in [PaperSize::Letter, PaperSize::Legal, PaperSize::A4] {
+                            ui.selectable_value(&mut defaults.paper_size, paper, paper.label(text));
+                        }
+                    });
+                    egui::ComboBox::from_id_source("default_output_format").selected_text(defaults.output_format.label(text)).show_ui(ui, |ui| {
+                        for format in [OutputFormat::Pdf, OutputFormat::PdfA] {
+                            ui.selectable_value(&mut defaults.output_format, format, format.label(text));
+                        }
+                    });
+                    let dpi_label = |dpi: Option<u32>| dpi.map_or_else(|| String::from(text.output_full_resolution), |dpi| format!("{dpi} DPI"));
+                    egui::ComboBox::from_id_source("default_output_dpi").selected_text(dpi_label(defaults.output_dpi)).show_ui(ui, |ui| {
+                        for dpi in [None].into_iter().chain(OUTPUT_DPI_CHOICES.map(Some)) {
+                            ui.selectable_value(&mut defaults.output_dpi, dpi, dpi_label(dpi));
+                        }
+                    });
+                    if ui.button(text.use_current_output).clicked() {
+                        defaults.paper_size = self.paper_size;
+                        defaults.output_format = self.output_format;
+                        defaults.output_dpi = self.output_dpi;
+                    }
+                }).response.on_hover_text(text.default_output_hover);
+                ui.end_row();
+
+                ui.label(text.document_feeder);
+                ui.horizontal(|ui| {
+                    ui.add(egui::DragValue::new(&mut self.settings.feed_retries).clamp_range(0..=10).suffix(text.feed_retries_suffix));
+                    ui.add(egui::DragValue::new(&mut self.settings.feed_retry_delay_ms).clamp_range(0..=10_000).speed(10.0).suffix(" ms"));
+                }).response.on_hover_text(text.feed_retries_hover);
+                ui.end_row();
+
+                ui.label(text.after_scanning);
+                ui.checkbox(&mut self.settings.auto_save_scans, text.auto_save_scans)
+                    .on_hover_text(text.auto_save_hover);
+                ui.end_row();
+
+                ui.label(text.while_scanning);
+                ui.checkbox(&mut self.settings.background_scanning, text.background_scanning)
+                    .on_hover_text(text.background_scanning_hover);
+                ui.end_row();
+
+                ui.label(text.image_smoothing);
+                egui::ComboBox::from_id_source("image_smoothing").selected_text(self.settings.image_smoothing.label(text)).show_ui(ui, |ui| {
+                    for smoothing in [ImageSmoothing::ColorOnly, ImageSmoothing::Always, ImageSmoothing::Never] {
+                        ui.selectable_value(&mut self.settings.image_smoothing, smoothing, smoothing.label(text));
+                    }
+                }).response.on_hover_text(text.image_smoothing_hover);
+                ui.end_row();
+
+                ui.label(text.pdf_compression);
+                egui::ComboBox::from_id_source("pdf_compression").selected_text(self.settings.pdf_compression.label(text)).show_ui(ui, |ui| {
+                    for compression in [PdfCompression::Off, PdfCompression::Fast, PdfCompression::Balanced, PdfCompression::Smallest] {
+                        ui.selectable_value(&mut self.settings.pdf_compression, compression, compression.label(text));
+                    }
+                }).response.on_hover_text(text.pdf_compression_hover);
+                ui.end_row();
+
+                ui.label(text.export_image_format);
+                egui::ComboBox::from_id_source("export_image_format").selected_text(self.settings.export_image_format.label(text)).show_ui(ui, |ui| {
+                    for format in [ImageFileFormat::Png, ImageFileFormat::Jpeg] {
+                        ui.selectable_value(&mut self.settings.export_image_format, format, format.label(text));
                     }
                 });
+                ui.end_row();
+
+                ui.label(text.file_name_field);
+                ui.checkbox(&mut self.settings.absolute_file_names, text.absolute_file_names)
+                    .on_hover_text(text.absolute_file_names_hover);
+                ui.end_row();
+
+                ui.label(text.after_saving);
+                ui.vertical(|ui| {
+                    ui.checkbox(&mut self.settings.copy_saved_path, text.auto_copy_path);
+                    ui.checkbox(&mut self.settings.keep_pages_after_save, text.keep_pages_after_save)
+                        .on_hover_text(text.keep_pages_after_save_hover);
+                });
+                ui.end_row();
+
+                ui.label(text.page_margins);
+                ui.horizontal(|ui| {
+                    let margins = &mut self.settings.page_margins;
+                    for (margin, label) in [(&mut margins.top, text.margin_top), (&mut margins.right, text.margin_right), (&mut margins.bottom, text.margin_bottom), (&mut margins.left, text.margin_left)] {
+                        ui.label(label);
+                        ui.add(egui::DragValue::new(margin).clamp_range(0.0..=MAX_PAGE_MARGIN_MM).speed(0.5).suffix(" mm"));
+                    }
+                }).response.on_hover_text(text.page_margins_hover);
+                ui.end_row();
+
+                ui.label(text.id_card_size);
+                ui.horizontal(|ui| {
+                    let card = &mut self.settings.id_card_size;
+                    ui.add(egui::DragValue::new(&mut card.width_mm).clamp_range(10.0..=MAX_ID_CARD_SIDE_MM).speed(0.5).suffix(" mm"));
+                    ui.label("×");
+                    ui.add(egui::DragValue::new(&mut card.height_mm).clamp_range(10.0..=MAX_ID_CARD_SIDE_MM).speed(0.5).suffix(" mm"));
+                    if ui.button(text.reset).clicked() {
+                        *card = IdCardSize::default();
+                    }
+                }).response.on_hover_text(text.id_card_size_hover);
+                ui.end_row();
+
+                ui.label(text.watermark);
+                ui.horizontal(|ui| {
+                    let watermark = &mut self.settings.watermark;
+                    ui.checkbox(&mut watermark.enabled, text.watermark_enabled);
+                    ui.add_enabled_ui(watermark.enabled, |ui| {
+                        ui.add(egui::TextEdit::singleline(&mut watermark.custom_text).hint_text(text.watermark_text_hint).desired_width(150.0));
+                        egui::ComboBox::from_id_source("watermark_corner").selected_text(watermark.corner.label(text)).show_ui(ui, |ui| {
+                            for corner in [Corner::TopLeft, Corner::TopRight, Corner::BottomLeft, Corner::BottomRight] {
+                                ui.selectable_value(&mut watermark.corner, corner, corner.label(text));
+                            }
+                        });
+                        ui.add(egui::Slider::new(&mut watermark.opacity, 0.1..=1.0).text(text.opacity));
+                    });
+                }).response.on_hover_text(text.watermark_hover);
+                ui.end_row();
+
+                ui.label(text.accessibility);
+                ui.checkbox(&mut self.settings.text_state_markers, text.text_markers)
+                    .on_hover_text(text.text_markers_hover);
+                ui.end_row();
+            });
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button(text.close).clicked() {
+                    self.dialog_status.settings = false;
+                }
+            });
+        });
+    }
+
+    /// Labelled details about the app, SANE and the selected device, as shown in the about window
+    fn diagnostic_rows(&self) -> Vec<(&'static str, String)> {
+        let text = self.settings.language.strings();
+        let mut rows = vec![
+            (text.app_version, String::from(env!("CARGO_PKG_VERSION"))),
+            (text.sane_version, sane_version_string(self.sane_instance.version_code)),
+            (text.configured_backends, self.sane_backends.as_ref().map_or_else(|| String::from(text.backends_unavailable), |backends| backends.join(", "))),
+        ];
+
+        match self.scanner_list.get(self.selected_scanner) {
+            Some(device) => {
+                let name = cstring_to_string(&device.name, "device name");
+                let backend = name.split(':').next().unwrap_or_default().to_owned();
+                rows.extend([
+                    (text.device_name, name),
+                    (text.device_vendor, cstring_to_string(&device.vendor, "device vendor")),
+                    (text.device_model, cstring_to_string(&device.model, "device model")),
+                    (text.device_type, cstring_to_string(&device.type_, "device type")),
+                    (text.device_backend, backend),
+                    (text.device_opened, String::from(if self.selected_handle.is_some() { text.yes } else { text.no })),
+                ]);
+            },
+            None => rows.push((text.device_name, String::from(text.no_scanner))),
+        }
+
+        rows
+    }
+
+    fn show_about_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let rows = self.diagnostic_rows();
+        egui::Window::new(text.about_title).id(egui::Id::new("about_window")).resizable(false).show(ctx, |ui| {
+            egui::Grid::new("diagnostics").striped(true).show(ui, |ui| {
+                for (label, value) in &rows {
+                    ui.label(*label);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            });
 
-                ui.add_enabled_ui(self.selected_handle.is_some() && self.scan_status == ScanStatus::Running, |ui| {
-                    if ui.button("Cancel scan").clicked() {
-                        self.cancel_scan();
-                    }
-                })
+            ui.horizontal(|ui| {
+                if ui.button(text.copy_diagnostics).clicked() {
+                    let diagnostics: Vec<String> = rows.iter().map(|(label, value)| format!("{label} {value}")).collect();
+                    ctx.output_mut(|output| output.copied_text = diagnostics.join("\n"));
+                }
+
+                if ui.button(text.close).clicked() {
+                    self.dialog_status.about = false;
+                }
             });
         });
     }
 
-    fn draw_bottom_panel(&mut self, ctx: &Context) {
-        egui::TopBottomPanel::bottom("MainUI-BottomPanel").show(ctx, |ui| {
-            ui.horizontal_wrapped(|ui| {
-                ui.add(egui::Slider::new(&mut self.image_max_x, 100.0..=500.0).text("Preview size"));
-
-                if ui.button("Select root save location...").clicked() {
-                    if let Some(path) = select_folder_dialog("Select root save location", self.root_location.as_ref().unwrap_or(&PathBuf::new()).to_str().unwrap_or("")) {
-                        self.root_location = Some(PathBuf::from(path));
+    fn show_profiles_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let can_apply = self.scan_status == ScanStatus::Stopped && !self.device_busy();
+        let mut applied = None;
+        let mut deleted = None;
+        let mut open = true;
+        egui::Window::new(text.profiles_title).id(egui::Id::new("profiles_window")).open(&mut open).resizable(false).show(ctx, |ui| {
+            egui::Grid::new("profiles").striped(true).show(ui, |ui| {
+                for (i, profile) in self.settings.scan_profiles.iter().enumerate() {
+                    ui.label(&profile.name);
+                    ui.weak(fill(text.profile_device_options, profile.device_options.len()));
+                    if ui.add_enabled(can_apply, egui::Button::new(text.apply_profile)).clicked() {
+                        applied = Some(profile.clone());
                     }
+                    if ui.button(text.delete_profile).clicked() {
+                        deleted = Some(i);
+                    }
+                    ui.end_row();
                 }
+            });
+            if self.settings.scan_profiles.is_empty() {
+                ui.weak(text.no_profiles);
+            }
+            ui.separator();
 
-                if let Some(path) = &self.root_location {
-                    ui.colored_label(Color32::GREEN, (*path.canonicalize().unwrap_or_default().to_string_lossy()).to_owned() + std::path::MAIN_SEPARATOR.to_string().as_str());
-                } else {
-                    ui.colored_label(Color32::RED, "No save location selected");
+            ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.profile_name).hint_text(text.profile_name_hint).desired_width(150.0));
+                let name = self.profile_name.trim().to_owned();
+                if ui.add_enabled(!name.is_empty(), egui::Button::new(text.save_profile)).on_hover_text(text.save_profile_hover).clicked() {
+                    self.save_profile(&name);
+                    self.profile_name.clear();
                 }
+            });
+        });
 
-                ui.label("File name/path: ");
+        if let Some(profile) = applied {
+            self.apply_profile(&profile);
+        }
+        if let Some(i) = deleted {
+            self.settings.scan_profiles.remove(i);
+        }
+        if !open {
+            self.dialog_status.profiles = false;
+        }
+    }
 
-                self.path_field = Some(ui.add(egui::TextEdit::singleline(&mut self.file_save_path).hint_text(DEFAULT_FILE_NAME).cursor_at_end(false)));
+    fn show_statistics_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        let statistics = *self.scan_statistics.lock().unwrap();
+        let average = statistics.average_page_time().map_or_else(|| String::from("—"), |time| format!("{:.1} s", time.as_secs_f32()));
+        let rows = [
+            (text.pages_scanned, statistics.pages_scanned.to_string()),
+            (text.pages_saved, statistics.pages_saved.to_string()),
+            (text.average_page_time, average),
+        ];
 
-                if let Some(field) = &self.path_field {
-                    if field.lost_focus() && ctx.input(|i| i.key_pressed(egui::Key::Enter)) {
-                        match self.write_pdf() {
-                            Ok(status) => if let SaveStatus::Completed = status {
-                                self.mark_selection_saved();
-                                self.clear_selection();
-                            },
-                            Err(error) =>
-                                message_box_ok(ERR_DIALOG_TITLE, &format!("Error occurred while saving PDF file: {error}"), MessageBoxIcon::Warning),
-                        }
-                    }
+        egui::Window::new(text.statistics_title).id(egui::Id::new("statistics_window")).resizable(false).show(ctx, |ui| {
+            egui::Grid::new("statistics").striped(true).show(ui, |ui| {
+                for (label, value) in &rows {
+                    ui.label(*label);
+                    ui.label(value);
+                    ui.end_row();
                 }
-
-                ui.checkbox(&mut self.show_saved_images, "Show saved")
-                    .on_hover_text("Show scanned images even after they are saved to a file (selecting reveals previously-saved images)");
             });
-        });
-    }
 
-    fn draw_center_panel(&mut self, ctx: &Context) {
-        let mut clearing_from_index: Option<usize> = None;
+            ui.horizontal(|ui| {
+                if ui.button(text.reset).clicked() {
+                    *self.scan_statistics.lock().unwrap() = ScanStatistics::default();
+                }
 
-        egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                ui.horizontal_wrapped(|ui| {
-                    for (i, image) in self.scanned_images.lock().unwrap().iter_mut().enumerate() {
-                        if image.saved_to_file && !self.show_saved_images {
-                            continue;
-                        }
-                
-                        if ui.add(egui::Image::new(&image.texture_handle)
-                            .fit_to_exact_size(scale_image_size(image.texture_handle.size_vec2(), self.image_max_x))
-                            .show_loading_spinner(true)
-                            .tint(if let Some(n) = image.selected_as_page {selection_tint_color(n, self.pages_selected)} else {Color32::WHITE})
-                            .sense(Sense::click()))
-                                .on_hover_text_at_pointer(if let Some(page) = image.selected_as_page {format!("Page {}", page+1)} else {format!("Selecting page {}...", self.pages_selected+1)})
-                                .clicked() {
-                                    if let Some(idx) = image.selected_as_page {
-                                        clearing_from_index = Some(idx);
-                                    } else {
-                                        self.selected_page_indices.push(i);
-                                        image.selected_as_page = Some(self.pages_selected);
-                                        self.pages_selected += 1;    
-                                    }
-                            
-                                    if let Some(resp) = &self.path_field {
-                                        resp.request_focus();
-                                    }
-                        };
-                    }
-                });
+                if ui.button(text.close).clicked() {
+                    self.dialog_status.statistics = false;
+                }
             });
         });
+    }
 
-        if let Some(idx) = clearing_from_index {
-            self.clear_selection_from(idx);
-        }
+    /// Saved pages are hidden from the grid unless asked for, or kept in view by the setting
+    fn is_page_visible(&self, image: &ScanEntry) -> bool {
+        !image.saved_to_file || self.show_saved_images || self.settings.keep_pages_after_save
     }
 
-    fn show_config_window(&mut self, ctx: &Context) {
-        egui::Window::new("Scanner Configuration").default_size([680.0, 500.0]).show(ctx, |ui| {
-            egui::TopBottomPanel::bottom("close_panel")
-            .resizable(false)
-            .show_inside(ui, |ui| {
-                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("Close").clicked() {
-                        self.dialog_status.config = false;
-                        self.dialog_status.common_vals = false;
-                    }
+    /// First page not yet selected, skipping pages hidden because they were saved
+    fn first_unselected_page(&self) -> Option<usize> {
+        self.scanned_images.iter()
+            .position(|image| image.selected_as_page.is_none() && self.is_page_visible(image))
+    }
 
-                    if ui.button("Apply").clicked() {
-                        self.apply_config_changes();
-                    }
+    fn jump_to_unselected_page(&mut self) {
+        self.scroll_to_page = self.first_unselected_page();
+    }
 
-                    if ui.button("Common numerical values...").clicked() {
-                        self.dialog_status.common_vals = !self.dialog_status.common_vals;
-                    }
-                });
-            });
+    fn handle_jump_shortcut(&mut self, ctx: &Context) {
+        // Typing an N into a text field must not jump
+        if ctx.input(|i| i.key_pressed(egui::Key::N) && i.modifiers.is_none()) && !ctx.wants_keyboard_input() {
+            self.jump_to_unselected_page();
+        }
+    }
 
-            egui::CentralPanel::default().show_inside(ui, |ui| {
-                egui::ScrollArea::both().show(ui, |ui| {
-                    egui::Grid::new("device_config").striped(true).max_col_width(160.0).show(ui, |ui| {
-                        for option in &mut self.config_options {
+    /// Escape closes the configuration dialogs, frontmost first, and otherwise clears the page selection.
+    /// A focused widget such as a text field gets the key to itself.
+    fn handle_escape(&mut self, ctx: &Context) {
+        if !ctx.input(|i| i.key_pressed(egui::Key::Escape)) || ctx.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
 
-                            if let ValueType::Group = option.base_option.type_ {
-                                // Group titles get a special label and no controls (column 1)
-                                ui.colored_label(Color32::LIGHT_BLUE,
-                                    cstring_to_string(&option.base_option.title, "group title"));
-                            } else {
-                                // Draw the option item's label (column 1)
-                                let option_title = cstring_to_string(&option.base_option.title, "option title");
-                                ui.label(option_title).on_hover_text(cstring_to_string(&option.base_option.desc, "option description"));
-                            }
+        let status = &mut self.dialog_status;
+        // Child windows come before the windows they were opened from
+        let open_windows = [
+            ("common_values_window", status.common_vals),
+            ("page_breaks_window", status.page_breaks.is_some()),
+            ("annotation_window", status.annotate.is_some()),
+            ("merge_window", status.merge.is_some()),
+            ("print_window", status.print.is_some()),
+            ("pdf_preview_window", status.pdf_preview),
+            ("selection_order_window", status.selection_order),
+            ("profiles_window", status.profiles),
+            ("statistics_window", status.statistics),
+            ("about_window", status.about),
+            ("settings_window", status.settings),
+            ("config_window", status.config),
+        ];
+        let frontmost = ctx.memory(|memory| memory.areas().top_layer_id(egui::Order::Middle)).map(|layer| layer.id);
+        // The top layer may be a window that has since been closed, so fall back to the first open one
+        let to_close = open_windows.iter().find(|(id, open)| *open && frontmost == Some(egui::Id::new(id)))
+            .or_else(|| open_windows.iter().find(|(_, open)| *open))
+            .map(|(id, _)| *id);
 
-                            // Draw the option value controls (column 2)
-                            ui.add_enabled_ui(option.base_option.cap.contains(OptionCapability::SOFT_SELECT), |ui| {
-                                ui.with_layout(egui::Layout::left_to_right(egui::Align::Center), |ui| {
-                                    render_device_option_controls(ui, option);
-                                }).response.on_disabled_hover_text("This option cannot be changed in software — look on the hardware device to adjust.");
-                            });
+        match to_close {
+            Some("common_values_window") => status.common_vals = false,
+            Some("page_breaks_window") => status.page_breaks = None,
+            Some("annotation_window") => status.annotate = None,
+            Some("merge_window") => status.merge = None,
+            Some("print_window") => status.print = None,
+            Some("pdf_preview_window") => status.pdf_preview = false,
+            Some("selection_order_window") => status.selection_order = false,
+            Some("profiles_window") => status.profiles = false,
+            Some("statistics_window") => status.statistics = false,
+            Some("about_window") => status.about = false,
+            Some("settings_window") => status.settings = false,
+            Some(_) => {
+                // Matches the Close button, which closes the common values window too
+                status.config = false;
+                status.common_vals = false;
+            }
+            None => self.clear_selection(),
+        }
+    }
 
-                            ui.end_row();
-                        }
-                    });
-                });
+    fn show_values_window(&mut self, ctx: &Context) {
+        let text = self.settings.language.strings();
+        egui::Window::new(text.common_values_title).id(egui::Id::new("common_values_window")).default_size([400.0, 300.0]).show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(text.units);
+                for unit in [LengthUnit::Millimeters, LengthUnit::Inches] {
+                    ui.selectable_value(&mut self.common_vals_unit, unit, unit.as_str());
+                }
             });
-        });
-    }
 
-    fn show_values_window(ctx: &Context) {
-        egui::Window::new("Common Values").default_size([400.0, 300.0]).show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
                 for category in [ValueCategory::LetterUS, ValueCategory::A4] {
                     CollapsingHeader::new(category.as_str()).default_open(true).show(ui, |ui| {
                         egui::Grid::new(category.as_str()).striped(true).show(ui, |ui| {
                             for value in category.get_values() {
+                                let displayed_value = value.value_in(self.common_vals_unit);
+
                                 ui.label(value.name).on_hover_text(value.description);
-                                if ui.button("Copy").clicked() {
-                                    ui.output_mut(|o| value.value.clone_into(&mut o.copied_text));
+                                ui.label(format!("{displayed_value} {}", self.common_vals_unit.as_str()));
+                                if ui.button(text.copy).clicked() {
+                                    ui.output_mut(|o| o.copied_text = displayed_value);
                                 }
+                                ui.end_row();
                             }
                         });
                     });
@@ -544,112 +3890,558 @@ impl App {
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_device_opening();
+        self.poll_button_task();
+        self.poll_prescan_task(ctx);
+        self.receive_error_reports();
+        self.receive_decoded_pages(ctx);
+        self.poll_scan_completion();
+        self.handle_button_presses();
+        self.confirm_quit(ctx);
 
-        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
-            self.clear_selection();
-        }
+        self.handle_escape(ctx);
+        self.handle_jump_shortcut(ctx);
 
         self.draw_top_panel(ctx);
 
+        self.draw_summary_bar(ctx);
+
         self.draw_bottom_panel(ctx);
 
+        self.draw_staging_tray(ctx);
+
         self.draw_center_panel(ctx);
 
         if self.dialog_status.config {
             self.show_config_window(ctx);
         }
         if self.dialog_status.common_vals {
-            App::show_values_window(ctx);
+            self.show_values_window(ctx);
+        }
+        if let Some(index) = self.dialog_status.page_breaks {
+            self.show_page_breaks_window(ctx, index);
+        }
+        if let Some(index) = self.dialog_status.annotate {
+            self.show_annotation_window(ctx, index);
         }
+        if let Some(index) = self.dialog_status.merge {
+            self.show_merge_window(ctx, index);
+        }
+        if self.dialog_status.settings {
+            self.show_settings_window(ctx);
+        }
+        if self.dialog_status.selection_order {
+            self.show_selection_order_window(ctx);
+        }
+        if self.dialog_status.pdf_preview {
+            self.show_pdf_preview_window(ctx);
+        }
+        if let Some(indices) = self.dialog_status.print.clone() {
+            self.show_print_window(ctx, &indices);
+        }
+        if self.dialog_status.statistics {
+            self.show_statistics_window(ctx);
+        }
+        if self.dialog_status.profiles {
+            self.show_profiles_window(ctx);
+        }
+        if self.dialog_status.about {
+            self.show_about_window(ctx);
+        }
+        if self.prescan.is_some() {
+            self.show_scan_area_window(ctx);
+        }
+        if self.scan_area_diagram.is_some() {
+            self.show_scan_area_diagram_window(ctx);
+        }
+        self.show_error_window(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, eframe::APP_KEY, &self.settings);
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.scanned_images.clear();
+        let _ = fs::remove_dir_all(spill_directory());
+    }
+}
+
+/// Whether saving failed because the location can't be written to, as opposed to some other IO or encoding error
+fn is_permission_denied(error: &(dyn Error + 'static)) -> bool {
+    error.downcast_ref::<io::Error>()
+        .is_some_and(|error| matches!(error.kind(), io::ErrorKind::PermissionDenied | io::ErrorKind::ReadOnlyFilesystem))
+}
+
+/// Backends enabled in SANE's `dll.conf`, looked up the same way SANE does: `SANE_CONFIG_DIR` first, then the default directory.
+/// `None` if no configuration could be read.
+fn configured_backends() -> Option<Vec<String>> {
+    let config_dirs = std::env::var("SANE_CONFIG_DIR").unwrap_or_default();
+    let contents = config_dirs.split(':').filter(|dir| !dir.is_empty()).chain(["/etc/sane.d"])
+        .find_map(|dir| fs::read_to_string(Path::new(dir).join("dll.conf")).ok())?;
+    Some(parse_backend_list(&contents))
 }
 
 #[derive(Default)]
 struct DialogStatus {
     config: bool,
-    common_vals: bool
+    common_vals: bool,
+    page_breaks: Option<usize>,
+    annotate: Option<usize>,
+    /// Index of the first of the two pages being merged
+    merge: Option<usize>,
+    settings: bool,
+    statistics: bool,
+    about: bool,
+    selection_order: bool,
+    pdf_preview: bool,
+    profiles: bool,
+    /// Pages to print, in output order
+    print: Option<Vec<usize>>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct PageSplit {
+    enabled: bool,
+    interval_mm: f32,
+}
+
+impl Default for PageSplit {
+    fn default() -> Self {
+        Self::for_paper(PaperSize::default())
+    }
+}
+
+impl PageSplit {
+    /// Splitting disabled, with the interval set to the paper's height
+    fn for_paper(paper: PaperSize) -> Self {
+        Self {
+            enabled: false,
+            interval_mm: paper.size_mm().1,
+        }
+    }
+
+    fn rows_per_segment(&self) -> usize {
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let rows = (self.interval_mm / MM_PER_INCH * SCAN_DPI).round() as usize;
+        rows.max(1)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+enum PageOrder {
+    AsSelected,
+    Reverse,
+    ScanOrder,
+}
+
+impl PageOrder {
+    fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::AsSelected    => text.order_as_selected,
+            Self::Reverse       => text.order_reverse,
+            Self::ScanOrder     => text.order_scan,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum OutputMode {
+    SingleFile,
+    FilePerPage,
+    /// Blank pages separate documents, each saved to its own numbered PDF, and are left out
+    SplitOnBlankPages,
+}
+
+impl OutputMode {
+    fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::SingleFile    => text.output_single_file,
+            Self::FilePerPage   => text.output_file_per_page,
+            Self::SplitOnBlankPages => text.output_split_on_blank,
+        }
+    }
+}
+
+/// Throughput since the app started or the statistics were reset
+#[derive(Default, Clone, Copy)]
+struct ScanStatistics {
+    pages_scanned: usize,
+    pages_saved: usize,
+    /// Time spent reading and decoding scanned pages, excluding waits between them
+    scan_time: Duration,
+}
+
+impl ScanStatistics {
+    fn record_page(&mut self, time: Duration) {
+        self.pages_scanned += 1;
+        self.scan_time += time;
+    }
+
+    fn average_page_time(&self) -> Option<Duration> {
+        self.scan_time.checked_div(u32::try_from(self.pages_scanned).ok()?)
+    }
+}
+
+/// Width of the scan bed on the scan area diagram, in points
+const SCAN_AREA_DIAGRAM_WIDTH: f32 = 300.0;
+/// How close to a corner of the scan area a drag must start to resize it, in points
+const SCAN_AREA_HANDLE_RADIUS: f32 = 8.0;
+
+/// The device's scan area on a diagram of the bed, adjusted by dragging
+struct ScanAreaDiagram {
+    /// Width and height of the bed in the scan area options' own unit
+    bed_size: Vec2,
+    /// Selected area as fractions of the bed
+    region: Rect,
+    drag: Option<ScanAreaDrag>,
+}
+
+#[derive(Clone, Copy)]
+enum ScanAreaDrag {
+    /// Moving one corner, with the opposite one fixed at `anchor`
+    Resize { anchor: Pos2 },
+    Move { grab_offset: Vec2 },
+    /// Drawing a new area from `origin`
+    Draw { origin: Pos2 },
+}
+
+/// A low-resolution scan of the whole bed, used to pick the scan area graphically
+struct Prescan {
+    texture_handle: TextureHandle,
+    /// Selected region as fractions of the bed, from top-left (0, 0) to bottom-right (1, 1)
+    region: Option<Rect>,
+    drag_origin: Option<Pos2>,
+    /// The scan area from before the prescan, until another one is picked
+    previous_area: Option<[f64; 4]>,
 }
 
 #[derive(PartialEq)]
 enum ScanStatus {
     Stopped,
     Running,
+    /// Waiting between pages, after the page being read, until the scan is resumed
+    Paused,
+    /// Cancelled, waiting for the reading thread to stop
+    Cancelling,
+}
+
+/// Actions offered in a page's context menu
+#[derive(Clone, Copy)]
+enum PageAction {
+    InsertPageBreaks,
+    Annotate,
+    Duplicate,
+    MergeWithNext,
+    AddToTray,
+    Rescan,
+    Delete,
+}
+
+#[derive(Default)]
+struct MergeOptions {
+    direction: MergeDirection,
+    scale_to_match: bool,
+}
+
+struct PrintOptions {
+    printer: String,
+    copies: u32,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            printer: String::new(),
+            copies: 1,
+        }
+    }
+}
+
+/// Which pages a PDF is saved from
+#[derive(Clone, Copy)]
+enum PageSource {
+    Selection,
+    Staged,
 }
 
 enum SaveStatus {
-    Completed,
+    /// Holds the absolute paths of the files written
+    Completed(Vec<PathBuf>),
     Cancelled,
 }
 
-fn render_device_option_controls(ui: &mut egui::Ui, option: &mut EditingDeviceOption) {
+struct SummaryItem {
+    /// Title of the option as given by the device, or `None` for the combined scan area
+    title: Option<String>,
+    value: String,
+    option_idx: i32,
+}
+
+/// Picks the summary bar's options out of the loaded options, in a fixed order
+fn summarize_options(options: &[EditingDeviceOption]) -> Vec<SummaryItem> {
+    let find = |name: &str| options.iter()
+        .find(|option| cstring_to_string(&option.base_option.name, "option name") == name && !option.base_option.cap.contains(OptionCapability::INACTIVE));
+
+    let mut summary: Vec<SummaryItem> = SUMMARY_OPTION_NAMES.iter()
+        .filter_map(|name| find(name))
+        .filter_map(|option| Some(SummaryItem {
+            title: Some(cstring_to_string(&option.base_option.title, "option title")),
+            value: option.value_text()?,
+            option_idx: option.base_option.option_idx,
+        }))
+        .collect();
+
+    let corners: Option<Vec<(String, i32)>> = SCAN_AREA_OPTION_NAMES.iter()
+        .map(|name| find(name).and_then(|option| Some((option.value_text()?, option.base_option.option_idx))))
+        .collect();
+    if let Some(corners) = corners {
+        summary.push(SummaryItem {
+            title: None,
+            value: format!("({}, {}) – ({}, {})", corners[0].0, corners[1].0, corners[2].0, corners[3].0),
+            option_idx: corners[0].1,
+        });
+    }
+
+    summary
+}
+
+/// Output resolutions offered in addition to saving at full resolution
+const OUTPUT_DPI_CHOICES: [u32; 4] = [300, 200, 150, 100];
+
+/// Pixels per inch of a page once it is scaled to fill the width of the PDF page
+fn page_dpi(size: [usize; 2], paper: PaperSize) -> f32 {
+    #[allow(clippy::cast_precision_loss)]
+    let width = size[0] as f32;
+    width / (paper.size_mm().0 / MM_PER_INCH)
+}
+
+/// Factor a page is scaled by to bring it down to `output_dpi` on `paper`, or `None` if it is already within it
+fn downsample_scale(output_dpi: Option<u32>, size: [usize; 2], paper: PaperSize) -> Option<f32> {
+    #[allow(clippy::cast_precision_loss)]
+    output_dpi
+        .map(|dpi| dpi as f32 / page_dpi(size, paper))
+        .filter(|scale| *scale < 1.0)
+}
+
+fn downsample(pixels: &[u8], size: [usize; 2], scale: f32) -> Result<([usize; 2], Vec<u8>), Box<dyn std::error::Error>> {
+    let new_size = scaled_size(size, scale);
+    let resampled = resample_pixels(pixels, size, new_size).ok_or("Page data does not match its dimensions")?;
+    Ok((new_size, resampled))
+}
+
+fn scaled_size(size: [usize; 2], scale: f32) -> [usize; 2] {
+    #[allow(clippy::cast_precision_loss)]
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    size.map(|length| ((length as f32 * scale).round() as usize).max(1))
+}
+
+/// Dimensions a page of `size` pixels is saved at, after downsampling to `output_dpi`
+fn output_size(size: [usize; 2], output_dpi: Option<u32>, paper: PaperSize) -> [usize; 2] {
+    downsample_scale(output_dpi, size, paper).map_or(size, |scale| scaled_size(size, scale))
+}
+
+/// Maximum height of the hover zoom preview as a fraction of the window height
+const ZOOM_PREVIEW_MAX_HEIGHT: f32 = 0.8;
+
+/// Width of the page thumbnails in the staging tray, which are kept small so the tray doesn't crowd the page grid
+const STAGED_THUMBNAIL_WIDTH: f32 = 60.0;
+
+/// Width of the page thumbnails listed in the selection order window
+const SELECTION_ORDER_THUMBNAIL_WIDTH: f32 = 40.0;
+
+/// Largest margin that can be set on each side of output pages, which still leaves room for the image on any paper size
+const MAX_PAGE_MARGIN_MM: f32 = 50.0;
+
+/// Largest side of a card in ID card mode, which is enough for a passport page
+const MAX_ID_CARD_SIDE_MM: f32 = 150.0;
+
+/// Most columns the page grid can be pinned to
+const MAX_THUMBNAIL_COLUMNS: usize = 12;
+
+/// Range of the thumbnail width setting, in points
+const THUMBNAIL_SIZE_RANGE: RangeInclusive<f32> = 100.0..=500.0;
+
+/// How long after the last Ctrl+scroll step thumbnail textures are regenerated at the new size
+const THUMBNAIL_ZOOM_SETTLE_TIME: Duration = Duration::from_millis(300);
+
+/// Scans in a row that may fail while reading before the scanner is closed
+const MAX_CONSECUTIVE_SCAN_ERRORS: u32 = 3;
+
+/// Fraction of a page's pixels that may be dark for it to still count as a blank separator sheet
+const BLANK_PAGE_MAX_INK: f32 = 0.002;
+
+/// Width of each page drawn in the PDF layout preview
+const PDF_PREVIEW_PAGE_WIDTH: f32 = 170.0;
+
+/// Distance in points within which clicking a page break removes it instead of adding one
+const PAGE_BREAK_HIT_DISTANCE: f32 = 6.0;
+
+/// String choice lists longer than this get a filter field in their dropdown
+const FILTERABLE_LIST_LENGTH: usize = 12;
+
+/// `text_markers` prefixes state labels with a textual tag so they don't rely on color alone
+fn render_device_option_controls(ui: &mut egui::Ui, option: &mut EditingDeviceOption, text_markers: bool, text: &Strings) {
+    let with_marker = |marker: &str, label: String| if text_markers { format!("{marker} {label}") } else { label };
+    let range_label = |start, end, step| with_marker(text.range_marker, fill(&fill(&fill(text.range, start), end), step));
+
     if option.base_option.cap.contains(OptionCapability::INACTIVE) {
-        ui.colored_label(Color32::DARK_RED, "(Inactive)").on_hover_text("This option is inactive. There may be another option that, once applied, causes this option to take effect.");
+        ui.colored_label(Color32::DARK_RED, with_marker(text.inactive_marker, text.inactive.to_owned())).on_hover_text(text.inactive_hover);
         return;
     }
 
-    match &mut option.editing_value {
-        EditingDeviceOptionValue::Bool(val) => option_edited_if_changed(&ui.checkbox(val, ""), option),
-        EditingDeviceOptionValue::Int(val) => {
-            match &option.base_option.constraint {
-                sane_scan::OptionConstraint::WordList(list) => {
-                    if egui::ComboBox::from_id_source(option.base_option.option_idx).selected_text(val.clone()).show_ui(ui, |ui| {
-                        for word in list {
-                            ui.selectable_value(val, word.to_string(), word.to_string());
+    if let Some(error) = &option.load_error {
+        ui.colored_label(Color32::RED, with_marker(text.error_marker, text.value_unavailable.to_owned()))
+            .on_hover_text(fill(text.value_unavailable_hover, error));
+        return;
+    }
+
+    if let EditingDeviceOptionValue::Group = option.editing_value {
+        return;
+    }
+
+    if option.base_option.cap.contains(OptionCapability::AUTOMATIC) && !matches!(option.editing_value, EditingDeviceOptionValue::Button) {
+        option_edited_if_changed(&ui.checkbox(&mut option.set_auto, text.auto).on_hover_text(text.auto_hover), option);
+    }
+
+    // A value set to auto is chosen by the device, so there's nothing to edit
+    ui.add_enabled_ui(!option.set_auto, |ui| {
+        match &mut option.editing_value {
+            EditingDeviceOptionValue::Bool(val) | EditingDeviceOptionValue::Flag(val) => option_edited_if_changed(&ui.checkbox(val, ""), option),
+            EditingDeviceOptionValue::Int(val) => {
+                match &option.base_option.constraint {
+                    sane_scan::OptionConstraint::WordList(list) => {
+                        if egui::ComboBox::from_id_source(option.base_option.option_idx).selected_text(val.clone()).show_ui(ui, |ui| {
+                            for word in list {
+                                ui.selectable_value(val, word.to_string(), word.to_string());
+                            }
+                        }).response.clicked() {
+                            option.is_edited = true;
                         }
-                    }).response.clicked() {
-                        option.is_edited = true;
-                    }
-                },
-                sane_scan::OptionConstraint::Range { range, quant } => {
-                    ui.colored_label(Color32::GOLD, format!("(Range: {} – {}, step: {})", range.start, range.end, quant));
-                    option_edited_if_changed(&ui.text_edit_singleline( val), option);
-                },
-                _ => option_edited_if_changed(&ui.text_edit_singleline( val), option),
-            }
-        },
-        EditingDeviceOptionValue::Fixed(val) => {
-            match &option.base_option.constraint {
-                sane_scan::OptionConstraint::Range { range, quant } => {
-                    ui.colored_label(Color32::GOLD, format!("(Range: {} – {}, step: {})",
-                        sane_fixed_to_float(range.start), sane_fixed_to_float(range.end), sane_fixed_to_float(*quant)));
-                    option_edited_if_changed(&ui.text_edit_singleline(val), option);
-                },
-                _ => option_edited_if_changed(&ui.text_edit_singleline(val), option),
-            }
-        },
-        EditingDeviceOptionValue::String(val) => {
-            match &option.base_option.constraint {
-                sane_scan::OptionConstraint::StringList(list) => {
-                    let string_list: Vec<String> = list.iter().map(|item| cstring_to_string(item, "option choice")).collect();
-                    if egui::ComboBox::from_id_source(option.base_option.option_idx).selected_text(val.clone()).show_ui(ui, |ui| {
-                        for string in string_list {
-                            ui.selectable_value(val, string.clone(), string);
+                    },
+                    sane_scan::OptionConstraint::Range { range, quant } => {
+                        ui.colored_label(Color32::GOLD, range_label(f64::from(range.start), f64::from(range.end), f64::from(*quant)));
+                        option_edited_if_changed(&option_text_edit(ui, val, option.base_option.option_idx), option);
+                    },
+                    _ => option_edited_if_changed(&option_text_edit(ui, val, option.base_option.option_idx), option),
+                }
+            },
+            EditingDeviceOptionValue::Fixed(val) => {
+                match &option.base_option.constraint {
+                    sane_scan::OptionConstraint::Range { range, quant } => {
+                        ui.colored_label(Color32::GOLD, range_label(
+                            sane_fixed_to_float(range.start), sane_fixed_to_float(range.end), sane_fixed_to_float(*quant)));
+                        option_edited_if_changed(&option_text_edit(ui, val, option.base_option.option_idx), option);
+                    },
+                    _ => option_edited_if_changed(&option_text_edit(ui, val, option.base_option.option_idx), option),
+                }
+            },
+            EditingDeviceOptionValue::String(val) => {
+                match &option.base_option.constraint {
+                    sane_scan::OptionConstraint::StringList(list) => {
+                        let string_list: Vec<String> = list.iter().map(|item| cstring_to_string(item, "option choice")).collect();
+                        let filterable = string_list.len() > FILTERABLE_LIST_LENGTH;
+                        let filter = &mut option.choice_filter;
+                        if egui::ComboBox::from_id_source(option.base_option.option_idx).selected_text(val.clone()).show_ui(ui, |ui| {
+                            if filterable {
+                                ui.add(egui::TextEdit::singleline(filter).hint_text(text.type_to_filter)).request_focus();
+                            }
+
+                            let filter_lower = filter.to_lowercase();
+                            for string in string_list {
+                                if filterable && !string.to_lowercase().contains(&filter_lower) {
+                                    continue;
+                                }
+                                ui.selectable_value(val, string.clone(), string);
+                            }
+                        }).response.clicked() {
+                            option.is_edited = true;
                         }
-                    }).response.clicked() {
-                        option.is_edited = true;
-                    }
-                },
-                _ => option_edited_if_changed(&ui.text_edit_singleline(val), option),
-            }
-        },
-        EditingDeviceOptionValue::Button => {
-            if ui.button("Activate").clicked() {
-                option.is_edited = true;
-            }
-            if option.is_edited {
-                ui.label("Will activate when Apply button is clicked.");
-            }
-        },
-        EditingDeviceOptionValue::Group => return,
-    }
+                    },
+                    _ => option_edited_if_changed(&option_text_edit(ui, val, option.base_option.option_idx), option),
+                }
+            },
+            EditingDeviceOptionValue::Button => {
+                if ui.button(text.activate).clicked() {
+                    option.is_edited = true;
+                }
+                if option.is_edited {
+                    ui.label(if is_long_running_button(&option.base_option) { text.activate_pending_background } else { text.activate_pending });
+                }
+            },
+            EditingDeviceOptionValue::Group => {},
+        }
+    });
 
     ui.add_enabled_ui(option.is_edited, |ui| {
-        if ui.button("Reset").clicked() {
+        if ui.button(text.reset).clicked() {
             option.reset_editor_value();
         }
     });
 }
 
+/// Draws a page's annotations over its image shown in `rect`
+fn paint_annotations(painter: &egui::Painter, rect: Rect, annotations: &[Annotation]) {
+    for annotation in annotations {
+        let points: Vec<Pos2> = annotation.points.iter()
+            .map(|[x, y]| rect.min + Vec2::new(x * rect.width(), y * rect.height()))
+            .collect();
+
+        let color = match annotation.tool {
+            AnnotationTool::Pen         => annotation.tool.color(),
+            AnnotationTool::Highlighter => annotation.tool.color().gamma_multiply(0.4),
+        };
+        let width = annotation.tool.width() * rect.width();
+
+        if let [point] = points[..] {
+            painter.circle_filled(point, width / 2.0, color);
+        } else {
+            painter.add(egui::Shape::line(points, Stroke::new(width, color)));
+        }
+    }
+}
+
+/// Paints the rows of `image` that make up one output page onto `page`, placed as `write_pdf` places them
+fn paint_page_layout(painter: &egui::Painter, page: Rect, image: &ScanEntry, rows: Range<usize>, segmented: bool, paper: PaperSize, margins: Margins) {
+    let (paper_width, paper_height) = paper.size_mm();
+    let points_per_mm = page.width() / paper_width;
+    let placement = PagePlacement::new([image.size[0], rows.len()], segmented, paper, margins);
+    let top = paper_height - placement.bottom.0 - placement.height.0;
+    let segment_rect = Rect::from_min_size(page.min + Vec2::new(placement.left.0, top) * points_per_mm,
+        Vec2::new(placement.width.0, placement.height.0) * points_per_mm);
+
+    #[allow(clippy::cast_precision_loss)]
+    let row_fraction = |row: usize| row as f32 / image.size[1] as f32;
+    let uv = image.flip.uv();
+    let segment_uv = Rect::from_x_y_ranges(uv.x_range(),
+        egui::lerp(uv.y_range(), row_fraction(rows.start))..=egui::lerp(uv.y_range(), row_fraction(rows.end)));
+
+    painter.rect_filled(page, 0.0, Color32::WHITE);
+    painter.image(image.texture_handle.id(), segment_rect, segment_uv, Color32::WHITE);
+
+    // Annotations are placed over the whole image, of which only this segment's rows are visible
+    let image_height = segment_rect.height() / (row_fraction(rows.end) - row_fraction(rows.start));
+    let image_rect = Rect::from_min_size(segment_rect.min - Vec2::new(0.0, image_height * row_fraction(rows.start)),
+        Vec2::new(segment_rect.width(), image_height));
+    paint_annotations(&painter.with_clip_rect(segment_rect), image_rect, &image.annotations);
+}
+
+/// Paints the watermark text where `Watermark::draw` places it on `page`
+fn paint_watermark(painter: &egui::Painter, page: Rect, watermark: &Watermark, text: &str, paper: PaperSize) {
+    let points_per_mm = page.width() / paper.size_mm().0;
+    let (x, y) = watermark.text_position(text, paper.size());
+    let (_, height) = Watermark::text_size(text);
+    painter.text(Pos2::new(page.left() + x.0 * points_per_mm, page.bottom() - y.0 * points_per_mm), Align2::LEFT_BOTTOM, text,
+        FontId::monospace(height.0 * points_per_mm), Color32::BLACK.gamma_multiply(watermark.opacity));
+}
+
+/// Text field with an ID tied to the option, so focus survives options appearing or disappearing around it
+fn option_text_edit(ui: &mut egui::Ui, value: &mut String, option_idx: i32) -> Response {
+    ui.add(egui::TextEdit::singleline(value).id_source(("option_text", option_idx)))
+}
+
 fn option_edited_if_changed(response: &Response, option: &mut EditingDeviceOption) {
     if response.changed() {
         option.is_edited = true;
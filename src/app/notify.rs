@@ -0,0 +1,10 @@
+use std::process::Command;
+
+/// Shows a desktop notification through `notify-send`. Failures are only logged, as notifications are a convenience.
+pub fn send_notification(summary: &str, body: &str) {
+    match Command::new("notify-send").args(["--app-name", "SlickScan", summary, body]).status() {
+        Ok(status) if status.success() => {},
+        Ok(status) => println!("notify-send failed with {status}"),
+        Err(error) => println!("Failed to show a desktop notification: {error}"),
+    }
+}
@@ -0,0 +1,381 @@
+use std::{error::Error, io::{self, Write}, time::SystemTime};
+
+use flate2::{Compression, write::ZlibEncoder};
+use printpdf::{BlendMode, Color, ColorBits, ColorSpace, Greyscale, IndirectFontRef, Mm, PdfLayerReference, Pt, SeperableBlendMode, lopdf::{Dictionary, Document, Object, StringFormat}};
+use serde::{Deserialize, Serialize};
+
+use crate::{util::{format_utc_timestamp, is_grayscale, pack_bilevel}, LETTER_WIDTH_MM, LETTER_HEIGHT_MM, LEGAL_HEIGHT_MM, A4_WIDTH_MM, A4_HEIGHT_MM, MM_PER_INCH, SCAN_DPI};
+
+use super::{image::PageFormat, strings::Strings};
+
+/// Encodes text as a PDF text string (UTF-16BE with byte order mark) so any characters survive
+fn pdf_text_string(text: &str) -> Object {
+    let mut bytes = vec![0xFE, 0xFF];
+    for unit in text.encode_utf16() {
+        bytes.extend_from_slice(&unit.to_be_bytes());
+    }
+
+    Object::String(bytes, StringFormat::Hexadecimal)
+}
+
+/// Page size of saved PDFs
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaperSize {
+    #[default]
+    Letter,
+    Legal,
+    A4,
+}
+
+impl PaperSize {
+    /// Width and height in millimeters
+    pub fn size_mm(self) -> (f32, f32) {
+        match self {
+            Self::Letter    => (LETTER_WIDTH_MM, LETTER_HEIGHT_MM),
+            Self::Legal     => (LETTER_WIDTH_MM, LEGAL_HEIGHT_MM),
+            Self::A4        => (A4_WIDTH_MM, A4_HEIGHT_MM),
+        }
+    }
+
+    pub fn size(self) -> (Mm, Mm) {
+        let (width, height) = self.size_mm();
+        (Mm(width), Mm(height))
+    }
+
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::Letter    => text.paper_letter,
+            Self::Legal     => text.paper_legal,
+            Self::A4        => text.paper_a4,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OutputFormat {
+    #[default]
+    Pdf,
+    /// PDF/A-2b, for archiving
+    PdfA,
+}
+
+impl OutputFormat {
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::Pdf   => text.output_format_pdf,
+            Self::PdfA  => text.output_format_pdfa,
+        }
+    }
+}
+
+/// How hard page images in saved PDFs are compressed, trading save time for file size
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PdfCompression {
+    Off,
+    Fast,
+    #[default]
+    Balanced,
+    Smallest,
+}
+
+impl PdfCompression {
+    fn level(self) -> Option<Compression> {
+        match self {
+            Self::Off       => None,
+            Self::Fast      => Some(Compression::fast()),
+            Self::Balanced  => Some(Compression::default()),
+            Self::Smallest  => Some(Compression::best()),
+        }
+    }
+
+    /// Rough ratio of compressed to raw size for scanned page data at this level
+    pub fn estimated_ratio(self) -> f64 {
+        match self {
+            Self::Off       => 1.0,
+            Self::Fast      => 0.5,
+            Self::Balanced  => 0.4,
+            Self::Smallest  => 0.35,
+        }
+    }
+
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::Off       => text.compression_off,
+            Self::Fast      => text.compression_fast,
+            Self::Balanced  => text.compression_balanced,
+            Self::Smallest  => text.compression_smallest,
+        }
+    }
+}
+
+/// `data` compressed for a stream with the FlateDecode filter, or `None` if compression is off or wouldn't make it smaller
+pub fn deflate(data: &[u8], compression: PdfCompression) -> io::Result<Option<Vec<u8>>> {
+    let Some(level) = compression.level() else {
+        return Ok(None);
+    };
+    let mut encoder = ZlibEncoder::new(Vec::new(), level);
+    encoder.write_all(data)?;
+    let compressed = encoder.finish()?;
+
+    Ok((compressed.len() < data.len()).then_some(compressed))
+}
+
+/// Compresses the streams of a document saved by printpdf, images at `compression` and the rest, which are small, at the best level
+pub fn compress_document(bytes: &[u8], compression: PdfCompression) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut doc = Document::load_mem(bytes)?;
+    doc.prune_objects();
+    doc.delete_zero_length_streams();
+
+    for object in doc.objects.values_mut() {
+        let Object::Stream(stream) = object else {
+            continue;
+        };
+        if !stream.allows_compression || stream.dict.has(b"Filter") {
+            continue;
+        }
+
+        if stream.dict.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"Image") {
+            if let Some(compressed) = deflate(&stream.content, compression)? {
+                stream.dict.set("Filter", "FlateDecode");
+                stream.set_content(compressed);
+            }
+        } else {
+            stream.compress()?;
+        }
+    }
+
+    let mut compressed = Vec::new();
+    doc.save_to(&mut compressed)?;
+    Ok(compressed)
+}
+
+/// Blank space left around the image on each side of an output page, in millimeters
+#[derive(Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Margins {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl Margins {
+    pub fn is_zero(self) -> bool {
+        self == Self::default()
+    }
+}
+
+/// Where an image sits on an output page, as used both for saving and for the layout preview
+pub struct PagePlacement {
+    /// Scale factors for printpdf, relative to the image's size at `SCAN_DPI`
+    pub scale_x: f32,
+    pub scale_y: f32,
+    pub width: Mm,
+    pub height: Mm,
+    /// Distance from the left of the page to the left of the image
+    pub left: Mm,
+    /// Distance from the bottom of the page to the bottom of the image
+    pub bottom: Mm,
+}
+
+impl PagePlacement {
+    /// Whole pages are stretched to fill the page. Segments keep their aspect ratio, fit within the page, and are anchored to its top.
+    /// With margins, whole pages also keep their aspect ratio, and images are centered between the side margins.
+    pub fn new(size: [usize; 2], segmented: bool, paper: PaperSize, margins: Margins) -> Self {
+        let (paper_width, paper_height) = paper.size_mm();
+        // Margins too large for the page leave at least a sliver for the image
+        let area_width = (paper_width - margins.left - margins.right).max(1.0);
+        let area_height = (paper_height - margins.top - margins.bottom).max(1.0);
+
+        #[allow(clippy::cast_precision_loss)]
+        let inches_unscaled_x = size[0] as f32 / SCAN_DPI;
+        #[allow(clippy::cast_precision_loss)]
+        let inches_unscaled_y = size[1] as f32 / SCAN_DPI;
+
+        let fill_scale_x = area_width / MM_PER_INCH / inches_unscaled_x;
+        let fill_scale_y = area_height / MM_PER_INCH / inches_unscaled_y;
+
+        if !segmented && margins.is_zero() {
+            return Self { scale_x: fill_scale_x, scale_y: fill_scale_y, width: Mm(paper_width), height: Mm(paper_height), left: Mm(0.0), bottom: Mm(0.0) };
+        }
+
+        let uniform_scale = fill_scale_x.min(fill_scale_y);
+        let width = Mm(inches_unscaled_x * uniform_scale * MM_PER_INCH);
+        let height = Mm(inches_unscaled_y * uniform_scale * MM_PER_INCH);
+        let left = if margins.is_zero() { Mm(0.0) } else { Mm(margins.left + (area_width - width.0) / 2.0) };
+        let bottom = if segmented {
+            Mm(paper_height - margins.top) - height
+        } else {
+            Mm(margins.bottom + (area_height - height.0) / 2.0)
+        };
+
+        Self { scale_x: uniform_scale, scale_y: uniform_scale, width, height, left, bottom }
+    }
+}
+
+/// Color space, bit depth and data for embedding 8-bit RGB pixels scanned in `format`.
+/// Gray and lineart pages are stored with fewer channels or bits unless edits such as colored annotations added color.
+pub fn image_encoding(pixels: &[u8], size: [usize; 2], format: PageFormat) -> (ColorSpace, ColorBits, Vec<u8>) {
+    // Pure black-and-white pages are stored at one bit per pixel whatever their format
+    if let Some(packed) = pack_bilevel(pixels, size) {
+        return (ColorSpace::Greyscale, ColorBits::Bit1, packed);
+    }
+
+    match format {
+        PageFormat::Gray | PageFormat::Lineart if is_grayscale(pixels) => {
+            (ColorSpace::Greyscale, ColorBits::Bit8, pixels.iter().step_by(3).copied().collect())
+        },
+        _ => (ColorSpace::Rgb, ColorBits::Bit8, pixels.to_vec()),
+    }
+}
+
+/// Page dictionary entries attaching `note` as a text annotation in the top-left corner of the page
+pub fn note_annotation(note: &str, page_height: Mm) -> Dictionary {
+    let top = Pt::from(page_height).0;
+
+    let annotation = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Annot".to_vec())),
+        ("Subtype", Object::Name(b"Text".to_vec())),
+        ("Name", Object::Name(b"Note".to_vec())),
+        ("Rect", vec![10.0.into(), (top - 30.0).into(), 30.0.into(), (top - 10.0).into()].into()),
+        ("Contents", pdf_text_string(note)),
+        ("Open", false.into()),
+    ]);
+
+    Dictionary::from_iter(vec![("Annots", Object::Array(vec![annotation.into()]))])
+}
+
+/// Scan time and optional text stamped in a corner of every saved page
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Watermark {
+    pub enabled: bool,
+    pub custom_text: String,
+    pub corner: Corner,
+    /// From 0 (invisible) to 1 (solid black)
+    pub opacity: f32,
+}
+
+impl Default for Watermark {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            custom_text: String::new(),
+            corner: Corner::default(),
+            opacity: 0.6,
+        }
+    }
+}
+
+/// Size of the watermark text in points
+const WATERMARK_FONT_SIZE: f32 = 8.0;
+/// Advance of every glyph in Courier, as a fraction of the font size
+const COURIER_CHAR_WIDTH: f32 = 0.6;
+const WATERMARK_MARGIN: Mm = Mm(5.0);
+
+impl Watermark {
+    pub fn text(&self, scanned_at: SystemTime) -> String {
+        let timestamp = format_utc_timestamp(scanned_at);
+        match self.custom_text.trim() {
+            "" => timestamp,
+            custom => format!("{timestamp} {custom}"),
+        }
+    }
+
+    /// Size of `text` on the page. This relies on the fixed glyph width of the built-in Courier font it is drawn in.
+    pub fn text_size(text: &str) -> (Mm, Mm) {
+        #[allow(clippy::cast_precision_loss)]
+        let width: Mm = Pt(text.chars().count() as f32 * WATERMARK_FONT_SIZE * COURIER_CHAR_WIDTH).into();
+        (width, Pt(WATERMARK_FONT_SIZE).into())
+    }
+
+    /// Bottom-left corner of `text` on a page of `page_size`, measured from the bottom-left of the page
+    pub fn text_position(&self, text: &str, page_size: (Mm, Mm)) -> (Mm, Mm) {
+        let (width, height) = Self::text_size(text);
+        let x = match self.corner {
+            Corner::TopLeft | Corner::BottomLeft    => WATERMARK_MARGIN,
+            Corner::TopRight | Corner::BottomRight  => page_size.0 - WATERMARK_MARGIN - width,
+        };
+        let y = match self.corner {
+            Corner::TopLeft | Corner::TopRight      => page_size.1 - WATERMARK_MARGIN - height,
+            Corner::BottomLeft | Corner::BottomRight => WATERMARK_MARGIN,
+        };
+
+        (x, y)
+    }
+
+    /// Draws `text` in the chosen corner of a page. `font` must be the built-in Courier.
+    pub fn draw(&self, layer: &PdfLayerReference, font: &IndirectFontRef, text: &str, page_size: (Mm, Mm)) {
+        let (x, y) = self.text_position(text, page_size);
+
+        // Multiplying by a gray of 1 - opacity darkens the page exactly as black text at that opacity would
+        layer.save_graphics_state();
+        layer.set_blend_mode(BlendMode::Seperable(SeperableBlendMode::Multiply));
+        layer.set_fill_color(Color::Greyscale(Greyscale::new(1.0 - self.opacity.clamp(0.0, 1.0), None)));
+        layer.use_text(text, WATERMARK_FONT_SIZE, x, y, font);
+        layer.restore_graphics_state();
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl Corner {
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::TopLeft       => text.corner_top_left,
+            Self::TopRight      => text.corner_top_right,
+            Self::BottomLeft    => text.corner_bottom_left,
+            Self::BottomRight   => text.corner_bottom_right,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Width, height, left and bottom of a placement in millimeters, rounded to hundredths
+    fn bounds(placement: &PagePlacement) -> [f32; 4] {
+        [placement.width, placement.height, placement.left, placement.bottom].map(|mm| (mm.0 * 100.0).round() / 100.0)
+    }
+
+    #[test]
+    fn whole_pages_without_margins_are_stretched_over_the_page() {
+        // One by two inches on A4
+        let placement = PagePlacement::new([300, 600], false, PaperSize::A4, Margins::default());
+        assert_eq!(bounds(&placement), [210.0, 297.0, 0.0, 0.0]);
+        assert!((placement.scale_x - 210.0 / MM_PER_INCH).abs() < 1e-4);
+        assert!((placement.scale_y - 297.0 / MM_PER_INCH / 2.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn margins_keep_the_aspect_ratio_and_center_within_the_area() {
+        // The area left is 170 by 237 mm, so a one by two inch page is limited by the height
+        let margins = Margins { top: 20.0, right: 10.0, bottom: 40.0, left: 30.0 };
+        let placement = PagePlacement::new([300, 600], false, PaperSize::A4, margins);
+        assert_eq!(bounds(&placement), [118.5, 237.0, 55.75, 40.0]);
+        assert_eq!(placement.scale_x, placement.scale_y);
+
+        // As much space is left either side of the image
+        let right_space = 210.0 - margins.right - placement.left.0 - placement.width.0;
+        assert!((right_space - (placement.left.0 - margins.left)).abs() < 1e-3);
+    }
+
+    #[test]
+    fn segments_are_anchored_to_the_top_margin() {
+        // A two by one inch segment fills the width and hangs from the top
+        let placement = PagePlacement::new([600, 300], true, PaperSize::A4, Margins::default());
+        assert_eq!(bounds(&placement), [210.0, 105.0, 0.0, 192.0]);
+
+        let margins = Margins { top: 20.0, right: 10.0, bottom: 40.0, left: 30.0 };
+        let placement = PagePlacement::new([600, 300], true, PaperSize::A4, margins);
+        assert_eq!(bounds(&placement), [170.0, 85.0, 30.0, 192.0]);
+    }
+}
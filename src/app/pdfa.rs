@@ -0,0 +1,331 @@
+use std::error::Error;
+
+use printpdf::{CustomPdfConformance, OffsetDateTime, PdfConformance, PdfDocument, PdfDocumentReference, lopdf::{Dictionary, Document, Object, ObjectId, Stream}};
+
+/// Producer recorded in both the document information dictionary and the XMP metadata, which PDF/A requires to agree
+const PRODUCER: &str = concat!("SlickScan ", env!("CARGO_PKG_VERSION"));
+const OUTPUT_CONDITION: &str = "sRGB IEC61966-2.1";
+
+/// An empty document to pass to `convert_to_pdfa` once filled. It leaves out the color profile and metadata printpdf adds
+/// by default, which would otherwise be carried along unused.
+pub fn empty_document() -> PdfDocumentReference {
+    PdfDocument::empty("").with_conformance(PdfConformance::Custom(CustomPdfConformance::default()))
+}
+
+/// Rewrites a document saved by printpdf as PDF/A-2b and checks the result.
+///
+/// printpdf's own PDF/A modes embed a CMYK print profile under a PDF/X output intent and leave out the PDF/A identification,
+/// so the parts it gets wrong are replaced here: an sRGB output intent, XMP metadata, a binary header comment, no image
+/// interpolation and appearance streams for annotations.
+pub fn convert_to_pdfa(bytes: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut doc = Document::load_mem(bytes)?;
+    // The header must be followed by a comment of at least four bytes above 127. lopdf writes the version straight after
+    // `%PDF-` and counts the bytes it writes, so adding the comment here keeps the cross-reference offsets right.
+    doc.version = String::from("1.7\n%\u{e2}\u{e3}\u{cf}\u{d3}");
+
+    let now = OffsetDateTime::now_utc();
+    let info_id = doc.add_object(Dictionary::from_iter(vec![
+        ("Producer", Object::string_literal(PRODUCER)),
+        ("CreationDate", Object::string_literal(info_date(now))),
+        ("ModDate", Object::string_literal(info_date(now))),
+    ]));
+    doc.trailer.set("Info", Object::Reference(info_id));
+
+    let metadata_id = doc.add_object(xmp_metadata(now));
+    let profile_id = doc.add_object(Stream::new(
+        Dictionary::from_iter(vec![("N", Object::Integer(3))]),
+        srgb_icc_profile(),
+    ));
+    let output_intent = Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"OutputIntent".to_vec())),
+        ("S", Object::Name(b"GTS_PDFA1".to_vec())),
+        ("OutputConditionIdentifier", Object::string_literal(OUTPUT_CONDITION)),
+        ("RegistryName", Object::string_literal("http://www.color.org")),
+        ("Info", Object::string_literal(OUTPUT_CONDITION)),
+        ("DestinationOutputProfile", Object::Reference(profile_id)),
+    ]);
+
+    let catalog = doc.catalog_mut()?;
+    catalog.set("Metadata", Object::Reference(metadata_id));
+    catalog.set("OutputIntents", Object::Array(vec![output_intent.into()]));
+    // Every optional content configuration needs a name
+    if let Ok(Object::Dictionary(properties)) = catalog.get_mut(b"OCProperties") {
+        if let Ok(Object::Dictionary(default_config)) = properties.get_mut(b"D") {
+            default_config.set("Name", Object::string_literal("Layers"));
+        }
+    }
+
+    for object in doc.objects.values_mut() {
+        if let Object::Stream(stream) = object {
+            if stream.dict.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"Image") {
+                stream.dict.remove(b"Interpolate");
+                // printpdf writes a null soft mask when there is none
+                if matches!(stream.dict.get(b"SMask"), Ok(Object::Null)) {
+                    stream.dict.remove(b"SMask");
+                }
+            }
+        }
+    }
+
+    let appearance = Object::Reference(doc.add_object(note_appearance()));
+    let page_ids: Vec<ObjectId> = doc.page_iter().collect();
+    for page_id in page_ids {
+        if let Ok(Object::Array(annotations)) = doc.get_dictionary_mut(page_id)?.get_mut(b"Annots") {
+            for annotation in annotations.iter_mut().filter_map(|annotation| annotation.as_dict_mut().ok()) {
+                // Printed, and for notes also fixed in size and orientation
+                let is_note = annotation.get(b"Subtype").and_then(Object::as_name).is_ok_and(|subtype| subtype == b"Text");
+                annotation.set("F", Object::Integer(if is_note { 4 | 8 | 16 } else { 4 }));
+                if !annotation.has(b"AP") {
+                    annotation.set("AP", Dictionary::from_iter(vec![("N", appearance.clone())]));
+                }
+            }
+        }
+    }
+
+    // Drops the replaced information dictionary
+    doc.prune_objects();
+
+    let mut output = Vec::new();
+    doc.save_to(&mut output)?;
+    check_conformance(&Document::load_mem(&output)?)?;
+
+    Ok(output)
+}
+
+/// Checks the parts of PDF/A-2b that depend on how the document was written. This is not a full validator, but catches
+/// anything this app could add that would break conformance, such as unembedded fonts.
+pub fn check_conformance(doc: &Document) -> Result<(), Box<dyn Error>> {
+    if doc.trailer.has(b"Encrypt") {
+        return Err("PDF/A documents cannot be encrypted".into());
+    }
+    if !doc.trailer.get(b"ID").and_then(Object::as_array).is_ok_and(|id| id.len() == 2) {
+        return Err("PDF/A documents need a file identifier".into());
+    }
+
+    let catalog = doc.catalog()?;
+    let metadata = catalog.get(b"Metadata").and_then(Object::as_reference).and_then(|id| doc.get_object(id)).and_then(Object::as_stream)
+        .map_err(|_| "PDF/A documents need XMP metadata")?;
+    if metadata.dict.has(b"Filter") || !String::from_utf8_lossy(&metadata.content).contains("<pdfaid:part>2</pdfaid:part>") {
+        return Err("The XMP metadata does not identify the document as PDF/A-2".into());
+    }
+
+    let has_pdfa_intent = catalog.get(b"OutputIntents").and_then(Object::as_array).is_ok_and(|intents| intents.iter()
+        .filter_map(|intent| doc.dereference(intent).ok().and_then(|(_, intent)| intent.as_dict().ok()))
+        .any(|intent| intent.get(b"S").and_then(Object::as_name).is_ok_and(|s| s == b"GTS_PDFA1") && intent.has(b"DestinationOutputProfile")));
+    if !has_pdfa_intent {
+        return Err("PDF/A documents need an output intent with a color profile".into());
+    }
+
+    for object in doc.objects.values() {
+        let dict = match object {
+            Object::Dictionary(dict) => dict,
+            Object::Stream(stream) => &stream.dict,
+            _ => continue,
+        };
+        let type_name = dict.get(b"Type").and_then(Object::as_name).unwrap_or_default();
+        let subtype = dict.get(b"Subtype").and_then(Object::as_name).unwrap_or_default();
+
+        if subtype == b"Image" && dict.get(b"Interpolate").and_then(Object::as_bool).unwrap_or(false) {
+            return Err("PDF/A documents cannot use image interpolation".into());
+        }
+
+        // Type 0 fonts are embedded through their descendants and Type 3 fonts are drawn by the document itself
+        if type_name == b"Font" && subtype != b"Type0" && subtype != b"Type3" {
+            let embedded = dict.get(b"FontDescriptor").and_then(|descriptor| doc.dereference(descriptor)).and_then(|(_, descriptor)| descriptor.as_dict())
+                .is_ok_and(|descriptor| [&b"FontFile"[..], b"FontFile2", b"FontFile3"].iter().any(|key| descriptor.has(key)));
+            if !embedded {
+                return Err("PDF/A documents can only use embedded fonts".into());
+            }
+        }
+    }
+
+    for page_id in doc.page_iter() {
+        for annotation in doc.get_page_annotations(page_id) {
+            let subtype = annotation.get(b"Subtype").and_then(Object::as_name).unwrap_or_default();
+            let printed = annotation.get(b"F").and_then(Object::as_i64).is_ok_and(|flags| flags & 4 != 0);
+            if !printed || (subtype != b"Popup" && subtype != b"Link" && !annotation.has(b"AP")) {
+                return Err("PDF/A annotations must be printable and have an appearance".into());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `D:YYYYMMDDHHmmSS+00'00'`, the date format of the document information dictionary
+fn info_date(time: OffsetDateTime) -> String {
+    format!("D:{:04}{:02}{:02}{:02}{:02}{:02}+00'00'", time.year(), u8::from(time.month()), time.day(), time.hour(), time.minute(), time.second())
+}
+
+/// The same instant in the XMP date format, so the two dictionaries agree
+fn xmp_date(time: OffsetDateTime) -> String {
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}+00:00", time.year(), u8::from(time.month()), time.day(), time.hour(), time.minute(), time.second())
+}
+
+fn xmp_metadata(time: OffsetDateTime) -> Stream {
+    let date = xmp_date(time);
+    let packet = format!(r#"<?xpacket begin="{bom}" id="W5M0MpCehiHzreSzNTczkc9d"?>
+<x:xmpmeta xmlns:x="adobe:ns:meta/">
+  <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+    <rdf:Description rdf:about=""
+        xmlns:pdfaid="http://www.aiim.org/pdfa/ns/id/"
+        xmlns:xmp="http://ns.adobe.com/xap/1.0/"
+        xmlns:pdf="http://ns.adobe.com/pdf/1.3/"
+        xmlns:dc="http://purl.org/dc/elements/1.1/">
+      <pdfaid:part>2</pdfaid:part>
+      <pdfaid:conformance>B</pdfaid:conformance>
+      <xmp:CreateDate>{date}</xmp:CreateDate>
+      <xmp:ModifyDate>{date}</xmp:ModifyDate>
+      <xmp:MetadataDate>{date}</xmp:MetadataDate>
+      <pdf:Producer>{PRODUCER}</pdf:Producer>
+      <dc:format>application/pdf</dc:format>
+    </rdf:Description>
+  </rdf:RDF>
+</x:xmpmeta>
+<?xpacket end="w"?>"#, bom = '\u{feff}');
+
+    // Metadata must stay readable without decoding, so it is never compressed
+    let mut stream = Stream::new(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"Metadata".to_vec())),
+        ("Subtype", Object::Name(b"XML".to_vec())),
+    ]), packet.into_bytes());
+    stream.allows_compression = false;
+    stream
+}
+
+/// Appearance for note annotations: a small yellow square in the annotation's rectangle
+fn note_appearance() -> Stream {
+    Stream::new(Dictionary::from_iter(vec![
+        ("Type", Object::Name(b"XObject".to_vec())),
+        ("Subtype", Object::Name(b"Form".to_vec())),
+        ("BBox", vec![0.into(), 0.into(), 20.into(), 20.into()].into()),
+    ]), b"1 0.9 0.3 rg 0 0 0 RG 1 w 0.5 0.5 19 19 re B".to_vec())
+}
+
+/// `value` in the ICC s15Fixed16Number encoding
+fn s15_fixed16(value: f64) -> [u8; 4] {
+    #[allow(clippy::cast_possible_truncation)]
+    let fixed = (value * 65536.0).round() as i32;
+    fixed.to_be_bytes()
+}
+
+/// A version 2 ICC profile for sRGB, built in code so no profile file has to be shipped.
+/// The primaries are adapted to the D50 connection space, and the tone curve is sampled from the sRGB transfer function.
+fn srgb_icc_profile() -> Vec<u8> {
+    const CURVE_POINTS: usize = 1024;
+
+    let xyz = |x: f64, y: f64, z: f64| [&b"XYZ \0\0\0\0"[..], &s15_fixed16(x), &s15_fixed16(y), &s15_fixed16(z)].concat();
+
+    let mut description = b"desc\0\0\0\0".to_vec();
+    description.extend_from_slice(&u32::try_from(OUTPUT_CONDITION.len() + 1).unwrap_or_default().to_be_bytes());
+    description.extend_from_slice(OUTPUT_CONDITION.as_bytes());
+    // Terminator, then empty Unicode and ScriptCode descriptions
+    description.extend_from_slice(&[0; 1 + 8 + 3 + 67]);
+
+    let mut curve = b"curv\0\0\0\0".to_vec();
+    curve.extend_from_slice(&u32::try_from(CURVE_POINTS).unwrap_or_default().to_be_bytes());
+    for point in 0..CURVE_POINTS {
+        #[allow(clippy::cast_precision_loss)]
+        let encoded = point as f64 / (CURVE_POINTS - 1) as f64;
+        let linear = if encoded <= 0.04045 { encoded / 12.92 } else { ((encoded + 0.055) / 1.055).powf(2.4) };
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_sign_loss)]
+        let sample = (linear * 65535.0).round() as u16;
+        curve.extend_from_slice(&sample.to_be_bytes());
+    }
+
+    let tags: [(&[u8; 4], Vec<u8>); 7] = [
+        (b"desc", description),
+        (b"cprt", b"text\0\0\0\0No copyright, use freely\0".to_vec()),
+        (b"wtpt", xyz(0.9642, 1.0, 0.8249)),
+        (b"rXYZ", xyz(0.436_074_7, 0.222_504_5, 0.013_932_2)),
+        (b"gXYZ", xyz(0.385_064_9, 0.716_878_6, 0.097_104_5)),
+        (b"bXYZ", xyz(0.143_080_4, 0.060_616_9, 0.714_173_3)),
+        (b"rTRC", curve),
+    ];
+    // The three channels share one tone curve
+    let curve_tag = tags.len() - 1;
+    let entries: Vec<(&[u8; 4], usize)> = tags.iter().enumerate()
+        .map(|(tag, (signature, _))| (*signature, tag))
+        .chain([(b"gTRC", curve_tag), (b"bTRC", curve_tag)])
+        .collect();
+
+    let table_size = 4 + 12 * entries.len();
+    let mut offsets = Vec::with_capacity(tags.len());
+    let mut data = Vec::new();
+    for (_, tag) in &tags {
+        offsets.push(128 + table_size + data.len());
+        data.extend_from_slice(tag);
+        // Tags start on four-byte boundaries
+        data.resize(data.len().next_multiple_of(4), 0);
+    }
+    let size = 128 + table_size + data.len();
+    let be32 = |value: usize| u32::try_from(value).unwrap_or_default().to_be_bytes();
+
+    let mut profile = Vec::with_capacity(size);
+    profile.extend_from_slice(&be32(size));
+    profile.extend_from_slice(&[0; 4]);
+    // Version 2.1, display device, RGB data, XYZ connection space
+    profile.extend_from_slice(b"\x02\x10\0\0mntrRGB XYZ ");
+    // Creation date: 2024-01-01 00:00:00
+    for field in [2024u16, 1, 1, 0, 0, 0] {
+        profile.extend_from_slice(&field.to_be_bytes());
+    }
+    profile.extend_from_slice(b"acsp");
+    // Platform, flags, manufacturer, model, attributes and perceptual rendering intent are all left unset
+    profile.extend_from_slice(&[0; 28]);
+    profile.extend_from_slice(&[s15_fixed16(0.9642), s15_fixed16(1.0), s15_fixed16(0.8249)].concat());
+    profile.resize(128, 0);
+
+    profile.extend_from_slice(&be32(entries.len()));
+    for (signature, tag) in entries {
+        profile.extend_from_slice(signature);
+        profile.extend_from_slice(&be32(offsets[tag]));
+        profile.extend_from_slice(&be32(tags[tag].1.len()));
+    }
+    profile.extend_from_slice(&data);
+
+    profile
+}
+
+#[cfg(test)]
+mod tests {
+    use printpdf::{BuiltinFont, ColorBits, ColorSpace, Image, ImageTransform, ImageXObject, Mm, Px};
+
+    use super::*;
+    use crate::app::pdf::note_annotation;
+
+    /// A one page document with an interpolated image and a note, as the app writes them
+    fn sample_document() -> PdfDocumentReference {
+        let doc = empty_document();
+        let (page, layer) = doc.add_page(Mm(210.0), Mm(297.0), "Layer 1");
+        doc.get_page(page).extend_with(note_annotation("Note", Mm(297.0)));
+        Image::from(ImageXObject {
+            width: Px(2),
+            height: Px(2),
+            color_space: ColorSpace::Rgb,
+            bits_per_component: ColorBits::Bit8,
+            interpolate: true,
+            image_data: vec![128; 2 * 2 * 3],
+            image_filter: None,
+            clipping_bbox: None,
+            smask: None,
+        }).add_to_layer(doc.get_page(page).get_layer(layer), ImageTransform::default());
+        doc
+    }
+
+    #[test]
+    fn converted_documents_conform() {
+        let output = convert_to_pdfa(&sample_document().save_to_bytes().unwrap()).unwrap();
+        assert!(output.starts_with(b"%PDF-1.7\n%"));
+        assert!(output[10..14].iter().all(|&byte| byte > 127));
+        check_conformance(&Document::load_mem(&output).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn unembedded_fonts_do_not_conform() {
+        let doc = sample_document();
+        doc.add_builtin_font(BuiltinFont::Courier).unwrap();
+        assert!(convert_to_pdfa(&doc.save_to_bytes().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use std::{error::Error, fs::File, io::{BufWriter, Write}, path::Path};
+
+use printpdf::{ColorSpace, Pt};
+
+use super::{image::PageFormat, pdf::{deflate, image_encoding, Margins, PagePlacement, PaperSize, PdfCompression}, settings::ImageSmoothing};
+
+/// Object numbers of the catalog and page tree, which are written last but referred to by every page
+const CATALOG_ID: usize = 1;
+const PAGES_ID: usize = 2;
+
+/// Writes a PDF one page at a time, so a long scan can be saved as it runs without holding its pages in memory.
+/// Pages are placed as whole pages are when saving normally. Notes, page breaks and the watermark aren't supported.
+pub struct StreamingPdf {
+    file: BufWriter<File>,
+    /// Bytes written so far, which is where the next object starts
+    position: usize,
+    /// Start of each object, by object number minus one. The catalog and page tree are filled in by `finish`.
+    offsets: Vec<usize>,
+    page_ids: Vec<usize>,
+    compression: PdfCompression,
+}
+
+impl StreamingPdf {
+    pub fn create(path: &Path, compression: PdfCompression) -> Result<Self, Box<dyn Error>> {
+        let mut pdf = Self {
+            file: BufWriter::new(File::create(path)?),
+            position: 0,
+            offsets: vec![0; PAGES_ID],
+            page_ids: Vec::new(),
+            compression,
+        };
+        // The comment of high bytes marks the file as binary
+        pdf.write(b"%PDF-1.7\n%\xe2\xe3\xcf\xd3\n")?;
+
+        Ok(pdf)
+    }
+
+    /// Appends a page showing 8-bit RGB `pixels`, stored as compactly as `format` allows
+    pub fn add_page(&mut self, pixels: &[u8], size: [usize; 2], format: PageFormat, paper: PaperSize, margins: Margins, smoothing: ImageSmoothing) -> Result<(), Box<dyn Error>> {
+        let (color_space, bits, data) = image_encoding(pixels, size, format);
+        let grayscale = matches!(color_space, ColorSpace::Greyscale);
+
+        let (filter, data) = match deflate(&data, self.compression)? {
+            Some(compressed) => ("/Filter /FlateDecode ", compressed),
+            None => ("", data),
+        };
+        let color_space_name: &str = color_space.into();
+        let image_id = self.write_stream(&format!(
+            "/Type /XObject /Subtype /Image /Width {} /Height {} /ColorSpace /{color_space_name} /BitsPerComponent {} /Interpolate {} {filter}",
+            size[0], size[1], i64::from(bits), smoothing.applies(grayscale),
+        ), &data)?;
+
+        let placement = PagePlacement::new(size, false, paper, margins);
+        let contents = format!("q {} 0 0 {} {} {} cm /Im0 Do Q",
+            Pt::from(placement.width).0, Pt::from(placement.height).0, Pt::from(placement.left).0, Pt::from(placement.bottom).0);
+        let contents_id = self.write_stream("", contents.as_bytes())?;
+
+        let (width, height) = paper.size();
+        let page_id = self.write_object(&format!(
+            "<< /Type /Page /Parent {PAGES_ID} 0 R /MediaBox [0 0 {} {}] /Resources << /XObject << /Im0 {image_id} 0 R >> >> /Contents {contents_id} 0 R >>",
+            Pt::from(width).0, Pt::from(height).0,
+        ))?;
+        self.page_ids.push(page_id);
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    /// Writes the page tree, catalog and cross-reference table, completing the file
+    pub fn finish(mut self) -> Result<(), Box<dyn Error>> {
+        let kids: Vec<String> = self.page_ids.iter().map(|id| format!("{id} 0 R")).collect();
+        self.write_object_with_id(PAGES_ID, &format!("<< /Type /Pages /Kids [{}] /Count {} >>", kids.join(" "), kids.len()))?;
+        self.write_object_with_id(CATALOG_ID, &format!("<< /Type /Catalog /Pages {PAGES_ID} 0 R >>"))?;
+
+        let xref_start = self.position;
+        let mut xref = format!("xref\n0 {}\n0000000000 65535 f \n", self.offsets.len() + 1);
+        for offset in &self.offsets {
+            xref += &format!("{offset:010} 00000 n \n");
+        }
+        xref += &format!("trailer\n<< /Size {} /Root {CATALOG_ID} 0 R >>\nstartxref\n{xref_start}\n%%EOF\n", self.offsets.len() + 1);
+        self.write(xref.as_bytes())?;
+        self.file.flush()?;
+
+        Ok(())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+        self.file.write_all(bytes)?;
+        self.position += bytes.len();
+        Ok(())
+    }
+
+    fn write_object(&mut self, body: &str) -> Result<usize, Box<dyn Error>> {
+        self.offsets.push(0);
+        let id = self.offsets.len();
+        self.write_object_with_id(id, body)?;
+        Ok(id)
+    }
+
+    fn write_object_with_id(&mut self, id: usize, body: &str) -> Result<(), Box<dyn Error>> {
+        self.offsets[id - 1] = self.position;
+        self.write(format!("{id} 0 obj\n{body}\nendobj\n").as_bytes())
+    }
+
+    /// Writes a stream whose dictionary holds `entries` along with its length
+    fn write_stream(&mut self, entries: &str, content: &[u8]) -> Result<usize, Box<dyn Error>> {
+        self.offsets.push(0);
+        let id = self.offsets.len();
+        self.offsets[id - 1] = self.position;
+        self.write(format!("{id} 0 obj\n<< {entries}/Length {} >>\nstream\n", content.len()).as_bytes())?;
+        self.write(content)?;
+        self.write(b"\nendstream\nendobj\n")?;
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use printpdf::lopdf::{Document, Object};
+
+    use super::*;
+
+    #[test]
+    fn streamed_pages_load_as_a_pdf() {
+        let path = std::env::temp_dir().join(format!("slickscan-stream-test-{}.pdf", std::process::id()));
+        let mut pdf = StreamingPdf::create(&path, PdfCompression::Balanced).unwrap();
+        pdf.add_page(&[200; 4 * 3 * 3], [4, 3], PageFormat::Color, PaperSize::Letter, Margins::default(), ImageSmoothing::Never).unwrap();
+        pdf.add_page(&[50; 2 * 2 * 3], [2, 2], PageFormat::Gray, PaperSize::A4, Margins::default(), ImageSmoothing::Never).unwrap();
+        pdf.finish().unwrap();
+
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let document = Document::load_mem(&bytes).unwrap();
+
+        let pages = document.get_pages();
+        assert_eq!(pages.len(), 2);
+        for page_id in pages.values() {
+            let (resources, _) = document.get_page_resources(*page_id);
+            let image = resources.unwrap().get(b"XObject").and_then(Object::as_dict).and_then(|images| images.get(b"Im0")).unwrap();
+            assert!(document.get_object(image.as_reference().unwrap()).is_ok());
+        }
+    }
+}
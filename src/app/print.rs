@@ -0,0 +1,33 @@
+use std::{error::Error, path::Path, process::{Command, Output}};
+
+use crate::util::{parse_default_printer, parse_printer_list};
+
+/// Printers set up in CUPS, with the default one first. Empty if there are none or CUPS isn't installed.
+pub fn available_printers() -> Vec<String> {
+    let lpstat = |argument: &str| Command::new("lpstat").arg(argument).output().ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned());
+
+    let mut printers = lpstat("-a").map(|list| parse_printer_list(&list)).unwrap_or_default();
+    let default = lpstat("-d").and_then(|output| parse_default_printer(&output));
+    if let Some(position) = default.and_then(|default| printers.iter().position(|printer| *printer == default)) {
+        let default = printers.remove(position);
+        printers.insert(0, default);
+    }
+
+    printers
+}
+
+/// Queues the file at `path` on `printer`. CUPS copies the file when queueing, so it can be removed afterwards.
+pub fn print_file(path: &Path, printer: &str, copies: u32) -> Result<(), Box<dyn Error>> {
+    let Output { status, stderr, .. } = Command::new("lp")
+        .args(["-d", printer, "-n", &copies.to_string()])
+        .arg(path)
+        .output()?;
+
+    if status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&stderr).trim().into())
+    }
+}
@@ -1,40 +1,1042 @@
-use sane_scan::{DeviceHandle, DeviceOption, DeviceOptionValue};
+use std::{ffi::CString, sync::{Arc, Mutex, mpsc::{self, Receiver, Sender, TryRecvError}}, thread::{self, JoinHandle}, time::{Duration, Instant}};
 
-use crate::util::{cstring_to_string, string_to_cstring, sane_fixed_to_float, float_to_sane_fixed};
+use eframe::egui::Context;
+use sane_scan::{Device, DeviceHandle, DeviceOption, DeviceOptionValue, OptionCapability, OptionConstraint, ValueType, ValueUnit, Frame, Parameters};
+
+use serde::{Deserialize, Serialize};
+
+use super::{image::PageFormat, testpattern::{TestPatternDevice, is_test_pattern_device}};
+use crate::{util::{cstring_to_string, string_to_cstring, sane_fixed_to_float, float_to_sane_fixed, repeat_all_elements, unpack_bits}, MM_PER_INCH};
+
+/// The device operations the app relies on, so scanning logic can also run against a mock device
+pub trait ScanDevice {
+    fn get_options(&mut self) -> Result<Vec<DeviceOption>, sane_scan::Error>;
+    fn get_option(&mut self, option: &DeviceOption) -> Result<DeviceOptionValue, sane_scan::Error>;
+    fn set_option(&mut self, option: &DeviceOption, value: DeviceOptionValue) -> Result<(), sane_scan::Error>;
+    fn set_option_auto(&mut self, option: &DeviceOption) -> Result<(), sane_scan::Error>;
+    fn start_scan(&mut self) -> Result<(), sane_scan::Error>;
+    fn get_parameters(&mut self) -> Result<Parameters, sane_scan::Error>;
+    /// Reads the next chunk of the current frame into `buffer`, returning `None` once the frame is complete
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, sane_scan::Error>;
+    /// Aborts the current scan, making a read in progress return early
+    fn cancel(&mut self);
+}
+
+impl ScanDevice for DeviceHandle {
+    fn get_options(&mut self) -> Result<Vec<DeviceOption>, sane_scan::Error> {
+        DeviceHandle::get_options(self)
+    }
+
+    fn get_option(&mut self, option: &DeviceOption) -> Result<DeviceOptionValue, sane_scan::Error> {
+        DeviceHandle::get_option(self, option)
+    }
+
+    fn set_option(&mut self, option: &DeviceOption, value: DeviceOptionValue) -> Result<(), sane_scan::Error> {
+        DeviceHandle::set_option(self, option, value).map(|_| ())
+    }
+
+    fn set_option_auto(&mut self, option: &DeviceOption) -> Result<(), sane_scan::Error> {
+        DeviceHandle::set_option_auto(self, option).map(|_| ())
+    }
+
+    fn start_scan(&mut self) -> Result<(), sane_scan::Error> {
+        DeviceHandle::start_scan(self)
+    }
+
+    fn get_parameters(&mut self) -> Result<Parameters, sane_scan::Error> {
+        DeviceHandle::get_parameters(self)
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, sane_scan::Error> {
+        DeviceHandle::read(self, buffer)
+    }
+
+    fn cancel(&mut self) {
+        DeviceHandle::cancel(self);
+    }
+}
 
 pub struct ThDeviceHandle {
-    pub handle: DeviceHandle,
+    pub handle: Box<dyn ScanDevice>,
 }
 
 unsafe impl Send for ThDeviceHandle {}
 
+const BUTTON_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug)]
+pub enum PageReadError {
+    Reading(sane_scan::Error),
+    Parameters(sane_scan::Error),
+}
+
+/// Order of the color channels in a device's RGB frames. SANE specifies RGB, but some backends deliver BGR.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelOrder {
+    #[default]
+    Rgb,
+    Bgr,
+}
+
+/// Bytes requested per read. Kept small so a cancel requested while a chunk is being read takes effect soon after.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Collects a whole frame from `read_chunk`, which fills the buffer and returns how much it wrote, or `None` at the end of the frame
+fn read_frame(mut read_chunk: impl FnMut(&mut [u8]) -> Result<Option<usize>, sane_scan::Error>) -> Result<Vec<u8>, PageReadError> {
+    let mut data = Vec::new();
+    let mut chunk = vec![0; READ_CHUNK_SIZE];
+    while let Some(length) = read_chunk(&mut chunk).map_err(PageReadError::Reading)? {
+        data.extend_from_slice(&chunk[..length]);
+    }
+
+    Ok(data)
+}
+
+/// Reads the current page from the device and converts it to 8-bit RGB, returning its dimensions and pixels
+pub fn read_rgb_page(device: &mut dyn ScanDevice, order: ChannelOrder) -> Result<RgbPage, PageReadError> {
+    let scanned_pixels = read_frame(|buffer| device.read(buffer))?;
+    let parameters = device.get_parameters().map_err(PageReadError::Parameters)?;
+
+    Ok(to_rgb_page(&parameters, scanned_pixels, order))
+}
+
+/// Like `read_rgb_page`, but only locks the handle for each chunk. Setting `interrupt` cancels the page before the next chunk,
+/// so cancelling doesn't have to wait for the handle while a blocking read holds it.
+pub fn read_shared_rgb_page(handle: &Mutex<ThDeviceHandle>, order: ChannelOrder, interrupt: &Mutex<bool>) -> Result<RgbPage, PageReadError> {
+    let scanned_pixels = read_frame(|buffer| {
+        let mut handle = handle.lock().unwrap();
+        if *interrupt.lock().unwrap() {
+            handle.handle.cancel();
+        }
+        handle.handle.read(buffer)
+    })?;
+    let parameters = handle.lock().unwrap().handle.get_parameters().map_err(PageReadError::Parameters)?;
+
+    Ok(to_rgb_page(&parameters, scanned_pixels, order))
+}
+
+/// A page converted to 8-bit RGB, along with the format it was scanned in
+pub struct RgbPage {
+    pub size: [usize; 2],
+    pub pixels: Vec<u8>,
+    pub format: PageFormat,
+    /// Whether every line the device announced arrived. Always false when the device didn't know the page length in advance.
+    pub complete: bool,
+}
+
+fn to_rgb_page(parameters: &Parameters, scanned_pixels: Vec<u8>, order: ChannelOrder) -> RgbPage {
+    let bytes_per_line = TryInto::<usize>::try_into(parameters.bytes_per_line).expect("Failed to convert `bytes_per_line` to unsigned");
+    let lines = scanned_pixels.len() / bytes_per_line;
+
+    let channels = match parameters.format {
+        Frame::Rgb => 3,
+        _ => 1,
+    };
+
+    // Lineart packs eight samples into each byte, and lines may end in padding bits
+    let (pixels_per_line, mut samples) = if parameters.depth == 1 {
+        let pixels_per_line = TryInto::<usize>::try_into(parameters.pixels_per_line).expect("Failed to convert `pixels_per_line` to unsigned");
+        // A set bit is black in gray frames but full intensity in color ones
+        let set_value = if channels == 1 { 0 } else { u8::MAX };
+        let samples = scanned_pixels.chunks_exact(bytes_per_line)
+            .flat_map(|line| unpack_bits(line).take(pixels_per_line * channels))
+            .map(|bit| if bit { set_value } else { !set_value })
+            .collect();
+        (pixels_per_line, samples)
+    } else {
+        (bytes_per_line / channels, scanned_pixels)
+    };
+
+    let pixels = match parameters.format {
+        Frame::Rgb => {
+            if order == ChannelOrder::Bgr {
+                for pixel in samples.chunks_exact_mut(3) {
+                    pixel.swap(0, 2);
+                }
+            }
+            samples
+        },
+        _ => repeat_all_elements(samples, 3),
+    };
+
+    let complete = usize::try_from(parameters.lines).is_ok_and(|expected| lines >= expected);
+    RgbPage { size: [pixels_per_line, lines], pixels, format: page_format(parameters), complete }
+}
+
+fn page_format(parameters: &Parameters) -> PageFormat {
+    match (parameters.format, parameters.depth) {
+        (Frame::Gray, 1)    => PageFormat::Lineart,
+        (Frame::Gray, _)    => PageFormat::Gray,
+        _                   => PageFormat::Color,
+    }
+}
+
+/// Starts the next page of a multi-page scan. Some feeders report spurious errors between pages,
+/// so a failure is retried up to `retries` times, `delay` apart, before the feeder is considered empty.
+/// Returns whether a page was started.
+pub fn start_next_page(handle: &Mutex<ThDeviceHandle>, retries: u32, delay: Duration, interrupt: &Mutex<bool>) -> bool {
+    for attempt in 0..=retries {
+        if attempt > 0 {
+            thread::sleep(delay);
+        }
+        if *interrupt.lock().unwrap() {
+            return false;
+        }
+
+        match handle.lock().unwrap().handle.start_scan() {
+            Ok(()) => return true,
+            Err(error) if attempt < retries => println!("Failed to start next page, retrying: {error}"),
+            Err(_) => {},
+        }
+    }
+
+    false
+}
+
+/// How often a paused scan checks whether it was resumed or cancelled
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Holds a multi-page scan between pages until it is resumed, so paper can be added to the feeder.
+/// Returns early if the scan is cancelled.
+pub fn wait_while_paused(pause: &Mutex<bool>, interrupt: &Mutex<bool>) {
+    while *pause.lock().unwrap() && !*interrupt.lock().unwrap() {
+        thread::sleep(PAUSE_POLL_INTERVAL);
+    }
+}
+
+/// How long to wait for a device to open before giving up on it
+const DEVICE_OPEN_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub enum OpenProgress {
+    Pending,
+    Opened(ThDeviceHandle),
+    Failed(String),
+    TimedOut,
+}
+
+/// Opens a device on a background thread so an unresponsive scanner can't freeze the UI.
+/// SANE has no way to interrupt an open in progress, so dropping the opener abandons the thread;
+/// if the device does open afterwards, its handle is dropped (closing it) when the result can't be delivered.
+pub struct DeviceOpener {
+    started: Instant,
+    result: Receiver<Result<ThDeviceHandle, sane_scan::Error>>,
+}
+
+impl DeviceOpener {
+    pub fn start(device: Device, ctx: Context) -> Self {
+        let (sender, result) = mpsc::channel();
+
+        thread::spawn(move || {
+            let opened = if is_test_pattern_device(&device) {
+                Ok(ThDeviceHandle { handle: Box::new(TestPatternDevice::default()) })
+            } else {
+                device.open().map(|handle| ThDeviceHandle { handle: Box::new(handle) })
+            };
+            if sender.send(opened).is_ok() {
+                ctx.request_repaint();
+            }
+        });
+
+        Self { started: Instant::now(), result }
+    }
+
+    pub fn poll(&self) -> OpenProgress {
+        match self.result.try_recv() {
+            Ok(Ok(handle)) => OpenProgress::Opened(handle),
+            Ok(Err(error)) => OpenProgress::Failed(error.to_string()),
+            Err(TryRecvError::Empty) if self.started.elapsed() < DEVICE_OPEN_TIMEOUT => OpenProgress::Pending,
+            Err(TryRecvError::Empty) => OpenProgress::TimedOut,
+            Err(TryRecvError::Disconnected) => OpenProgress::Failed(String::from("the opening thread terminated abnormally")),
+        }
+    }
+}
+
+/// Sensors are read-only options reporting hardware state, such as a scan button on the device
+pub fn is_sensor_option(option: &DeviceOption) -> bool {
+    option.cap.contains(OptionCapability::SOFT_DETECT)
+        && !option.cap.contains(OptionCapability::SOFT_SELECT)
+        && !option.cap.contains(OptionCapability::INACTIVE)
+        && matches!(option.type_, ValueType::Bool | ValueType::Int)
+}
+
+/// Names of button options that commonly keep the device busy for a while, such as calibration or cleaning
+const LONG_RUNNING_BUTTON_NAMES: [&str; 2] = ["calibrate", "clean"];
+
+pub fn is_long_running_button(option: &DeviceOption) -> bool {
+    matches!(option.type_, ValueType::Button)
+        && LONG_RUNNING_BUTTON_NAMES.contains(&cstring_to_string(&option.name, "option name").as_str())
+}
+
+/// Activates button options on a background thread so slow ones (e.g. calibration) don't block the UI
+pub struct ButtonTask {
+    thread: JoinHandle<Result<(), sane_scan::Error>>,
+}
+
+impl ButtonTask {
+    pub fn start(handle: Arc<Mutex<ThDeviceHandle>>, option_indices: Vec<i32>, ctx: Context) -> Self {
+        let thread = thread::spawn(move || {
+            let result = (|| {
+                let mut device = handle.lock().unwrap();
+                for option in device.handle.get_options()?.iter().filter(|option| option_indices.contains(&option.option_idx)) {
+                    device.handle.set_option_auto(option)?;
+                }
+                Ok(())
+            })();
+
+            ctx.request_repaint();
+            result
+        });
+
+        Self { thread }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    pub fn finish(self) -> Result<(), String> {
+        match self.thread.join() {
+            Ok(result) => result.map_err(|error| error.to_string()),
+            Err(error) => Err(format!("{error:?}")),
+        }
+    }
+}
+
+/// Well-known SANE names of the options shown in the settings summary, besides the scan area
+pub const SUMMARY_OPTION_NAMES: [&str; 3] = ["resolution", "source", "mode"];
+
+/// Well-known SANE name of the scan resolution option
+pub const RESOLUTION_OPTION_NAME: &str = "resolution";
+
+/// Well-known SANE name of the option choosing between the flatbed, document feeder and so on
+const SOURCE_OPTION_NAME: &str = "source";
+
+/// Well-known SANE names of the options choosing color, gray or lineart scanning and the bits per sample
+pub const MODE_OPTION_NAME: &str = "mode";
+const DEPTH_OPTION_NAME: &str = "depth";
+
+/// Bit depth set along with the gray and color modes, where the device lets it be chosen
+const STANDARD_DEPTH: i32 = 8;
+
+/// Resolutions offered when the device accepts any value within a range
+const PRESET_RESOLUTIONS: [i32; 3] = [150, 300, 600];
+
+pub fn is_resolution_option(option: &DeviceOption) -> bool {
+    cstring_to_string(&option.name, "option name") == RESOLUTION_OPTION_NAME
+}
+
+pub fn is_source_option(option: &DeviceOption) -> bool {
+    cstring_to_string(&option.name, "option name") == SOURCE_OPTION_NAME
+}
+
+pub fn is_mode_option(option: &DeviceOption) -> bool {
+    cstring_to_string(&option.name, "option name") == MODE_OPTION_NAME
+}
+
+pub fn is_depth_option(option: &DeviceOption) -> bool {
+    cstring_to_string(&option.name, "option name") == DEPTH_OPTION_NAME
+}
+
+fn is_settable(option: &DeviceOption) -> bool {
+    !option.cap.contains(OptionCapability::INACTIVE) && option.cap.contains(OptionCapability::SOFT_SELECT)
+}
+
+/// Sets a remembered source, then resolution, skipping any the device doesn't currently offer.
+/// The source goes first because switching it can change which resolutions are available.
+pub fn restore_scan_settings(device: &mut dyn ScanDevice, resolution: Option<i32>, source: Option<&str>) -> Result<(), sane_scan::Error> {
+    if let Some(source) = source {
+        if let Some(option) = device.get_options()?.into_iter().find(|option| is_source_option(option) && is_settable(option)) {
+            let choice = match &option.constraint {
+                OptionConstraint::StringList(list) => list.iter().find(|choice| cstring_to_string(choice, "option choice") == source).cloned(),
+                _ => None,
+            };
+            if let Some(choice) = choice {
+                device.set_option(&option, DeviceOptionValue::String(choice))?;
+            }
+        }
+    }
+
+    if let Some(dpi) = resolution {
+        if let Some(option) = device.get_options()?.into_iter().find(is_resolution_option) {
+            let value = device.get_option(&option)?;
+            // Ranged resolutions only list a few presets, so those accept any value and let the device round it
+            let resolution = ResolutionOption::new(option, &value)
+                .filter(|resolution| resolution.current != dpi)
+                .filter(|resolution| !matches!(resolution.option.constraint, OptionConstraint::WordList(_)) || resolution.choices.contains(&dpi));
+            if let Some(resolution) = resolution {
+                device.set_option(&resolution.option, resolution.value_for(dpi))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// The device's resolution option along with the DPI values offered for quick selection
+pub struct ResolutionOption {
+    pub option: DeviceOption,
+    pub choices: Vec<i32>,
+    pub current: i32,
+}
+
+impl ResolutionOption {
+    pub fn new(option: DeviceOption, value: &DeviceOptionValue) -> Option<Self> {
+        if !is_resolution_option(&option)
+            || option.cap.contains(OptionCapability::INACTIVE)
+            || !option.cap.contains(OptionCapability::SOFT_SELECT) {
+            return None;
+        }
+
+        let is_fixed = match option.type_ {
+            ValueType::Int => false,
+            ValueType::Fixed => true,
+            _ => return None,
+        };
+        let to_dpi = |raw: i32| if is_fixed { fixed_to_dpi(raw) } else { raw };
+
+        let mut choices: Vec<i32> = match &option.constraint {
+            OptionConstraint::WordList(list) => list.iter().map(|&word| to_dpi(word)).collect(),
+            OptionConstraint::Range { range, quant } => PRESET_RESOLUTIONS.iter()
+                .map(|&dpi| snap_to_range(dpi, to_dpi(range.start), to_dpi(range.end), to_dpi(*quant)))
+                .collect(),
+            _ => PRESET_RESOLUTIONS.to_vec(),
+        };
+        choices.sort_unstable();
+        choices.dedup();
+
+        let current = match value {
+            DeviceOptionValue::Int(raw) => *raw,
+            DeviceOptionValue::Fixed(raw) => fixed_to_dpi(*raw),
+            _ => return None,
+        };
+
+        Some(Self { option, choices, current })
+    }
+
+    /// Device option value representing `dpi` in the option's own value type
+    pub fn value_for(&self, dpi: i32) -> DeviceOptionValue {
+        match self.option.type_ {
+            ValueType::Fixed => DeviceOptionValue::Fixed(float_to_sane_fixed(f64::from(dpi))),
+            _ => DeviceOptionValue::Int(dpi),
+        }
+    }
+}
+
+/// A choice of the device's mode option standing for one of the common formats, with the bit depth to set along with it
+struct ColorModeChoice {
+    format: PageFormat,
+    mode: CString,
+    depth: Option<i32>,
+}
+
+/// The device's scan mode option, with its choices mapped onto lineart, gray and color for the quick control
+pub struct ColorModeOption {
+    pub option: DeviceOption,
+    choices: Vec<ColorModeChoice>,
+    /// `None` when the device is set to a mode outside the common ones, such as halftone
+    pub current: Option<PageFormat>,
+    /// The device's own name for its current mode
+    pub current_mode: String,
+}
+
+impl ColorModeOption {
+    /// Builds the control from the mode option and, if the device has one, its separate bit depth option.
+    /// Backends without a lineart mode often scan it as 1-bit gray, so that is offered in its place.
+    pub fn new(option: DeviceOption, value: &DeviceOptionValue, depth: Option<(&DeviceOption, &DeviceOptionValue)>) -> Option<Self> {
+        if !is_mode_option(&option) || !is_settable(&option) {
+            return None;
+        }
+        let (OptionConstraint::StringList(list), DeviceOptionValue::String(current)) = (&option.constraint, value) else {
+            return None;
+        };
+        let depth = depth.filter(|(depth_option, _)| is_depth_option(depth_option) && is_settable(depth_option));
+        let allows_depth = |bits| depth.is_some_and(|(depth_option, _)| depth_allows(depth_option, bits));
+        let standard_depth = allows_depth(STANDARD_DEPTH).then_some(STANDARD_DEPTH);
+
+        let find_mode = |names: &[&str]| list.iter()
+            .find(|choice| names.iter().any(|name| cstring_to_string(choice, "option choice").to_lowercase().contains(name)))
+            .cloned();
+        let gray = find_mode(&["gray", "grey"]);
+        let lineart = find_mode(&["lineart", "binary", "black"]).map(|mode| (mode, None))
+            .or_else(|| gray.clone().filter(|_| allows_depth(1)).map(|mode| (mode, Some(1))));
+
+        let choices: Vec<ColorModeChoice> = [
+            lineart.map(|(mode, depth)| ColorModeChoice { format: PageFormat::Lineart, mode, depth }),
+            gray.map(|mode| ColorModeChoice { format: PageFormat::Gray, mode, depth: standard_depth }),
+            find_mode(&["color", "colour"]).map(|mode| ColorModeChoice { format: PageFormat::Color, mode, depth: standard_depth }),
+        ].into_iter().flatten().collect();
+        if choices.len() < 2 {
+            return None;
+        }
+
+        // When lineart and gray share a mode, the current depth tells them apart
+        let current_depth = match depth {
+            Some((_, DeviceOptionValue::Int(bits))) => Some(*bits),
+            _ => None,
+        };
+        let matching_mode = || choices.iter().filter(|choice| choice.mode == *current);
+        let current_format = matching_mode().find(|choice| choice.depth.is_none_or(|bits| bits == 1) == (current_depth == Some(1)))
+            .or_else(|| matching_mode().next())
+            .map(|choice| choice.format);
+        let current_mode = cstring_to_string(current, "option value");
+
+        Some(Self { option, choices, current: current_format, current_mode })
+    }
+
+    /// Formats the device can be switched to, from fewest to most bits per pixel
+    pub fn formats(&self) -> impl Iterator<Item = PageFormat> + '_ {
+        self.choices.iter().map(|choice| choice.format)
+    }
+
+    /// Switches the device to scan in `format`, setting the bit depth after the mode as the mode may change which depths are allowed
+    pub fn apply(&self, device: &mut dyn ScanDevice, format: PageFormat) -> Result<(), sane_scan::Error> {
+        let Some(choice) = self.choices.iter().find(|choice| choice.format == format) else {
+            return Ok(());
+        };
+
+        device.set_option(&self.option, DeviceOptionValue::String(choice.mode.clone()))?;
+        if let Some(bits) = choice.depth {
+            if let Some(depth) = device.get_options()?.into_iter().find(|option| is_depth_option(option) && is_settable(option) && depth_allows(option, bits)) {
+                device.set_option(&depth, DeviceOptionValue::Int(bits))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn depth_allows(option: &DeviceOption, bits: i32) -> bool {
+    match &option.constraint {
+        OptionConstraint::WordList(list) => list.contains(&bits),
+        OptionConstraint::Range { range, .. } => (range.start..=range.end).contains(&bits),
+        _ => false,
+    }
+}
+
+/// Boolean options that switch duplex scanning on
+const DUPLEX_BOOL_OPTION_NAMES: [&str; 1] = ["duplex"];
+/// String list options that may offer a duplex choice, such as `source` with "ADF Duplex" or `adf-mode` with "Duplex"
+const DUPLEX_CHOICE_OPTION_NAMES: [&str; 3] = ["adf-mode", "source", "scan-source"];
+
+/// A single device option that turns duplex scanning on and off, for the quick toggle
+pub struct DuplexOption {
+    pub option: DeviceOption,
+    /// Values to set for duplex and single-sided scanning, when the option is a list of choices rather than a boolean
+    choices: Option<(CString, CString)>,
+    pub enabled: bool,
+}
+
+impl DuplexOption {
+    /// Whether `option` is worth reading to pass to `new`
+    pub fn may_control_duplex(option: &DeviceOption) -> bool {
+        let name = cstring_to_string(&option.name, "option name");
+        DUPLEX_BOOL_OPTION_NAMES.contains(&name.as_str()) || DUPLEX_CHOICE_OPTION_NAMES.contains(&name.as_str())
+    }
+
+    pub fn new(option: DeviceOption, value: &DeviceOptionValue) -> Option<Self> {
+        if option.cap.contains(OptionCapability::INACTIVE) || !option.cap.contains(OptionCapability::SOFT_SELECT) {
+            return None;
+        }
+
+        let name = cstring_to_string(&option.name, "option name");
+        match (&option.type_, &option.constraint, value) {
+            (ValueType::Bool, _, DeviceOptionValue::Bool(enabled)) if DUPLEX_BOOL_OPTION_NAMES.contains(&name.as_str()) => {
+                let enabled = *enabled;
+                Some(Self { option, choices: None, enabled })
+            },
+            // Some backends report booleans as integers
+            (ValueType::Bool, _, DeviceOptionValue::Int(enabled)) if DUPLEX_BOOL_OPTION_NAMES.contains(&name.as_str()) => {
+                let enabled = *enabled != 0;
+                Some(Self { option, choices: None, enabled })
+            },
+            (ValueType::String, OptionConstraint::StringList(list), DeviceOptionValue::String(current)) if DUPLEX_CHOICE_OPTION_NAMES.contains(&name.as_str()) => {
+                let lowercase = |choice: &CString| cstring_to_string(choice, "option choice").to_lowercase();
+                let duplex = list.iter().find(|choice| lowercase(choice).contains("duplex"))?.clone();
+                // Prefer an explicitly single-sided choice, then another feeder choice, then anything that isn't duplex
+                let preferences: [fn(&str) -> bool; 3] = [
+                    |choice| choice.contains("simplex"),
+                    |choice| choice.contains("adf") || choice.contains("feeder"),
+                    |_| true,
+                ];
+                let simplex = preferences.iter().find_map(|matches| list.iter().find(|choice| !lowercase(choice).contains("duplex") && matches(&lowercase(choice))))?.clone();
+
+                let enabled = *current == duplex;
+                Some(Self { option, choices: Some((duplex, simplex)), enabled })
+            },
+            _ => None,
+        }
+    }
+
+    /// Device option value that turns duplex scanning on or off
+    pub fn value_for(&self, enabled: bool) -> DeviceOptionValue {
+        match &self.choices {
+            Some((duplex, simplex)) => DeviceOptionValue::String(if enabled { duplex.clone() } else { simplex.clone() }),
+            None => DeviceOptionValue::Bool(enabled),
+        }
+    }
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn fixed_to_dpi(fixed: i32) -> i32 {
+    sane_fixed_to_float(fixed).round() as i32
+}
+
+/// Clamps `value` to `start..=end`, then moves it onto the nearest step of size `quant` from `start` that stays in range
+fn snap_to_range(value: i32, start: i32, end: i32, quant: i32) -> i32 {
+    let clamped = value.clamp(start, end);
+    if quant <= 0 {
+        return clamped;
+    }
+
+    let snapped = start + (clamped - start + quant / 2) / quant * quant;
+    if snapped > end { snapped - quant } else { snapped }
+}
+
+/// Well-known SANE names of the scan area options: the top-left and bottom-right corners
+pub const SCAN_AREA_OPTION_NAMES: [&str; 4] = ["tl-x", "tl-y", "br-x", "br-y"];
+
+/// The device's scan area options along with the extent of the scan bed they can describe
+pub struct ScanArea {
+    /// Options in the order of `SCAN_AREA_OPTION_NAMES`
+    options: Vec<DeviceOption>,
+    /// Lowest and highest positions in the options' own unit (usually mm)
+    x_bounds: (f64, f64),
+    y_bounds: (f64, f64),
+}
+
+impl ScanArea {
+    /// Picks the scan area options out of `options`, or `None` unless all four are present as ranged numbers
+    pub fn find(options: Vec<DeviceOption>) -> Option<Self> {
+        let mut found: Vec<Option<DeviceOption>> = SCAN_AREA_OPTION_NAMES.iter().map(|_| None).collect();
+        for option in options {
+            let name = cstring_to_string(&option.name, "option name");
+            if let Some(position) = SCAN_AREA_OPTION_NAMES.iter().position(|area_name| *area_name == name) {
+                found[position] = Some(option);
+            }
+        }
+
+        let options: Vec<DeviceOption> = found.into_iter().collect::<Option<_>>()?;
+        let bounds: Vec<(f64, f64)> = options.iter().map(option_bounds).collect::<Option<_>>()?;
+
+        Some(Self {
+            x_bounds: (bounds[0].0, bounds[2].1),
+            y_bounds: (bounds[1].0, bounds[3].1),
+            options,
+        })
+    }
+
+    /// Option values selecting `region`, given as `[left, top, right, bottom]` fractions of the whole scan bed
+    pub fn values_for(&self, region: [f64; 4]) -> Vec<(&DeviceOption, DeviceOptionValue)> {
+        let bounds = [self.x_bounds, self.y_bounds, self.x_bounds, self.y_bounds];
+
+        self.options.iter().zip(bounds).zip(region)
+            .map(|((option, (low, high)), fraction)| {
+                let position = low + (high - low) * fraction.clamp(0.0, 1.0);
+
+                #[allow(clippy::cast_possible_truncation)]
+                let value = match option.type_ {
+                    ValueType::Fixed => DeviceOptionValue::Fixed(float_to_sane_fixed(position)),
+                    _ => DeviceOptionValue::Int(position.round() as i32),
+                };
+                (option, value)
+            })
+            .collect()
+    }
+
+    /// Width and height of the scan bed in the options' own unit
+    pub fn bed_size(&self) -> (f64, f64) {
+        (self.x_bounds.1 - self.x_bounds.0, self.y_bounds.1 - self.y_bounds.0)
+    }
+
+    /// The region selected by option values, as fractions of the whole scan bed in the form taken by `values_for`
+    pub fn region_of(&self, values: &[DeviceOptionValue]) -> Option<[f64; 4]> {
+        let bounds = [self.x_bounds, self.y_bounds, self.x_bounds, self.y_bounds];
+        let mut region = [0.0; 4];
+        for ((fraction, value), (low, high)) in region.iter_mut().zip(values).zip(bounds) {
+            let position = match value {
+                DeviceOptionValue::Fixed(raw) => sane_fixed_to_float(*raw),
+                DeviceOptionValue::Int(raw) => f64::from(*raw),
+                _ => return None,
+            };
+            *fraction = if high > low { ((position - low) / (high - low)).clamp(0.0, 1.0) } else { 0.0 };
+        }
+
+        (values.len() == region.len()).then_some(region)
+    }
+
+    /// Reads the region the device is currently set to scan
+    pub fn current_region(&self, device: &mut dyn ScanDevice) -> Result<Option<[f64; 4]>, sane_scan::Error> {
+        let values = self.options.iter().map(|option| device.get_option(option)).collect::<Result<Vec<_>, _>>()?;
+        Ok(self.region_of(&values))
+    }
+}
+
+/// Region covering the whole scan bed, in the form taken by `ScanArea::values_for`
+pub const FULL_SCAN_AREA: [f64; 4] = [0.0, 0.0, 1.0, 1.0];
+
+/// Lowest and highest values accepted by a ranged Int or Fixed option
+fn option_bounds(option: &DeviceOption) -> Option<(f64, f64)> {
+    let OptionConstraint::Range { range, .. } = &option.constraint else {
+        return None;
+    };
+
+    let to_float: fn(i32) -> f64 = match option.type_ {
+        ValueType::Int => f64::from,
+        ValueType::Fixed => sane_fixed_to_float,
+        _ => return None,
+    };
+    Some((to_float(range.start), to_float(range.end)))
+}
+
+/// Size of the data for one page as the device would send it with the loaded options
+pub struct ScanSizeEstimate {
+    /// Width and height of the scan area in inches
+    area: (f64, f64),
+    pub dpi: i32,
+    bits_per_pixel: u32,
+}
+
+impl ScanSizeEstimate {
+    /// Works out the scan area from its options, the bits per pixel from the mode and depth options (8-bit gray if
+    /// they're missing) and the resolution. `None` without a resolution or a scan area given in millimeters.
+    pub fn from_options(options: &[EditingDeviceOption]) -> Option<Self> {
+        let find = |name: &str| options.iter()
+            .filter(|option| option.load_error.is_none() && !option.base_option.cap.contains(OptionCapability::INACTIVE))
+            .find(|option| cstring_to_string(&option.base_option.name, "option name") == name);
+
+        let mut positions = [0.0; 4];
+        for (position, name) in positions.iter_mut().zip(SCAN_AREA_OPTION_NAMES) {
+            let option = find(name).filter(|option| option.base_option.unit == ValueUnit::Mm)?;
+            *position = match option.original_value {
+                DeviceOptionValue::Int(raw) => f64::from(raw),
+                DeviceOptionValue::Fixed(raw) => sane_fixed_to_float(raw),
+                _ => return None,
+            };
+        }
+        let area = ((positions[2] - positions[0]).max(0.0) / f64::from(MM_PER_INCH), (positions[3] - positions[1]).max(0.0) / f64::from(MM_PER_INCH));
+        let dpi = options.iter().find_map(EditingDeviceOption::resolution_dpi)?;
+
+        let mode = find(MODE_OPTION_NAME).map(|option| match &option.original_value {
+            DeviceOptionValue::String(mode) => cstring_to_string(mode, "option value").to_lowercase(),
+            _ => String::new(),
+        }).unwrap_or_default();
+        let depth = find(DEPTH_OPTION_NAME).and_then(|option| match option.original_value {
+            DeviceOptionValue::Int(bits) => u32::try_from(bits).ok(),
+            _ => None,
+        });
+        let bits_per_pixel = if ["lineart", "binary", "black", "halftone"].iter().any(|name| mode.contains(name)) {
+            1
+        } else {
+            let channels = if mode.contains("color") || mode.contains("colour") { 3 } else { 1 };
+            channels * depth.unwrap_or(8)
+        };
+
+        Some(Self { area, dpi, bits_per_pixel })
+    }
+
+    /// Bytes of data for one page scanned at `dpi`
+    #[allow(clippy::cast_possible_truncation)]
+    #[allow(clippy::cast_sign_loss)]
+    pub fn bytes_at(&self, dpi: i32) -> u64 {
+        let dpi = f64::from(dpi);
+        (self.area.0 * dpi * self.area.1 * dpi * f64::from(self.bits_per_pixel) / 8.0) as u64
+    }
+
+    pub fn bytes(&self) -> u64 {
+        self.bytes_at(self.dpi)
+    }
+}
+
+pub enum PrescanError {
+    /// The device has no scan area options, so a prescan couldn't be used to pick one
+    NoScanArea,
+    Failed(String),
+}
+
+impl From<sane_scan::Error> for PrescanError {
+    fn from(error: sane_scan::Error) -> Self {
+        Self::Failed(error.to_string())
+    }
+}
+
+impl From<PageReadError> for PrescanError {
+    fn from(error: PageReadError) -> Self {
+        match error {
+            PageReadError::Reading(error) | PageReadError::Parameters(error) => error.into(),
+        }
+    }
+}
+
+/// A prescan of the whole bed, along with the scan area it replaced
+pub struct PrescanPage {
+    pub page: RgbPage,
+    /// The area set before the prescan, as fractions of the bed, to go back to if no other area is picked
+    pub previous_area: Option<[f64; 4]>,
+}
+
+pub type PrescanResult = Result<PrescanPage, PrescanError>;
+
+/// Scans the whole bed at the lowest offered resolution, leaving the scan area at full size
+/// and restoring the previous resolution afterwards. A failed prescan restores the previous scan area too.
+fn prescan(device: &mut dyn ScanDevice, order: ChannelOrder) -> PrescanResult {
+    let area = ScanArea::find(device.get_options()?).ok_or(PrescanError::NoScanArea)?;
+    let previous_area = area.current_region(device)?;
+    for (option, value) in area.values_for(FULL_SCAN_AREA) {
+        device.set_option(option, value)?;
+    }
+
+    let resolution = match device.get_options()?.into_iter().find(is_resolution_option) {
+        Some(option) => {
+            let value = device.get_option(&option)?;
+            ResolutionOption::new(option, &value)
+        },
+        None => None,
+    };
+    if let Some(resolution) = &resolution {
+        if let Some(&lowest) = resolution.choices.first() {
+            device.set_option(&resolution.option, resolution.value_for(lowest))?;
+        }
+    }
+
+    let page = device.start_scan()
+        .map_err(PrescanError::from)
+        .and_then(|()| read_rgb_page(device, order).map_err(PrescanError::from));
+    // Ends the scan, since a device left mid-batch may refuse to change options
+    device.cancel();
+
+    if let Some(resolution) = &resolution {
+        if let Err(error) = device.set_option(&resolution.option, resolution.value_for(resolution.current)) {
+            println!("Failed to restore resolution after prescan: {error}");
+        }
+    }
+    if let (Err(_), Some(previous)) = (&page, previous_area) {
+        for (option, value) in area.values_for(previous) {
+            if let Err(error) = device.set_option(option, value) {
+                println!("Failed to restore scan area after prescan: {error}");
+            }
+        }
+    }
+
+    page.map(|page| PrescanPage { page, previous_area })
+}
+
+/// Runs a prescan on a background thread so the UI stays responsive while the device works
+pub struct PrescanTask {
+    thread: JoinHandle<PrescanResult>,
+}
+
+impl PrescanTask {
+    pub fn start(handle: Arc<Mutex<ThDeviceHandle>>, order: ChannelOrder, ctx: Context) -> Self {
+        let thread = thread::spawn(move || {
+            let result = prescan(handle.lock().unwrap().handle.as_mut(), order);
+
+            ctx.request_repaint();
+            result
+        });
+
+        Self { thread }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    pub fn finish(self) -> PrescanResult {
+        self.thread.join().unwrap_or_else(|error| Err(PrescanError::Failed(format!("{error:?}"))))
+    }
+}
+
+/// Watches a sensor option on a background thread and reports each press through a channel
+pub struct ButtonPoller {
+    stop: Arc<Mutex<bool>>,
+    paused: Arc<Mutex<bool>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ButtonPoller {
+    pub fn start(handle: Arc<Mutex<ThDeviceHandle>>, sensor: DeviceOption, ctx: Context, presses: Sender<()>) -> Self {
+        let stop = Arc::new(Mutex::new(false));
+        let paused = Arc::new(Mutex::new(false));
+        let thread_stop = stop.clone();
+        let thread_paused = paused.clone();
+
+        let thread = thread::spawn(move || {
+            let mut was_pressed = false;
+
+            while !*thread_stop.lock().unwrap() {
+                thread::sleep(BUTTON_POLL_INTERVAL);
+
+                if *thread_paused.lock().unwrap() {
+                    was_pressed = false;
+                    continue;
+                }
+
+                // Never wait on the device while something else (e.g. a scan) is using it
+                let Ok(mut device) = handle.try_lock() else {
+                    continue;
+                };
+                let pressed = match device.handle.get_option(&sensor) {
+                    Ok(DeviceOptionValue::Bool(value)) => value,
+                    Ok(DeviceOptionValue::Int(value)) => value != 0,
+                    _ => false,
+                };
+                drop(device);
+
+                if pressed && !was_pressed {
+                    if presses.send(()).is_err() {
+                        return;
+                    }
+                    ctx.request_repaint();
+                }
+                was_pressed = pressed;
+            }
+        });
+
+        Self { stop, paused, thread: Some(thread) }
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        *self.paused.lock().unwrap() = paused;
+    }
+
+    pub fn stop(mut self) {
+        *self.stop.lock().unwrap() = true;
+        if let Some(thread) = self.thread.take() {
+            if let Err(error) = thread.join() {
+                println!("Button polling thread terminated abnormally: {error:?}");
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct EditingDeviceOption {
     pub base_option: DeviceOption,
     pub editing_value: EditingDeviceOptionValue,
     pub is_edited: bool,
+    /// Let the device pick the value on apply, rather than setting the edited one
+    pub set_auto: bool,
+    pub choice_filter: String,
+    /// Whether the option became active or inactive as a result of the last applied changes
+    pub activity_changed: bool,
+    /// Why the option's value couldn't be read, in which case it is shown read-only
+    pub load_error: Option<String>,
     original_value: DeviceOptionValue,
 }
 
 impl EditingDeviceOption {
     pub fn new(base_option: DeviceOption, original_value: DeviceOptionValue) -> Self {
         Self {
+            editing_value: EditingDeviceOptionValue::for_option(&base_option, &original_value),
             base_option,
-            editing_value: (&original_value).into(),
             is_edited: false,
+            set_auto: false,
+            choice_filter: String::new(),
+            activity_changed: false,
+            load_error: None,
             original_value,
         }
     }
 
+    /// An option whose value failed to load. It has no value to edit, so it is never applied.
+    pub fn with_load_error(base_option: DeviceOption, error: String) -> Self {
+        Self {
+            load_error: Some(error),
+            ..Self::new(base_option, DeviceOptionValue::Group)
+        }
+    }
+
+    /// The loaded value of the option in DPI, if this is the resolution option
+    pub fn resolution_dpi(&self) -> Option<i32> {
+        if !is_resolution_option(&self.base_option) || self.load_error.is_some() {
+            return None;
+        }
+
+        match self.original_value {
+            DeviceOptionValue::Int(raw) => Some(raw),
+            DeviceOptionValue::Fixed(raw) => Some(fixed_to_dpi(raw)),
+            _ => None,
+        }
+    }
+
+    /// The value loaded from the device as display text, if it has a simple textual form
+    pub fn value_text(&self) -> Option<String> {
+        if self.load_error.is_some() {
+            return None;
+        }
+
+        match EditingDeviceOptionValue::from(&self.original_value) {
+            EditingDeviceOptionValue::Int(value)
+            | EditingDeviceOptionValue::Fixed(value)
+            | EditingDeviceOptionValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Carries over unapplied edits and editor state from the same option before a reload
+    pub fn keep_edits_from(&mut self, previous: Self) {
+        if previous.is_edited && std::mem::discriminant(&previous.editing_value) == std::mem::discriminant(&self.editing_value) {
+            self.editing_value = previous.editing_value;
+            self.is_edited = true;
+        }
+        if previous.is_edited && previous.set_auto {
+            self.set_auto = true;
+            self.is_edited = true;
+        }
+        self.choice_filter = previous.choice_filter;
+        self.activity_changed = previous.activity_changed;
+    }
+
+    /// The value shown for the option, for saving in a scan profile. `None` for options that can't be set.
+    pub fn profile_value(&self) -> Option<&EditingDeviceOptionValue> {
+        let storable = self.load_error.is_none() && is_settable(&self.base_option)
+            && !matches!(self.editing_value, EditingDeviceOptionValue::Button | EditingDeviceOptionValue::Group);
+        storable.then_some(&self.editing_value)
+    }
+
+    /// Stages a value from a scan profile to be applied, if the option can be set to that kind of value.
+    /// Returns whether this changed the value to be applied.
+    pub fn stage_profile_value(&mut self, value: &EditingDeviceOptionValue) -> bool {
+        let Some(current) = self.profile_value() else {
+            return false;
+        };
+        if current == value || std::mem::discriminant(current) != std::mem::discriminant(value) {
+            return false;
+        }
+
+        self.editing_value = value.clone();
+        self.set_auto = false;
+        self.is_edited = true;
+        true
+    }
+
     pub fn reset_editor_value(&mut self) {
-        self.editing_value = (&self.original_value).into();
+        self.editing_value = EditingDeviceOptionValue::for_option(&self.base_option, &self.original_value);
         self.is_edited = false;
+        self.set_auto = false;
     }
 }
 
-#[derive(Debug)]
+/// Words in an option's name or description suggesting that it switches something on or off
+const FLAG_WORDS: [&str; 5] = ["enable", "disable", "on/off", "whether", "toggle"];
+
+/// Whether an Int option is really an on/off switch: a single unconstrained, unitless value described with words like "enable".
+/// Genuine numbers, even ones that happen to be 0 or 1, keep their text box.
+fn is_flag_like(option: &DeviceOption) -> bool {
+    let described = format!("{} {}", cstring_to_string(&option.name, "option name"), cstring_to_string(&option.desc, "option description")).to_lowercase();
+
+    option.type_ == ValueType::Int
+        && option.constraint == OptionConstraint::None
+        && option.unit == ValueUnit::None
+        && usize::try_from(option.size).is_ok_and(|size| size == std::mem::size_of::<i32>())
+        && FLAG_WORDS.iter().any(|word| described.contains(word))
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum EditingDeviceOptionValue {
 	Bool(bool),
+	/// An Int option that only switches something on or off, applied as 0 or 1
+	Flag(bool),
 	Int(String),
 	Fixed(String),
 	String(String),
@@ -42,6 +1044,18 @@ pub enum EditingDeviceOptionValue {
 	Group,
 }
 
+impl EditingDeviceOptionValue {
+    /// Converts a value read from `option`. Some backends return Bool-typed options as Int, which are edited as a Bool regardless.
+    /// Others declare on/off options as Int, which are edited as a `Flag` when they look like one.
+    pub fn for_option(option: &DeviceOption, value: &DeviceOptionValue) -> Self {
+        match (option.type_, value) {
+            (ValueType::Bool, DeviceOptionValue::Int(val)) => Self::Bool(*val != 0),
+            (ValueType::Int, DeviceOptionValue::Int(val @ (0 | 1))) if is_flag_like(option) => Self::Flag(*val == 1),
+            _ => value.into(),
+        }
+    }
+}
+
 impl From<&DeviceOptionValue> for EditingDeviceOptionValue {
     fn from(opt_value: &DeviceOptionValue) -> Self {
         match opt_value {
@@ -58,7 +1072,8 @@ impl From<&DeviceOptionValue> for EditingDeviceOptionValue {
 impl TryFrom<&EditingDeviceOptionValue> for DeviceOptionValue {
     fn try_from(opt_edit: &EditingDeviceOptionValue) -> Result<Self, Self::Error> {
         match opt_edit {
-            EditingDeviceOptionValue::Bool(val) => Ok(Self::Int((*val).into())),
+            EditingDeviceOptionValue::Bool(val) => Ok(Self::Bool(*val)),
+            EditingDeviceOptionValue::Flag(val) => Ok(Self::Int(i32::from(*val))),
             EditingDeviceOptionValue::Int(val) => Ok(Self::Int(val.parse()?)),
             EditingDeviceOptionValue::Fixed(val) => Ok(Self::Fixed(float_to_sane_fixed(val.parse()?))),
             EditingDeviceOptionValue::String(val) => Ok(Self::String(string_to_cstring(val.clone()))),
@@ -68,4 +1083,584 @@ impl TryFrom<&EditingDeviceOptionValue> for DeviceOptionValue {
     }
 
     type Error = Box<dyn std::error::Error>;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::VecDeque, ffi::CString};
+
+    use sane_scan::Status;
+
+    use super::*;
+
+    /// Serves pre-recorded pages in place of a real scanner
+    #[derive(Default)]
+    struct MockDevice {
+        pages: VecDeque<(Parameters, Vec<u8>)>,
+        current: Option<Parameters>,
+        /// Remaining data of the page being read
+        unread: VecDeque<u8>,
+        cancelled: bool,
+        fail_parameters: bool,
+        /// Number of upcoming `start_scan` calls that fail even though pages remain
+        spurious_start_failures: u32,
+        /// Options with their current values, changed by `set_option`
+        options: Vec<(DeviceOption, DeviceOptionValue)>,
+    }
+
+    impl MockDevice {
+        fn with_page(format: Frame, bytes_per_line: i32, data: Vec<u8>) -> Self {
+            let parameters = Parameters {
+                format,
+                last_frame: true,
+                bytes_per_line,
+                pixels_per_line: if matches!(format, Frame::Rgb) { bytes_per_line / 3 } else { bytes_per_line },
+                lines: -1,
+                depth: 8,
+            };
+
+            Self { pages: VecDeque::from([(parameters, data)]), ..Self::default() }
+        }
+    }
+
+    impl ScanDevice for MockDevice {
+        fn get_options(&mut self) -> Result<Vec<DeviceOption>, sane_scan::Error> {
+            Ok(self.options.iter().map(|(option, _)| option.clone()).collect())
+        }
+
+        fn get_option(&mut self, option: &DeviceOption) -> Result<DeviceOptionValue, sane_scan::Error> {
+            self.options.iter()
+                .find(|(candidate, _)| candidate.option_idx == option.option_idx)
+                .map(|(_, value)| value.clone())
+                .ok_or(sane_scan::Error(Status::Unsupported))
+        }
+
+        fn set_option(&mut self, option: &DeviceOption, value: DeviceOptionValue) -> Result<(), sane_scan::Error> {
+            let (_, current) = self.options.iter_mut()
+                .find(|(candidate, _)| candidate.option_idx == option.option_idx)
+                .ok_or(sane_scan::Error(Status::Unsupported))?;
+            *current = value;
+            Ok(())
+        }
+
+        fn set_option_auto(&mut self, _option: &DeviceOption) -> Result<(), sane_scan::Error> {
+            Err(sane_scan::Error(Status::Unsupported))
+        }
+
+        fn start_scan(&mut self) -> Result<(), sane_scan::Error> {
+            self.cancelled = false;
+            if self.spurious_start_failures > 0 {
+                self.spurious_start_failures -= 1;
+                Err(sane_scan::Error(Status::IoError))
+            } else if self.pages.is_empty() {
+                Err(sane_scan::Error(Status::NoDocs))
+            } else {
+                Ok(())
+            }
+        }
+
+        fn get_parameters(&mut self) -> Result<Parameters, sane_scan::Error> {
+            if self.fail_parameters {
+                return Err(sane_scan::Error(Status::IoError));
+            }
+            self.current.take().ok_or(sane_scan::Error(Status::Inval))
+        }
+
+        fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, sane_scan::Error> {
+            if self.cancelled {
+                return Err(sane_scan::Error(Status::Cancelled));
+            }
+            if self.current.is_none() {
+                let (parameters, data) = self.pages.pop_front().ok_or(sane_scan::Error(Status::Inval))?;
+                self.current = Some(parameters);
+                self.unread = data.into();
+            }
+            if self.unread.is_empty() {
+                return Ok(None);
+            }
+
+            let length = buffer.len().min(self.unread.len());
+            for (slot, byte) in buffer.iter_mut().zip(self.unread.drain(..length)) {
+                *slot = byte;
+            }
+            Ok(Some(length))
+        }
+
+        fn cancel(&mut self) {
+            self.cancelled = true;
+        }
+    }
+
+    fn option_of_type(type_: ValueType) -> DeviceOption {
+        DeviceOption {
+            option_idx: 1,
+            name: string_to_cstring(String::from("option")),
+            title: string_to_cstring(String::from("Option")),
+            desc: CString::default(),
+            type_,
+            unit: ValueUnit::None,
+            size: 4,
+            cap: OptionCapability::SOFT_SELECT,
+            constraint: OptionConstraint::None,
+        }
+    }
+
+    /// The value that would be applied after editing `value` of `option` without changes
+    fn round_trip(option: &DeviceOption, value: &DeviceOptionValue) -> DeviceOptionValue {
+        let editor = EditingDeviceOption::new(option.clone(), value.clone());
+        DeviceOptionValue::try_from(&editor.editing_value).unwrap()
+    }
+
+    #[test]
+    fn bool_options_apply_as_bool() {
+        let option = option_of_type(ValueType::Bool);
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Bool(true)), DeviceOptionValue::Bool(true));
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Bool(false)), DeviceOptionValue::Bool(false));
+    }
+
+    #[test]
+    fn bool_options_read_as_int_are_edited_as_bool() {
+        let option = option_of_type(ValueType::Bool);
+        let editor = EditingDeviceOption::new(option.clone(), DeviceOptionValue::Int(1));
+        assert!(matches!(editor.editing_value, EditingDeviceOptionValue::Bool(true)));
+
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Int(1)), DeviceOptionValue::Bool(true));
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Int(0)), DeviceOptionValue::Bool(false));
+    }
+
+    #[test]
+    fn set_auto_survives_reload_until_reset() {
+        let option = option_of_type(ValueType::Int);
+        let mut previous = EditingDeviceOption::new(option.clone(), DeviceOptionValue::Int(5));
+        previous.set_auto = true;
+        previous.is_edited = true;
+
+        let mut reloaded = EditingDeviceOption::new(option, DeviceOptionValue::Int(5));
+        reloaded.keep_edits_from(previous);
+        assert!(reloaded.set_auto && reloaded.is_edited);
+
+        reloaded.reset_editor_value();
+        assert!(!reloaded.set_auto && !reloaded.is_edited);
+    }
+
+    #[test]
+    fn int_options_stay_int() {
+        let option = option_of_type(ValueType::Int);
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Int(1)), DeviceOptionValue::Int(1));
+    }
+
+    #[test]
+    fn on_off_int_options_are_edited_as_flags() {
+        let option = DeviceOption { desc: string_to_cstring(String::from("Enable lamp saving")), ..option_of_type(ValueType::Int) };
+        let editor = EditingDeviceOption::new(option.clone(), DeviceOptionValue::Int(1));
+        assert!(matches!(editor.editing_value, EditingDeviceOptionValue::Flag(true)));
+
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Int(0)), DeviceOptionValue::Int(0));
+        assert_eq!(round_trip(&option, &DeviceOptionValue::Int(1)), DeviceOptionValue::Int(1));
+    }
+
+    #[test]
+    fn numeric_int_options_keep_their_text_box() {
+        let option = DeviceOption { desc: string_to_cstring(String::from("Enable count")), ..option_of_type(ValueType::Int) };
+        assert!(matches!(EditingDeviceOption::new(option.clone(), DeviceOptionValue::Int(5)).editing_value, EditingDeviceOptionValue::Int(_)));
+
+        let constrained = DeviceOption { constraint: OptionConstraint::Range { range: 0..10, quant: 1 }, ..option };
+        assert!(matches!(EditingDeviceOption::new(constrained, DeviceOptionValue::Int(1)).editing_value, EditingDeviceOptionValue::Int(_)));
+    }
+
+    #[test]
+    fn rgb_page_passes_through() {
+        let data: Vec<u8> = (0..2 * 3 * 4).collect();
+        let mut device = MockDevice::with_page(Frame::Rgb, 2 * 3, data.clone());
+
+        let RgbPage { size, pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap();
+        assert_eq!(size, [2, 4]);
+        assert_eq!(pixels, data);
+    }
+
+    #[test]
+    fn bgr_page_is_swapped_to_rgb() {
+        let mut device = MockDevice::with_page(Frame::Rgb, 2 * 3, vec![1, 2, 3, 4, 5, 6]);
+
+        let RgbPage { size, pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Bgr).unwrap();
+        assert_eq!(size, [2, 1]);
+        assert_eq!(pixels, [3, 2, 1, 6, 5, 4]);
+    }
+
+    #[test]
+    fn grayscale_page_ignores_channel_order() {
+        let mut device = MockDevice::with_page(Frame::Gray, 2, vec![1, 2]);
+
+        let RgbPage { pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Bgr).unwrap();
+        assert_eq!(pixels, [1, 1, 1, 2, 2, 2]);
+    }
+
+    #[test]
+    fn page_format_follows_frame_and_depth() {
+        let mut color = MockDevice::with_page(Frame::Rgb, 3, vec![1, 2, 3]);
+        assert_eq!(read_rgb_page(&mut color, ChannelOrder::Rgb).unwrap().format, PageFormat::Color);
+
+        let mut gray = MockDevice::with_page(Frame::Gray, 1, vec![1]);
+        assert_eq!(read_rgb_page(&mut gray, ChannelOrder::Rgb).unwrap().format, PageFormat::Gray);
+
+        let mut lineart = MockDevice::with_page(Frame::Gray, 1, vec![0xff]);
+        if let Some((parameters, _)) = lineart.pages.front_mut() {
+            parameters.depth = 1;
+            parameters.pixels_per_line = 8;
+        }
+        assert_eq!(read_rgb_page(&mut lineart, ChannelOrder::Rgb).unwrap().format, PageFormat::Lineart);
+    }
+
+    #[test]
+    fn lineart_page_unpacks_to_black_and_white() {
+        // 10 pixels per line padded to 2 bytes, with set bits being black
+        let mut device = MockDevice::with_page(Frame::Gray, 2, vec![0b1000_0001, 0b0100_0000, 0, 0xff]);
+        if let Some((parameters, _)) = device.pages.front_mut() {
+            parameters.depth = 1;
+            parameters.pixels_per_line = 10;
+        }
+
+        let RgbPage { size, pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap();
+        assert_eq!(size, [10, 2]);
+        let gray: Vec<u8> = pixels.chunks_exact(3).map(|pixel| pixel[0]).collect();
+        assert_eq!(gray[..10], [0, 255, 255, 255, 255, 255, 255, 0, 255, 0]);
+        assert_eq!(gray[10..], [255, 255, 255, 255, 255, 255, 255, 255, 0, 0]);
+    }
+
+    #[test]
+    fn grayscale_page_expands_to_rgb() {
+        let mut device = MockDevice::with_page(Frame::Gray, 3, vec![0, 128, 255, 7, 8, 9]);
+
+        let RgbPage { size, pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap();
+        assert_eq!(size, [3, 2]);
+        assert_eq!(pixels, [0, 0, 0, 128, 128, 128, 255, 255, 255, 7, 7, 7, 8, 8, 8, 9, 9, 9]);
+    }
+
+    #[test]
+    fn line_count_comes_from_data_read() {
+        // Hand scanners and sheet feeders report an unknown length (-1 lines) up front
+        let mut device = MockDevice::with_page(Frame::Gray, 4, vec![1; 4 * 5]);
+
+        let RgbPage { size, pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap();
+        assert_eq!(size, [4, 5]);
+        assert_eq!(pixels.len(), 4 * 5 * 3);
+    }
+
+    #[test]
+    fn read_failure_is_reported() {
+        let mut device = MockDevice::default();
+        assert!(matches!(read_rgb_page(&mut device, ChannelOrder::Rgb), Err(PageReadError::Reading(_))));
+    }
+
+    #[test]
+    fn parameter_failure_is_reported() {
+        let mut device = MockDevice { fail_parameters: true, ..MockDevice::with_page(Frame::Rgb, 3, vec![0; 3]) };
+        assert!(matches!(read_rgb_page(&mut device, ChannelOrder::Rgb), Err(PageReadError::Parameters(_))));
+    }
+
+    #[test]
+    fn pages_are_read_through_shared_handle() {
+        let mut device = MockDevice::with_page(Frame::Rgb, 3, vec![10, 20, 30]);
+        device.pages.extend(MockDevice::with_page(Frame::Rgb, 3, vec![10, 20, 30]).pages);
+        let handle = Arc::new(Mutex::new(ThDeviceHandle { handle: Box::new(device) }));
+
+        let interrupt = Mutex::new(false);
+
+        let mut pages = 0;
+        while handle.lock().unwrap().handle.start_scan().is_ok() {
+            let RgbPage { size, pixels, .. } = read_shared_rgb_page(&handle, ChannelOrder::Rgb, &interrupt).unwrap();
+            assert_eq!(size, [1, 1]);
+            assert_eq!(pixels, [10, 20, 30]);
+            pages += 1;
+        }
+        assert_eq!(pages, 2);
+    }
+
+    #[test]
+    fn pages_span_several_chunks() {
+        let data: Vec<u8> = (0..READ_CHUNK_SIZE * 2 + 10).map(|n| (n % 251) as u8).collect();
+        let mut device = MockDevice::with_page(Frame::Gray, 10, data.clone());
+
+        let RgbPage { size, pixels, .. } = read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap();
+        assert_eq!(size, [10, data.len() / 10]);
+        assert_eq!(pixels, repeat_all_elements(data, 3));
+    }
+
+    #[test]
+    fn pages_are_complete_only_with_every_announced_line() {
+        let mut device = MockDevice::with_page(Frame::Gray, 2, vec![0; 6]);
+        assert!(!read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap().complete);
+
+        for (lines, complete) in [(3, true), (4, false)] {
+            let mut device = MockDevice::with_page(Frame::Gray, 2, vec![0; 6]);
+            device.pages[0].0.lines = lines;
+            assert_eq!(read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap().complete, complete);
+        }
+    }
+
+    #[test]
+    fn cancel_interrupts_page_mid_read() {
+        let device = MockDevice::with_page(Frame::Gray, 4, vec![0; READ_CHUNK_SIZE * 3]);
+        let handle = Mutex::new(ThDeviceHandle { handle: Box::new(device) });
+
+        let mut chunk = vec![0; READ_CHUNK_SIZE];
+        assert_eq!(handle.lock().unwrap().handle.read(&mut chunk).unwrap(), Some(READ_CHUNK_SIZE));
+        handle.lock().unwrap().handle.cancel();
+
+        let result = read_shared_rgb_page(&handle, ChannelOrder::Rgb, &Mutex::new(false));
+        assert!(matches!(result, Err(PageReadError::Reading(sane_scan::Error(Status::Cancelled)))));
+    }
+
+    #[test]
+    fn interrupt_cancels_page_before_next_chunk() {
+        let device = MockDevice::with_page(Frame::Gray, 4, vec![0; READ_CHUNK_SIZE * 3]);
+        let handle = Mutex::new(ThDeviceHandle { handle: Box::new(device) });
+
+        let result = read_shared_rgb_page(&handle, ChannelOrder::Rgb, &Mutex::new(true));
+        assert!(matches!(result, Err(PageReadError::Reading(sane_scan::Error(Status::Cancelled)))));
+    }
+
+    #[test]
+    fn spurious_start_failures_are_retried() {
+        let mut device = MockDevice::with_page(Frame::Rgb, 3, vec![10, 20, 30]);
+        device.spurious_start_failures = 2;
+        let handle = Mutex::new(ThDeviceHandle { handle: Box::new(device) });
+        let interrupt = Mutex::new(false);
+
+        assert!(start_next_page(&handle, 2, Duration::ZERO, &interrupt));
+    }
+
+    #[test]
+    fn feeder_is_empty_once_retries_run_out() {
+        let mut device = MockDevice::with_page(Frame::Rgb, 3, vec![10, 20, 30]);
+        device.spurious_start_failures = 2;
+        let handle = Mutex::new(ThDeviceHandle { handle: Box::new(device) });
+        let interrupt = Mutex::new(false);
+
+        assert!(!start_next_page(&handle, 1, Duration::ZERO, &interrupt));
+
+        let empty = Mutex::new(ThDeviceHandle { handle: Box::new(MockDevice::default()) });
+        assert!(!start_next_page(&empty, 2, Duration::ZERO, &interrupt));
+    }
+
+    #[test]
+    fn resuming_ends_the_pause() {
+        let pause = Arc::new(Mutex::new(true));
+        let interrupt = Mutex::new(false);
+
+        let resume = {
+            let pause = pause.clone();
+            thread::spawn(move || {
+                thread::sleep(PAUSE_POLL_INTERVAL);
+                *pause.lock().unwrap() = false;
+            })
+        };
+        wait_while_paused(&pause, &interrupt);
+        assert!(!*pause.lock().unwrap());
+        resume.join().unwrap();
+    }
+
+    #[test]
+    fn cancelling_ends_the_pause() {
+        let pause = Mutex::new(true);
+        let interrupt = Mutex::new(true);
+
+        wait_while_paused(&pause, &interrupt);
+    }
+
+    #[test]
+    fn interrupt_stops_retrying() {
+        let device = MockDevice::with_page(Frame::Rgb, 3, vec![10, 20, 30]);
+        let handle = Mutex::new(ThDeviceHandle { handle: Box::new(device) });
+        let interrupt = Mutex::new(true);
+
+        assert!(!start_next_page(&handle, 2, Duration::ZERO, &interrupt));
+    }
+
+    #[test]
+    fn test_pattern_scanner_feeds_repeatable_batches() {
+        let mut device = TestPatternDevice::default();
+        let mode = device.get_options().unwrap().into_iter().find(is_mode_option).unwrap();
+        device.set_option(&mode, DeviceOptionValue::String(string_to_cstring(String::from("Gray")))).unwrap();
+
+        let mut batch = Vec::new();
+        while device.start_scan().is_ok() {
+            batch.push(read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap());
+        }
+        assert_eq!(batch.len(), 3);
+        assert!(batch.iter().all(|page| page.complete && page.format == PageFormat::Gray));
+        assert_ne!(batch[0].pixels, batch[1].pixels);
+
+        // The next scan starts the same batch over
+        device.start_scan().unwrap();
+        assert_eq!(read_rgb_page(&mut device, ChannelOrder::Rgb).unwrap().pixels, batch[0].pixels);
+    }
+
+    fn named_option(name: &str, type_: ValueType, constraint: OptionConstraint) -> DeviceOption {
+        DeviceOption { name: string_to_cstring(String::from(name)), constraint, ..option_of_type(type_) }
+    }
+
+    fn string_list(choices: &[&str]) -> OptionConstraint {
+        OptionConstraint::StringList(choices.iter().map(|choice| string_to_cstring(String::from(*choice))).collect())
+    }
+
+    #[test]
+    fn profile_values_only_stage_onto_the_same_kind_of_option() {
+        let mut mode = EditingDeviceOption::new(named_option("mode", ValueType::String, string_list(&["Gray", "Color"])), string_value("Color"));
+        assert!(!mode.stage_profile_value(&EditingDeviceOptionValue::Int(String::from("1"))));
+        assert!(!mode.stage_profile_value(&EditingDeviceOptionValue::String(String::from("Color"))));
+        assert!(!mode.is_edited);
+
+        assert!(mode.stage_profile_value(&EditingDeviceOptionValue::String(String::from("Gray"))));
+        assert!(mode.is_edited);
+        assert_eq!(DeviceOptionValue::try_from(&mode.editing_value).unwrap(), string_value("Gray"));
+
+        let inactive = DeviceOption { cap: OptionCapability::SOFT_SELECT | OptionCapability::INACTIVE, ..option_of_type(ValueType::Int) };
+        let mut inactive = EditingDeviceOption::new(inactive, DeviceOptionValue::Int(5));
+        assert!(inactive.profile_value().is_none());
+        assert!(!inactive.stage_profile_value(&EditingDeviceOptionValue::Int(String::from("6"))));
+    }
+
+    #[test]
+    fn scan_size_is_estimated_from_loaded_options() {
+        let position = |name: &str, mm: i32| EditingDeviceOption::new(
+            DeviceOption { unit: ValueUnit::Mm, ..named_option(name, ValueType::Int, OptionConstraint::None) }, DeviceOptionValue::Int(mm));
+        let mut options: Vec<EditingDeviceOption> = [("tl-x", 0), ("tl-y", 0), ("br-x", 254), ("br-y", 127)].into_iter()
+            .map(|(name, mm)| position(name, mm))
+            .collect();
+        options.push(EditingDeviceOption::new(named_option("resolution", ValueType::Int, OptionConstraint::None), DeviceOptionValue::Int(100)));
+        options.push(EditingDeviceOption::new(named_option("mode", ValueType::String, string_list(&["Gray", "Color"])), string_value("Color")));
+        options.push(EditingDeviceOption::new(named_option("depth", ValueType::Int, OptionConstraint::None), DeviceOptionValue::Int(16)));
+
+        // 10 by 5 inches, at 6 bytes per pixel
+        let estimate = ScanSizeEstimate::from_options(&options).unwrap();
+        assert_eq!(estimate.bytes(), 1000 * 500 * 6);
+        assert_eq!(estimate.bytes_at(200), 2000 * 1000 * 6);
+
+        options.pop();
+        options.pop();
+        assert_eq!(ScanSizeEstimate::from_options(&options).unwrap().bytes(), 1000 * 500);
+
+        options.remove(0);
+        assert!(ScanSizeEstimate::from_options(&options).is_none());
+    }
+
+    #[test]
+    fn duplex_is_found_in_source_choices() {
+        let option = named_option("source", ValueType::String, string_list(&["Flatbed", "ADF Front", "ADF Duplex"]));
+        let duplex = DuplexOption::new(option, &DeviceOptionValue::String(string_to_cstring(String::from("Flatbed")))).unwrap();
+
+        assert!(!duplex.enabled);
+        assert_eq!(duplex.value_for(true), DeviceOptionValue::String(string_to_cstring(String::from("ADF Duplex"))));
+        assert_eq!(duplex.value_for(false), DeviceOptionValue::String(string_to_cstring(String::from("ADF Front"))));
+    }
+
+    #[test]
+    fn duplex_prefers_an_explicit_simplex_choice() {
+        let option = named_option("adf-mode", ValueType::String, string_list(&["Duplex", "Simplex"]));
+        let duplex = DuplexOption::new(option, &DeviceOptionValue::String(string_to_cstring(String::from("Duplex")))).unwrap();
+
+        assert!(duplex.enabled);
+        assert_eq!(duplex.value_for(false), DeviceOptionValue::String(string_to_cstring(String::from("Simplex"))));
+    }
+
+    #[test]
+    fn duplex_boolean_accepts_integer_values() {
+        let option = named_option("duplex", ValueType::Bool, OptionConstraint::None);
+        let duplex = DuplexOption::new(option, &DeviceOptionValue::Int(1)).unwrap();
+
+        assert!(duplex.enabled);
+        assert_eq!(duplex.value_for(false), DeviceOptionValue::Bool(false));
+    }
+
+    #[test]
+    fn options_without_duplex_are_ignored() {
+        let source = named_option("source", ValueType::String, string_list(&["Flatbed", "ADF"]));
+        assert!(DuplexOption::new(source, &DeviceOptionValue::String(string_to_cstring(String::from("ADF")))).is_none());
+
+        let mut inactive = named_option("duplex", ValueType::Bool, OptionConstraint::None);
+        inactive.cap |= OptionCapability::INACTIVE;
+        assert!(DuplexOption::new(inactive, &DeviceOptionValue::Bool(true)).is_none());
+    }
+
+    fn string_value(value: &str) -> DeviceOptionValue {
+        DeviceOptionValue::String(string_to_cstring(String::from(value)))
+    }
+
+    #[test]
+    fn color_modes_are_found_in_mode_choices() {
+        let mode = named_option("mode", ValueType::String, string_list(&["Lineart", "Halftone", "Gray", "Color"]));
+        let depth = DeviceOption { option_idx: 2, ..named_option("depth", ValueType::Int, OptionConstraint::WordList(vec![8, 16])) };
+        let color_mode = ColorModeOption::new(mode, &string_value("Halftone"), Some((&depth, &DeviceOptionValue::Int(8)))).unwrap();
+
+        assert_eq!(color_mode.formats().collect::<Vec<_>>(), [PageFormat::Lineart, PageFormat::Gray, PageFormat::Color]);
+        assert_eq!(color_mode.current, None);
+        assert_eq!(color_mode.current_mode, "Halftone");
+    }
+
+    #[test]
+    fn lineart_falls_back_to_one_bit_gray() {
+        let mode = DeviceOption { option_idx: 1, ..named_option("mode", ValueType::String, string_list(&["Gray", "Color"])) };
+        let depth = DeviceOption { option_idx: 2, ..named_option("depth", ValueType::Int, OptionConstraint::WordList(vec![1, 8, 16])) };
+        let color_mode = ColorModeOption::new(mode.clone(), &string_value("Gray"), Some((&depth, &DeviceOptionValue::Int(1)))).unwrap();
+        assert_eq!(color_mode.current, Some(PageFormat::Lineart));
+
+        let mut device = MockDevice { options: vec![(mode, string_value("Color")), (depth, DeviceOptionValue::Int(16))], ..MockDevice::default() };
+        color_mode.apply(&mut device, PageFormat::Lineart).unwrap();
+        assert_eq!(device.options[0].1, string_value("Gray"));
+        assert_eq!(device.options[1].1, DeviceOptionValue::Int(1));
+
+        color_mode.apply(&mut device, PageFormat::Gray).unwrap();
+        assert_eq!(device.options[1].1, DeviceOptionValue::Int(8));
+    }
+
+    #[test]
+    fn single_mode_devices_have_no_color_mode_control() {
+        let mode = named_option("mode", ValueType::String, string_list(&["Color"]));
+        assert!(ColorModeOption::new(mode, &string_value("Color"), None).is_none());
+    }
+
+    #[test]
+    fn scan_area_region_round_trips_through_option_values() {
+        let bound = |name: &str, end: f64| named_option(name, ValueType::Fixed, OptionConstraint::Range { range: 0..float_to_sane_fixed(end), quant: 0 });
+        let area = ScanArea::find(vec![bound("tl-x", 200.0), bound("tl-y", 300.0), bound("br-x", 200.0), bound("br-y", 300.0)]).unwrap();
+        assert_eq!(area.bed_size(), (200.0, 300.0));
+
+        let region = [0.25, 0.1, 0.75, 0.5];
+        let values: Vec<DeviceOptionValue> = area.values_for(region).into_iter().map(|(_, value)| value).collect();
+        let read_back = area.region_of(&values).unwrap();
+        for (read, expected) in read_back.iter().zip(region) {
+            assert!((read - expected).abs() < 1e-4);
+        }
+    }
+
+    fn device_with_source_and_resolution(source: &str, dpi: i32) -> MockDevice {
+        let source_option = DeviceOption { option_idx: 1, ..named_option("source", ValueType::String, string_list(&["Flatbed", "ADF"])) };
+        let resolution_option = DeviceOption { option_idx: 2, ..named_option("resolution", ValueType::Int, OptionConstraint::WordList(vec![150, 300, 600])) };
+        MockDevice {
+            options: vec![
+                (source_option, DeviceOptionValue::String(string_to_cstring(String::from(source)))),
+                (resolution_option, DeviceOptionValue::Int(dpi)),
+            ],
+            ..MockDevice::default()
+        }
+    }
+
+    #[test]
+    fn remembered_settings_are_restored() {
+        let mut device = device_with_source_and_resolution("Flatbed", 150);
+        restore_scan_settings(&mut device, Some(600), Some("ADF")).unwrap();
+
+        assert_eq!(device.options[0].1, DeviceOptionValue::String(string_to_cstring(String::from("ADF"))));
+        assert_eq!(device.options[1].1, DeviceOptionValue::Int(600));
+    }
+
+    #[test]
+    fn unavailable_remembered_settings_are_skipped() {
+        let mut device = device_with_source_and_resolution("Flatbed", 150);
+        restore_scan_settings(&mut device, Some(1200), Some("Transparency")).unwrap();
+
+        assert_eq!(device.options[0].1, DeviceOptionValue::String(string_to_cstring(String::from("Flatbed"))));
+        assert_eq!(device.options[1].1, DeviceOptionValue::Int(150));
+    }
+}
@@ -0,0 +1,98 @@
+use std::{error::Error, fs, path::{Path, PathBuf}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use serde::{Deserialize, Serialize};
+
+use super::{image::{Annotation, Dewarp, Flip, PageFormat, PixelAdjustments, ScanEntry}, pdf::{Margins, PaperSize}, PageOrder, PageSplit};
+
+/// Bumped whenever the session file format changes incompatibly
+const SESSION_VERSION: u32 = 1;
+
+/// Everything needed to pick up a scanning session where it was left off.
+/// Page pixels are stored as PNG files in a directory next to the session file.
+#[derive(Serialize, Deserialize)]
+pub struct Session {
+    version: u32,
+    pub pages: Vec<SessionPage>,
+    pub selected_page_indices: Vec<usize>,
+    pub pixel_adjustments: PixelAdjustments,
+    pub page_split: PageSplit,
+    pub page_order: PageOrder,
+    pub root_location: Option<PathBuf>,
+    pub file_save_path: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SessionPage {
+    /// Name of the page's image file within the session's page directory
+    pub image_file: String,
+    pub saved_to_file: bool,
+    pub note: String,
+    pub page_breaks: Vec<usize>,
+    pub dewarp: Option<Dewarp>,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+    #[serde(default)]
+    pub flip: Flip,
+    /// Seconds since the Unix epoch, missing from sessions saved before scan times were kept
+    #[serde(default)]
+    pub scanned_at: Option<u64>,
+    /// Pages from sessions saved before formats were kept are treated as color
+    #[serde(default)]
+    pub format: PageFormat,
+    #[serde(default)]
+    pub id_card_layout: Option<(PaperSize, Margins)>,
+}
+
+impl SessionPage {
+    /// Writes the entry's pixels into `directory` and records its other state
+    pub fn from_entry(entry: &ScanEntry, index: usize, directory: &Path) -> Result<Self, Box<dyn Error>> {
+        let image_file = format!("page-{index}.png");
+        let buffer = ::image::RgbImage::from_raw(u32::try_from(entry.size[0])?, u32::try_from(entry.size[1])?, entry.pixels()?.into_owned())
+            .ok_or("Page data does not match its dimensions")?;
+        buffer.save(directory.join(&image_file))?;
+
+        Ok(Self {
+            image_file,
+            saved_to_file: entry.saved_to_file,
+            note: entry.note.clone(),
+            page_breaks: entry.page_breaks.clone(),
+            dewarp: entry.dewarp,
+            annotations: entry.annotations.clone(),
+            flip: entry.flip,
+            scanned_at: entry.scanned_at.duration_since(UNIX_EPOCH).ok().map(|elapsed| elapsed.as_secs()),
+            format: entry.format,
+            id_card_layout: entry.id_card_layout,
+        })
+    }
+
+    pub fn scan_time(&self) -> Option<SystemTime> {
+        self.scanned_at.map(|seconds| UNIX_EPOCH + Duration::from_secs(seconds))
+    }
+}
+
+impl Session {
+    pub fn new(pages: Vec<SessionPage>, selected_page_indices: Vec<usize>, pixel_adjustments: PixelAdjustments,
+        page_split: PageSplit, page_order: PageOrder, root_location: Option<PathBuf>, file_save_path: String) -> Self {
+        Self { version: SESSION_VERSION, pages, selected_page_indices, pixel_adjustments, page_split, page_order, root_location, file_save_path }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, Box<dyn Error>> {
+        let session: Self = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if session.version != SESSION_VERSION {
+            return Err(format!("Unsupported session version {}", session.version).into());
+        }
+
+        Ok(session)
+    }
+}
+
+/// Directory holding a session's page images, named after the session file
+pub fn page_directory(session_path: &Path) -> PathBuf {
+    let stem = session_path.file_stem().map_or_else(|| String::from("session"), |stem| stem.to_string_lossy().into_owned());
+    session_path.with_file_name(format!("{stem}-pages"))
+}
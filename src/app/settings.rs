@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use eframe::egui::Modifiers;
+use serde::{Deserialize, Serialize};
+
+use super::{export::ImageFileFormat, image::{IdCardSize, PageFormat, PixelAdjustments}, scanner::EditingDeviceOptionValue, pdf::{Margins, OutputFormat, PaperSize, PdfCompression, Watermark}, strings::{Language, Strings}};
+
+/// User preferences persisted between sessions
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub language: Language,
+    pub overwrite_behavior: OverwriteBehavior,
+    /// Key press in the file name field that saves the selected pages
+    pub save_shortcut: SaveShortcut,
+    pub spill_idle_pages: bool,
+    pub selection_color: [u8; 3],
+    pub selection_alpha: u8,
+    /// Label option states with text as well as color
+    pub text_state_markers: bool,
+    pub copy_saved_path: bool,
+    /// Whether saved pages stay visible after saving, only being deselected, so overlapping subsets can be saved
+    pub keep_pages_after_save: bool,
+    /// Save to an absolute path typed in the file name field as is, rather than within the save location
+    pub absolute_file_names: bool,
+    /// Save every completed scan to the save location without prompting, for unattended use
+    pub auto_save_scans: bool,
+    /// Minimize the window while scanning and show a desktop notification when the scan ends or fails
+    pub background_scanning: bool,
+    /// Times to retry starting the next page when the feeder reports an error between pages
+    pub feed_retries: u32,
+    pub feed_retry_delay_ms: u64,
+    /// Warn before scanning below `low_resolution_threshold` DPI, which is too coarse for reliable OCR
+    pub warn_low_resolution: bool,
+    pub low_resolution_threshold: i32,
+    /// Warn before scanning pages estimated to come to more than `large_scan_limit_mb` megabytes each
+    pub warn_large_scans: bool,
+    pub large_scan_limit_mb: u32,
+    /// Names of devices whose backend delivers BGR data for RGB frames
+    pub bgr_devices: Vec<String>,
+    /// List a built-in scanner that produces test pattern pages, for trying the app without hardware
+    pub test_pattern_scanner: bool,
+    /// Restore each device's last used resolution and source when it is opened
+    pub remember_device_settings: bool,
+    /// Last used resolution and source, by device name
+    pub device_scan_settings: HashMap<String, DeviceScanSettings>,
+    pub defaults: Defaults,
+    pub page_margins: Margins,
+    pub watermark: Watermark,
+    pub image_smoothing: ImageSmoothing,
+    pub pdf_compression: PdfCompression,
+    /// Format of the page images in exported ZIP archives
+    pub export_image_format: ImageFileFormat,
+    pub id_card_size: IdCardSize,
+    pub scan_profiles: Vec<ScanProfile>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            language: Language::default(),
+            overwrite_behavior: OverwriteBehavior::default(),
+            save_shortcut: SaveShortcut::default(),
+            spill_idle_pages: false,
+            selection_color: [0, 0, 255],
+            selection_alpha: 50,
+            text_state_markers: false,
+            copy_saved_path: false,
+            keep_pages_after_save: false,
+            absolute_file_names: false,
+            auto_save_scans: false,
+            background_scanning: false,
+            feed_retries: 2,
+            feed_retry_delay_ms: 500,
+            warn_low_resolution: true,
+            low_resolution_threshold: 300,
+            warn_large_scans: true,
+            large_scan_limit_mb: 500,
+            bgr_devices: Vec::new(),
+            test_pattern_scanner: false,
+            remember_device_settings: true,
+            device_scan_settings: HashMap::new(),
+            defaults: Defaults::default(),
+            page_margins: Margins::default(),
+            watermark: Watermark::default(),
+            image_smoothing: ImageSmoothing::default(),
+            pdf_compression: PdfCompression::default(),
+            export_image_format: ImageFileFormat::default(),
+            id_card_size: IdCardSize::default(),
+            scan_profiles: Vec::new(),
+        }
+    }
+}
+
+/// Starting points for choices made in the main window, so it opens ready for the usual workflow
+#[derive(Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Defaults {
+    pub paper_size: PaperSize,
+    /// `None` saves at full resolution
+    pub output_dpi: Option<u32>,
+    pub output_format: OutputFormat,
+    /// Color mode to switch each device to when it is opened, if it offers it. `None` keeps the device's own default.
+    pub color_mode: Option<PageFormat>,
+}
+
+/// Settings scanned with last on a device, for the ones users most often change back after reopening it
+#[derive(Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceScanSettings {
+    pub resolution: Option<i32>,
+    pub source: Option<String>,
+}
+
+/// A named set of device options and output settings, applied together from the top panel
+#[derive(Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ScanProfile {
+    pub name: String,
+    /// Values of the device's settable options by option name. They apply to whichever device is open, where it has a
+    /// matching option taking the same kind of value.
+    pub device_options: Vec<(String, EditingDeviceOptionValue)>,
+    pub paper_size: PaperSize,
+    pub output_dpi: Option<u32>,
+    pub output_format: OutputFormat,
+    pub pixel_adjustments: PixelAdjustments,
+    pub pdf_compression: PdfCompression,
+    pub export_image_format: ImageFileFormat,
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverwriteBehavior {
+    #[default]
+    Prompt,
+    Overwrite,
+    AppendSuffix,
+}
+
+impl OverwriteBehavior {
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::Prompt        => text.overwrite_prompt_choice,
+            Self::Overwrite     => text.overwrite_always,
+            Self::AppendSuffix  => text.overwrite_never,
+        }
+    }
+}
+
+/// Whether PDF viewers are asked to smooth (interpolate) page images when scaling them
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageSmoothing {
+    /// Smooth color pages only, since smoothing blurs the edges of text and line art but hides pixelation in photos
+    #[default]
+    ColorOnly,
+    Always,
+    Never,
+}
+
+impl ImageSmoothing {
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::ColorOnly => text.smoothing_color_only,
+            Self::Always    => text.smoothing_always,
+            Self::Never     => text.smoothing_never,
+        }
+    }
+
+    pub fn applies(self, grayscale: bool) -> bool {
+        match self {
+            Self::ColorOnly => !grayscale,
+            Self::Always    => true,
+            Self::Never     => false,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SaveShortcut {
+    /// Plain Enter only finishes editing the name
+    #[default]
+    CtrlEnter,
+    Enter,
+}
+
+impl SaveShortcut {
+    pub fn label(self, text: &Strings) -> &'static str {
+        match self {
+            Self::CtrlEnter => text.save_on_ctrl_enter,
+            Self::Enter     => text.save_on_enter,
+        }
+    }
+
+    pub fn matches(self, modifiers: Modifiers) -> bool {
+        match self {
+            Self::CtrlEnter => modifiers.command,
+            Self::Enter     => true,
+        }
+    }
+}
@@ -0,0 +1,1352 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+
+/// Languages the UI can be displayed in
+#[derive(Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::Spanish];
+
+    /// Name of the language in that language, so it can be recognized regardless of the current one
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::English   => "English",
+            Self::Spanish   => "Español",
+        }
+    }
+
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Self::English   => &ENGLISH,
+            Self::Spanish   => &SPANISH,
+        }
+    }
+}
+
+/// Replaces the first `{}` in a translated template with `value`
+pub fn fill(template: &str, value: impl Display) -> String {
+    template.replacen("{}", &value.to_string(), 1)
+}
+
+/// Every user-facing string in the UI. Entries containing `{}` are templates for [`fill`].
+pub struct Strings {
+    // Dialog titles
+    pub error_title: &'static str,
+    pub remove_partial_title: &'static str,
+    pub delete_page_title: &'static str,
+    pub create_directory_title: &'static str,
+    pub overwrite_title: &'static str,
+    pub import_title: &'static str,
+    pub clear_tray_title: &'static str,
+    pub select_location_title: &'static str,
+    pub quit_title: &'static str,
+    pub save_session_title: &'static str,
+    pub load_session_title: &'static str,
+    pub low_resolution_title: &'static str,
+    pub large_scan_title: &'static str,
+
+    // Dialog messages
+    pub error_refreshing_devices: &'static str,
+    pub error_opening_device: &'static str,
+    pub error_open_timeout: &'static str,
+    pub error_retrieving_options: &'static str,
+    pub error_setting_resolution: &'static str,
+    pub error_setting_duplex: &'static str,
+    pub error_setting_color_mode: &'static str,
+    pub error_applying_config: &'static str,
+    pub error_prescan: &'static str,
+    pub error_no_scan_area: &'static str,
+    pub error_setting_scan_area: &'static str,
+    pub error_reading_scan_area: &'static str,
+    pub error_converting_value: &'static str,
+    pub error_no_device_handle: &'static str,
+    pub error_starting_scan: &'static str,
+    pub error_reading_image: &'static str,
+    pub error_scan_parameters: &'static str,
+    pub error_scanner_closed: &'static str,
+    pub reset_scanner: &'static str,
+    pub reset_scanner_hover: &'static str,
+    pub error_importing_images: &'static str,
+    pub error_refreshing_previews: &'static str,
+    pub error_moving_page_data: &'static str,
+    pub error_merging_pages: &'static str,
+    pub error_making_id_card: &'static str,
+    pub error_staging_pages: &'static str,
+    pub error_stopping_scan: &'static str,
+    pub error_saving_pdf: &'static str,
+    pub error_save_permission_denied: &'static str,
+    pub error_no_pages_selected: &'static str,
+    pub error_no_save_location: &'static str,
+    pub error_path_outside_root: &'static str,
+    pub remove_partial_prompt: &'static str,
+    pub clear_tray_prompt: &'static str,
+    pub create_directory_prompt: &'static str,
+    pub overwrite_prompt: &'static str,
+    pub quit_prompt: &'static str,
+    pub low_resolution_prompt: &'static str,
+    pub large_scan_prompt: &'static str,
+    pub large_scan_lower: &'static str,
+    pub large_scan_anyway: &'static str,
+    pub image_files: &'static str,
+    pub session_files: &'static str,
+    pub replace_pages_prompt: &'static str,
+    pub error_saving_session: &'static str,
+    pub error_exporting_zip: &'static str,
+    pub error_loading_session: &'static str,
+
+    // Top panel
+    pub refresh_devices_hover: &'static str,
+    pub search_network: &'static str,
+    pub selected_scanner: &'static str,
+    pub profile: &'static str,
+    pub profile_hover: &'static str,
+    pub no_profiles: &'static str,
+    pub manage_profiles: &'static str,
+    pub no_scanner: &'static str,
+    pub no_scanner_hover: &'static str,
+    pub opening_device: &'static str,
+    pub cancel: &'static str,
+    pub cancel_opening_hover: &'static str,
+    pub configure_scanner: &'static str,
+    pub resolution_hover: &'static str,
+    pub duplex: &'static str,
+    pub duplex_hover: &'static str,
+    pub format_color: &'static str,
+    pub format_gray: &'static str,
+    pub format_lineart: &'static str,
+    pub color_mode_hover: &'static str,
+    pub max_scan_area: &'static str,
+    pub max_scan_area_hover: &'static str,
+    pub scan_area_diagram: &'static str,
+    pub scan_area_diagram_hover: &'static str,
+    pub scan_area_diagram_title: &'static str,
+    pub scan_area_diagram_help: &'static str,
+    pub scan_area_size: &'static str,
+    pub start_scanning: &'static str,
+    pub disconnect_scanner: &'static str,
+    pub scan_area: &'static str,
+    pub summary_hover: &'static str,
+    pub disconnect_scanner_hover: &'static str,
+    pub prescan: &'static str,
+    pub prescan_hover: &'static str,
+    pub prescanning: &'static str,
+    pub import_images: &'static str,
+    pub import_images_hover: &'static str,
+    pub save_session: &'static str,
+    pub save_session_hover: &'static str,
+    pub load_session: &'static str,
+    pub load_session_hover: &'static str,
+    pub settings: &'static str,
+    pub about: &'static str,
+    pub statistics: &'static str,
+    pub cancel_scan: &'static str,
+    pub pause_scan: &'static str,
+    pub pause_scan_hover: &'static str,
+    pub resume_scan: &'static str,
+    pub scan_paused: &'static str,
+    pub scan_cancelling: &'static str,
+    pub scan_cancelled_summary: &'static str,
+    pub scan_on_button: &'static str,
+    pub scan_on_button_hover: &'static str,
+    pub no_sensors: &'static str,
+    pub no_scanners_found: &'static str,
+    pub no_scanners_found_hover: &'static str,
+    pub retry: &'static str,
+
+    // Bottom panel
+    pub preview_size: &'static str,
+    pub preview_size_hover: &'static str,
+    pub zoom_on_hover: &'static str,
+    pub zoom_on_hover_hover: &'static str,
+    pub thumbnail_columns: &'static str,
+    pub thumbnail_columns_hover: &'static str,
+    pub columns_fit_window: &'static str,
+    pub next_unselected: &'static str,
+    pub next_unselected_hover: &'static str,
+    pub select_location: &'static str,
+    pub no_save_location: &'static str,
+    pub file_name: &'static str,
+    pub saving_to: &'static str,
+    pub copy_saved_path: &'static str,
+    pub saved_size: &'static str,
+    pub saved_size_hover: &'static str,
+    pub copy_saved_path_hover: &'static str,
+    pub selected_pages: &'static str,
+    pub estimated_size_hover: &'static str,
+    pub page_data_memory: &'static str,
+    pub page_data_disk_hover: &'static str,
+    pub show_saved: &'static str,
+    pub show_saved_hover: &'static str,
+    pub saved_pages_hidden: &'static str,
+    pub auto_save_active: &'static str,
+    pub auto_save_hover: &'static str,
+    pub reorder_selection: &'static str,
+    pub reorder_selection_hover: &'static str,
+    pub stage_selection: &'static str,
+    pub stage_selection_hover: &'static str,
+    pub staging_tray: &'static str,
+    pub save_staged: &'static str,
+    pub save_staged_hover: &'static str,
+    pub clear_tray: &'static str,
+    pub remove_from_tray: &'static str,
+    pub invert_colors: &'static str,
+    pub invert_colors_hover: &'static str,
+    pub document_cleanup: &'static str,
+    pub document_cleanup_hover: &'static str,
+    pub cleanup_neighborhood_suffix: &'static str,
+    pub cleanup_neighborhood_hover: &'static str,
+    pub cleanup_threshold_suffix: &'static str,
+    pub cleanup_threshold_hover: &'static str,
+    pub page_order: &'static str,
+    pub page_order_hover: &'static str,
+    pub order_as_selected: &'static str,
+    pub order_reverse: &'static str,
+    pub order_scan: &'static str,
+    pub output_mode_hover: &'static str,
+    pub output_single_file: &'static str,
+    pub output_file_per_page: &'static str,
+    pub output_split_on_blank: &'static str,
+    pub error_only_blank_pages: &'static str,
+    pub output_format_pdf: &'static str,
+    pub output_format_pdfa: &'static str,
+    pub output_format_hover: &'static str,
+    pub watermark_not_in_pdfa: &'static str,
+    pub paper_letter: &'static str,
+    pub paper_legal: &'static str,
+    pub paper_a4: &'static str,
+    pub paper_size_hover: &'static str,
+    pub output_full_resolution: &'static str,
+    pub output_resolution_hover: &'static str,
+    pub output_dpi: &'static str,
+    pub split_pages: &'static str,
+    pub split_pages_hover: &'static str,
+    pub remove_partial: &'static str,
+    pub remove_partial_hover: &'static str,
+
+    // Page thumbnails
+    pub page_number: &'static str,
+    pub selecting_page: &'static str,
+    pub page_note: &'static str,
+    pub partial_page_warning: &'static str,
+    pub note_editor: &'static str,
+    pub done: &'static str,
+    pub insert_page_breaks: &'static str,
+    pub insert_page_breaks_hover: &'static str,
+    pub dewarp: &'static str,
+    pub dewarp_hover: &'static str,
+    pub dewarp_applied: &'static str,
+    pub spine_side: &'static str,
+    pub spine_left: &'static str,
+    pub spine_right: &'static str,
+    pub dewarp_strength: &'static str,
+    pub dewarp_width: &'static str,
+    pub annotate: &'static str,
+    pub annotate_hover: &'static str,
+    pub annotated: &'static str,
+    pub flip_horizontal: &'static str,
+    pub flip_vertical: &'static str,
+    pub flipped: &'static str,
+    pub duplicate_page: &'static str,
+    pub duplicate_page_hover: &'static str,
+    pub merge_with_next: &'static str,
+    pub merge_with_next_hover: &'static str,
+    pub merge_title: &'static str,
+    pub merge_side_by_side: &'static str,
+    pub merge_stacked: &'static str,
+    pub merge_scale_to_match: &'static str,
+    pub merge_scale_to_match_hover: &'static str,
+    pub merge_note: &'static str,
+    pub merge_pages: &'static str,
+    pub add_page_to_tray: &'static str,
+    pub add_page_to_tray_hover: &'static str,
+    pub rescan_page: &'static str,
+    pub rescan_page_hover: &'static str,
+    pub pages_locked_hover: &'static str,
+    pub delete_page: &'static str,
+    pub delete_page_hover: &'static str,
+    pub delete_page_prompt: &'static str,
+
+    // Selection order window
+    pub selection_order_title: &'static str,
+    pub selection_order_note: &'static str,
+    pub move_up: &'static str,
+    pub move_down: &'static str,
+
+    // Scanner configuration window
+    pub config_title: &'static str,
+    pub close: &'static str,
+    pub apply: &'static str,
+    pub common_values: &'static str,
+    pub swap_red_blue: &'static str,
+    pub swap_red_blue_hover: &'static str,
+    pub hardware_only_hover: &'static str,
+    pub inactive: &'static str,
+    pub inactive_hover: &'static str,
+    pub value_unavailable: &'static str,
+    pub value_unavailable_hover: &'static str,
+    pub range: &'static str,
+    pub type_to_filter: &'static str,
+    pub activate: &'static str,
+    pub activate_pending: &'static str,
+    pub activate_pending_background: &'static str,
+    pub auto: &'static str,
+    pub auto_hover: &'static str,
+    pub device_busy: &'static str,
+    pub reset: &'static str,
+    pub inactive_marker: &'static str,
+    pub range_marker: &'static str,
+    pub group_marker: &'static str,
+    pub error_marker: &'static str,
+    pub now_active: &'static str,
+    pub now_active_hover: &'static str,
+    pub now_inactive: &'static str,
+    pub now_inactive_hover: &'static str,
+
+    // Page breaks window
+    pub page_breaks_title: &'static str,
+    pub page_breaks_help: &'static str,
+    pub clear_breaks: &'static str,
+
+    // Annotation window
+    pub annotate_title: &'static str,
+    pub annotate_help: &'static str,
+    pub pen: &'static str,
+    pub highlighter: &'static str,
+    pub undo: &'static str,
+    pub clear_annotations: &'static str,
+
+    // Scan area window
+    pub scan_area_title: &'static str,
+    pub scan_area_help: &'static str,
+    pub use_selected_area: &'static str,
+    pub use_whole_bed: &'static str,
+
+    // Error window
+    pub error_details: &'static str,
+    pub ok: &'static str,
+    pub more_errors: &'static str,
+
+    // Settings window
+    pub settings_title: &'static str,
+    pub language: &'static str,
+    pub file_exists: &'static str,
+    pub save_shortcut: &'static str,
+    pub save_shortcut_hover: &'static str,
+    pub save_on_ctrl_enter: &'static str,
+    pub save_on_enter: &'static str,
+    pub save_pdf: &'static str,
+    pub save_pdf_hover: &'static str,
+    pub save_needs_selection: &'static str,
+    pub save_needs_selection_hover: &'static str,
+    pub scan_to_print: &'static str,
+    pub scan_to_print_hover: &'static str,
+    pub scan_to_pdf: &'static str,
+    pub scan_to_pdf_hover: &'static str,
+    pub scanning_to_pdf: &'static str,
+    pub print_pages: &'static str,
+    pub id_card_page: &'static str,
+    pub id_card_page_hover: &'static str,
+    pub id_card_needs_two_pages: &'static str,
+    pub id_card_layout_changed_title: &'static str,
+    pub id_card_layout_changed_prompt: &'static str,
+    pub print_pages_hover: &'static str,
+    pub export_zip: &'static str,
+    pub export_zip_hover: &'static str,
+    pub export_zip_title: &'static str,
+    pub zip_files: &'static str,
+    pub export_image_format: &'static str,
+    pub image_format_png: &'static str,
+    pub image_format_jpeg: &'static str,
+    pub print_title: &'static str,
+    pub print_page_count: &'static str,
+    pub printer: &'static str,
+    pub copies: &'static str,
+    pub print: &'static str,
+    pub error_printing: &'static str,
+    pub error_no_printer: &'static str,
+    pub image_smoothing: &'static str,
+    pub image_smoothing_hover: &'static str,
+    pub pdf_compression: &'static str,
+    pub pdf_compression_hover: &'static str,
+    pub compression_off: &'static str,
+    pub compression_fast: &'static str,
+    pub compression_balanced: &'static str,
+    pub compression_smallest: &'static str,
+    pub smoothing_color_only: &'static str,
+    pub smoothing_always: &'static str,
+    pub smoothing_never: &'static str,
+    pub while_scanning: &'static str,
+    pub background_scanning: &'static str,
+    pub background_scanning_hover: &'static str,
+    pub notify_scan_finished: &'static str,
+    pub notify_scan_pages: &'static str,
+    pub notify_scan_error: &'static str,
+    pub preview_layout: &'static str,
+    pub preview_layout_hover: &'static str,
+    pub preview_layout_title: &'static str,
+    pub preview_layout_note: &'static str,
+    pub overwrite_prompt_choice: &'static str,
+    pub overwrite_always: &'static str,
+    pub overwrite_never: &'static str,
+    pub selection_highlight: &'static str,
+    pub strength: &'static str,
+    pub page_data: &'static str,
+    pub spill_pages: &'static str,
+    pub spill_pages_hover: &'static str,
+    pub scanning: &'static str,
+    pub warn_low_resolution: &'static str,
+    pub warn_low_resolution_hover: &'static str,
+    pub warn_large_scans: &'static str,
+    pub warn_large_scans_hover: &'static str,
+    pub device_settings: &'static str,
+    pub remember_device_settings: &'static str,
+    pub remember_device_settings_hover: &'static str,
+    pub virtual_scanner: &'static str,
+    pub test_pattern_scanner: &'static str,
+    pub test_pattern_scanner_hover: &'static str,
+    pub preferred_color_mode: &'static str,
+    pub device_default_mode: &'static str,
+    pub preferred_color_mode_hover: &'static str,
+    pub default_output: &'static str,
+    pub use_current_output: &'static str,
+    pub default_output_hover: &'static str,
+    pub forget_device_settings: &'static str,
+    pub document_feeder: &'static str,
+    pub feed_retries_suffix: &'static str,
+    pub feed_retries_hover: &'static str,
+    pub after_scanning: &'static str,
+    pub auto_save_scans: &'static str,
+    pub after_saving: &'static str,
+    pub auto_copy_path: &'static str,
+    pub keep_pages_after_save: &'static str,
+    pub keep_pages_after_save_hover: &'static str,
+    pub file_name_field: &'static str,
+    pub absolute_file_names: &'static str,
+    pub absolute_file_names_hover: &'static str,
+    pub watermark: &'static str,
+    pub watermark_enabled: &'static str,
+    pub watermark_text_hint: &'static str,
+    pub watermark_hover: &'static str,
+    pub page_margins: &'static str,
+    pub page_margins_hover: &'static str,
+    pub id_card_size: &'static str,
+    pub id_card_size_hover: &'static str,
+    pub margin_top: &'static str,
+    pub margin_right: &'static str,
+    pub margin_bottom: &'static str,
+    pub margin_left: &'static str,
+    pub opacity: &'static str,
+    pub corner_top_left: &'static str,
+    pub corner_top_right: &'static str,
+    pub corner_bottom_left: &'static str,
+    pub corner_bottom_right: &'static str,
+    pub accessibility: &'static str,
+    pub text_markers: &'static str,
+    pub text_markers_hover: &'static str,
+
+    // About window
+    pub about_title: &'static str,
+    pub statistics_title: &'static str,
+    pub profiles_title: &'static str,
+    pub profile_device_options: &'static str,
+    pub apply_profile: &'static str,
+    pub delete_profile: &'static str,
+    pub profile_name_hint: &'static str,
+    pub save_profile: &'static str,
+    pub save_profile_hover: &'static str,
+    pub pages_scanned: &'static str,
+    pub pages_saved: &'static str,
+    pub average_page_time: &'static str,
+    pub app_version: &'static str,
+    pub sane_version: &'static str,
+    pub configured_backends: &'static str,
+    pub backends_unavailable: &'static str,
+    pub device_name: &'static str,
+    pub device_vendor: &'static str,
+    pub device_model: &'static str,
+    pub device_type: &'static str,
+    pub device_backend: &'static str,
+    pub device_opened: &'static str,
+    pub yes: &'static str,
+    pub no: &'static str,
+    pub copy_diagnostics: &'static str,
+
+    // Common values window
+    pub common_values_title: &'static str,
+    pub units: &'static str,
+    pub copy: &'static str,
+}
+
+pub const ENGLISH: Strings = Strings {
+    error_title: "SlickScan Error",
+    remove_partial_title: "Remove partial pages?",
+    delete_page_title: "Delete page?",
+    create_directory_title: "Create directory?",
+    overwrite_title: "Overwrite file?",
+    import_title: "Import images",
+    clear_tray_title: "Clear staging tray?",
+    select_location_title: "Select root save location",
+    quit_title: "Quit SlickScan?",
+    save_session_title: "Save session",
+    load_session_title: "Load session",
+    low_resolution_title: "Low resolution",
+    large_scan_title: "Large scan",
+
+    error_refreshing_devices: "Error refreshing device list:",
+    error_opening_device: "Failed to open device:",
+    error_open_timeout: "Timed out opening device — it may be unresponsive. Check its connection, then refresh the device list to try again.",
+    error_retrieving_options: "Failed to retrieve options:",
+    error_setting_resolution: "Error setting resolution:",
+    error_setting_duplex: "Error changing duplex scanning:",
+    error_setting_color_mode: "Error changing the color mode:",
+    error_applying_config: "Error applying configuration:",
+    error_prescan: "Error during prescan:",
+    error_no_scan_area: "This scanner doesn't support selecting a scan area.",
+    error_setting_scan_area: "Error setting the scan area:",
+    error_reading_scan_area: "Error reading the scan area:",
+    error_converting_value: "Error converting from editor value",
+    error_no_device_handle: "Not attached to a device handle!",
+    error_starting_scan: "Error occurred while initiating scan:",
+    error_reading_image: "Error reading image data:",
+    error_scan_parameters: "Error retrieving scan parameters:",
+    error_scanner_closed: "The scanner was closed because the last {} scans failed. It may need to be switched off and on again before resetting it. The last error was:",
+    reset_scanner: "Reset scanner",
+    reset_scanner_hover: "Open the scanner again",
+    error_importing_images: "Some images could not be imported:",
+    error_refreshing_previews: "Failed to refresh the preview of {} page(s)",
+    error_moving_page_data: "Error moving page data:",
+    error_merging_pages: "Error merging pages:",
+    error_making_id_card: "Error making ID card page:",
+    error_staging_pages: "Error copying pages to the staging tray:",
+    error_stopping_scan: "Error occurred while stopping scan (see console for details)",
+    error_saving_pdf: "Error occurred while saving PDF file:",
+    error_save_permission_denied: "You don't have permission to write to {}. Nothing was cleared, so the same pages can be saved once you choose a different location with \"Select root save location...\" or get write access to this folder (for example from its owner or an administrator). Details:",
+    error_no_pages_selected: "No pages selected",
+    error_no_save_location: "No root save location selected",
+    error_path_outside_root: "The file name leads outside the root save location",
+    remove_partial_prompt: "{} page(s) appear to be truncated compared to the others. Remove them?",
+    clear_tray_prompt: "Remove all {} unsaved page(s) from the staging tray?",
+    create_directory_prompt: "The location {} does not exist. Create it?",
+    overwrite_prompt: "A file with that name already exists. Overwrite?",
+    quit_prompt: "You have unsaved scans — quit anyway?",
+    low_resolution_prompt: "The scan resolution is {} DPI, below the {} DPI recommended for text recognition (OCR). Scan anyway?\n\nThis warning can be turned off in Settings.",
+    large_scan_prompt: "At {} DPI, each page will come to about {}, above the {} limit, and may be slow to scan and save.",
+    large_scan_lower: "Lower the resolution to {} DPI (about {} per page) before scanning? Choose No to scan at the current resolution.",
+    large_scan_anyway: "Scan anyway?\n\nThis warning can be turned off in Settings.",
+    image_files: "Image files",
+    session_files: "SlickScan sessions",
+    replace_pages_prompt: "Loading a session replaces the current pages. Continue?",
+    error_saving_session: "Error occurred while saving session:",
+    error_exporting_zip: "Error occurred while exporting pages:",
+    error_loading_session: "Error occurred while loading session:",
+
+    refresh_devices_hover: "Refresh the device list",
+    search_network: "Search the network for devices",
+    selected_scanner: " is the selected scanner.",
+    profile: "Profile",
+    profile_hover: "Apply a saved set of scanner options and output settings in one click",
+    no_profiles: "No profiles saved yet",
+    manage_profiles: "Manage profiles...",
+    no_scanner: "(None)",
+    no_scanner_hover: "No scanner available — try clicking refresh",
+    opening_device: "Opening device...",
+    cancel: "Cancel",
+    cancel_opening_hover: "Stop waiting for this device",
+    configure_scanner: "Configure scanner...",
+    resolution_hover: "Scan resolution",
+    duplex: "Duplex",
+    duplex_hover: "Scan both sides of each sheet in the document feeder",
+    format_color: "8-bit color",
+    format_gray: "8-bit gray",
+    format_lineart: "1-bit lineart",
+    color_mode_hover: "Color mode and bit depth to scan in",
+    max_scan_area: "Max area",
+    max_scan_area_hover: "Set the scan area to the whole bed or feeder, in case it was left cropped",
+    scan_area_diagram: "Scan area...",
+    scan_area_diagram_hover: "Show the scan area on a diagram of the bed and adjust it by dragging",
+    scan_area_diagram_title: "Scan area",
+    scan_area_diagram_help: "Drag the area or its corners, or drag outside it to draw a new one. The scanner is updated when you let go.",
+    scan_area_size: "Area: {} × {} (in the scanner's units, usually mm)",
+    start_scanning: "Start scanning",
+    disconnect_scanner: "Disconnect scanner",
+    scan_area: "Scan area",
+    summary_hover: "Click to show this option in the scanner configuration",
+    disconnect_scanner_hover: "Release the scanner so other applications can use it. Click refresh to reconnect.",
+    prescan: "Prescan...",
+    prescan_hover: "Quickly scan the whole bed at low resolution, then drag out the area to scan",
+    prescanning: "Prescanning...",
+    import_images: "Import images...",
+    import_images_hover: "Add PNG, JPEG, or TIFF files as pages alongside scanned ones",
+    save_session: "Save session...",
+    save_session_hover: "Save all pages, the selection, and output options so the session can be resumed later",
+    load_session: "Load session...",
+    load_session_hover: "Resume a previously saved session",
+    settings: "Settings...",
+    about: "About...",
+    statistics: "Statistics...",
+    cancel_scan: "Cancel scan",
+    pause_scan: "Pause",
+    pause_scan_hover: "Stop feeding pages after the current one without ending the scan, for example to add more paper",
+    resume_scan: "Resume",
+    scan_paused: "Paused — pages read so far are kept",
+    scan_cancelling: "Cancelling — waiting for the scanner to stop",
+    scan_cancelled_summary: "Scan cancelled — {} pages kept",
+    scan_on_button: "Scan on device button",
+    scan_on_button_hover: "Start scanning when a hardware button (a sensor option) on the scanner is pressed",
+    no_sensors: "(No sensors found)",
+    no_scanners_found: "No scanners found — ensure SANE backends are installed and the device is connected.",
+    no_scanners_found_hover: "Backends are enabled in /etc/sane.d/dll.conf. For network scanners, check \"Search the network for devices\".",
+    retry: "Retry",
+
+    preview_size: "Preview size",
+    preview_size_hover: "Ctrl+scroll over the pages also changes the preview size",
+    zoom_on_hover: "Zoom on hover",
+    zoom_on_hover_hover: "Show an enlarged preview of a page while hovering over it",
+    thumbnail_columns: "Columns",
+    thumbnail_columns_hover: "Lay thumbnails out in this many columns whatever their size, or fit as many as the window allows",
+    columns_fit_window: "Fit window",
+    next_unselected: "First unselected",
+    next_unselected_hover: "Scroll to the first page that hasn't been selected yet (shortcut: N)",
+    select_location: "Select root save location...",
+    no_save_location: "No save location selected",
+    file_name: "File name/path: ",
+    saving_to: "Saving to {}",
+    copy_saved_path: "Copy saved path",
+    saved_size: "Saved: {}",
+    saved_size_hover: "Combined size of the {} file(s) last saved",
+    copy_saved_path_hover: "Copy the full path of the last saved PDF to the clipboard",
+    selected_pages: "{} page(s), ~{}",
+    estimated_size_hover: "Estimated output size: {} uncompressed, ~{} compressed. Lower the scan resolution to reduce it.",
+    page_data_memory: "Page data: {} in memory",
+    page_data_disk_hover: "{} of page data is stored on disk",
+    show_saved: "Show saved",
+    show_saved_hover: "Show scanned images even after they are saved to a file (selecting reveals previously-saved images)",
+    saved_pages_hidden: "{} saved page(s) hidden",
+    auto_save_active: "Auto-save on",
+    auto_save_hover: "When the feeder runs out, all pages of the scan are saved to the save location under the file name above, without prompts. Missing directories are created and existing files are never overwritten. Turn this off in Settings.",
+    reorder_selection: "Reorder...",
+    reorder_selection_hover: "Change the order of the selected pages in the output without moving their thumbnails",
+    stage_selection: "Add to tray",
+    stage_selection_hover: "Copy the selected pages to the staging tray, which collects pages across several scans into one PDF",
+    staging_tray: "Staging tray: {} page(s)",
+    save_staged: "Save staged PDF",
+    save_staged_hover: "Save every page in the tray, in tray order, as a single PDF using the file name below",
+    clear_tray: "Clear tray",
+    remove_from_tray: "Remove from tray",
+    invert_colors: "Invert colors",
+    invert_colors_hover: "Invert all pages in the preview and saved output, e.g. for film negatives and transparencies",
+    document_cleanup: "Document cleanup",
+    document_cleanup_hover: "Turn pages into black text on a clean white background, for small and legible document scans. Applies to the preview and saved output.",
+    cleanup_neighborhood_suffix: "% area",
+    cleanup_neighborhood_hover: "Size of the area each point is compared with, as a percentage of the page width. Increase it if thick or large text gets hollow.",
+    cleanup_threshold_suffix: "% contrast",
+    cleanup_threshold_hover: "How much darker than its surroundings a point must be to be kept as text. Increase it to remove more background specks, decrease it to keep faint text.",
+    page_order: "page order",
+    page_order_hover: "Order in which selected pages are written to the PDF. Reverse suits back-of-stack feeder scans.",
+    order_as_selected: "As selected",
+    order_reverse: "Reverse",
+    order_scan: "By scan order",
+    output_mode_hover: "Write all selected pages to one PDF, each page to its own numbered PDF (e.g. scan-1.pdf, scan-2.pdf), or each run of pages between blank separator sheets to its own numbered PDF",
+    output_single_file: "One PDF",
+    output_file_per_page: "One PDF per page",
+    output_split_on_blank: "One PDF per document (split on blank pages)",
+    error_only_blank_pages: "All selected pages are blank, so there is nothing to save",
+    output_format_pdf: "PDF",
+    output_format_pdfa: "PDF/A (archival)",
+    output_format_hover: "PDF/A-2b embeds a color profile and metadata so the file can be archived long-term. The watermark is left out of PDF/A files.",
+    watermark_not_in_pdfa: "The watermark is left out of PDF/A files, which can only use embedded fonts",
+    paper_letter: "Letter",
+    paper_legal: "Legal",
+    paper_a4: "A4",
+    paper_size_hover: "Page size of the saved PDF",
+    output_full_resolution: "Full resolution",
+    output_resolution_hover: "Downsample pages with more detail than this when saving, for a smaller PDF",
+    output_dpi: "Output: about {} DPI",
+    split_pages: "Split pages every",
+    split_pages_hover: "Slice each long scan (e.g. a receipt) into multiple PDF pages of the given length",
+    remove_partial: "Remove partial pages ({})",
+    remove_partial_hover: "Remove pages that are much shorter than the others, such as a truncated final feeder page",
+
+    page_number: "Page {}",
+    selecting_page: "Selecting page {}...",
+    page_note: "Note: {}",
+    partial_page_warning: "⚠ This page is much shorter than the others and may be a partial read",
+    note_editor: "Page note (saved as a PDF annotation):",
+    done: "Done",
+    insert_page_breaks: "Insert page breaks...",
+    insert_page_breaks_hover: "View the whole scan and click to divide it into multiple PDF pages",
+    dewarp: "Straighten book curvature",
+    dewarp_hover: "Stretch the area near the spine of a bound book so curved lines of text lie flatter. Applied when saving.",
+    dewarp_applied: "Book curvature correction is applied when saving",
+    spine_side: "Spine:",
+    spine_left: "Left",
+    spine_right: "Right",
+    dewarp_strength: "strength",
+    dewarp_width: "width",
+    annotate: "Annotate...",
+    annotate_hover: "Draw or highlight on the page. Annotations are added to the saved PDF.",
+    annotated: "Annotations are drawn into the page when saving",
+    flip_horizontal: "Flip horizontally",
+    flip_vertical: "Flip vertically",
+    duplicate_page: "Duplicate",
+    duplicate_page_hover: "Insert a copy of this page after it, with its own note, breaks and annotations",
+    merge_with_next: "Merge with next page...",
+    merge_with_next_hover: "Join this page and the one after it into a single page, e.g. two halves of a large document",
+    merge_title: "Merge pages",
+    merge_side_by_side: "Side by side",
+    merge_stacked: "Stacked",
+    merge_scale_to_match: "Scale second page to match",
+    merge_scale_to_match_hover: "Resize the second page so the edges where the pages meet are the same length. Otherwise the shorter page is padded with white.",
+    merge_note: "Flips, annotations and dewarping are drawn into the merged page and can no longer be changed.",
+    merge_pages: "Merge",
+    add_page_to_tray: "Add to staging tray",
+    add_page_to_tray_hover: "Copy this page to the staging tray, which collects pages across several scans into one PDF",
+    rescan_page: "Replace with a new scan",
+    rescan_page_hover: "Scan a single page to take this page's place in the selection",
+    pages_locked_hover: "Unavailable until the page being rescanned arrives",
+    delete_page: "Delete",
+    delete_page_hover: "Remove this page from the scan",
+    delete_page_prompt: "Delete this page? This can't be undone.",
+    flipped: "Flipped",
+
+    selection_order_title: "Selected page order",
+    selection_order_note: "Applies when the page order is \"As selected\"",
+    move_up: "Move up",
+    move_down: "Move down",
+
+    config_title: "Scanner Configuration",
+    close: "Close",
+    apply: "Apply",
+    common_values: "Common numerical values...",
+    swap_red_blue: "Swap red/blue",
+    swap_red_blue_hover: "Turn on if colors from this scanner come out with red and blue swapped. Some backends deliver BGR data while reporting RGB. Remembered for this scanner and applied to the next scan.",
+    hardware_only_hover: "This option cannot be changed in software — look on the hardware device to adjust.",
+    inactive: "(Inactive)",
+    inactive_hover: "This option is inactive. There may be another option that, once applied, causes this option to take effect.",
+    value_unavailable: "(Couldn't read value)",
+    value_unavailable_hover: "The scanner returned an error when reading this option, so it can't be edited: {}",
+    range: "(Range: {} – {}, step: {})",
+    type_to_filter: "Type to filter...",
+    activate: "Activate",
+    activate_pending: "Will activate when Apply button is clicked.",
+    activate_pending_background: "Will run in the background when Apply button is clicked. This may take a while.",
+    auto: "Auto",
+    auto_hover: "Let the scanner choose this value automatically when the Apply button is clicked",
+    device_busy: "Device is busy...",
+    reset: "Reset",
+    inactive_marker: "[inactive]",
+    range_marker: "[range]",
+    group_marker: "[group]",
+    error_marker: "[error]",
+    now_active: "★ now active",
+    now_active_hover: "This option became available after the last Apply",
+    now_inactive: "now inactive",
+    now_inactive_hover: "This option stopped taking effect after the last Apply",
+
+    page_breaks_title: "Page Breaks",
+    page_breaks_help: "{} PDF page(s). Click the scan to insert a page break; click a break to remove it.",
+    clear_breaks: "Clear breaks",
+
+    annotate_title: "Annotate Page",
+    annotate_help: "Drag on the page to draw.",
+    pen: "Pen",
+    highlighter: "Highlighter",
+    undo: "Undo",
+    clear_annotations: "Clear",
+
+    scan_area_title: "Scan Area",
+    scan_area_help: "Drag on the prescan to select the area to scan.",
+    use_selected_area: "Use selected area",
+    use_whole_bed: "Use whole bed",
+
+    error_details: "Details",
+    ok: "OK",
+    more_errors: "{} more error(s)",
+
+    settings_title: "Settings",
+    language: "Language:",
+    file_exists: "When the file already exists:",
+    save_shortcut: "Save from the file name with:",
+    save_shortcut_hover: "Key press in the file name field that saves the selected pages. With Ctrl+Enter, a plain Enter just finishes editing the name.",
+    save_on_ctrl_enter: "Ctrl+Enter",
+    save_on_enter: "Enter",
+    save_pdf: "Save PDF",
+    save_pdf_hover: "Save the selected pages using the file name to the left (shortcut: {})",
+    save_needs_selection: "Select pages to save",
+    save_needs_selection_hover: "Click pages below to select them, in the order they should appear in the PDF, then save",
+    scan_to_print: "Scan to print",
+    scan_to_print_hover: "Scan, then print the scanned pages",
+    scan_to_pdf: "Scan directly to PDF",
+    scan_to_pdf_hover: "Write each page to a PDF in the save location as it is scanned, instead of keeping it in the page list. Suited to very long scans. Notes, page breaks, the watermark and PDF/A are not applied.",
+    scanning_to_pdf: "Scanning directly to {}",
+    print_pages: "Print...",
+    id_card_page: "ID card page",
+    id_card_page_hover: "Replace the two selected pages, the front and back of a card in the order selected, with one page showing both sides at actual size",
+    id_card_needs_two_pages: "Select the front and then the back of a card",
+    id_card_layout_changed_title: "ID card pages",
+    id_card_layout_changed_prompt: "{} ID card page(s) were laid out for a different paper size or margins, so they won't come out at actual size. Switch back to that paper size and those margins to keep them at actual size. Save anyway?",
+    print_pages_hover: "Print the selected pages",
+    export_zip: "Export as ZIP",
+    export_zip_hover: "Save the selected pages as {} images in a ZIP archive",
+    export_zip_title: "Export pages as ZIP",
+    zip_files: "ZIP archives",
+    export_image_format: "Exported images",
+    image_format_png: "PNG",
+    image_format_jpeg: "JPEG",
+    print_title: "Print",
+    print_page_count: "{} page(s) to print",
+    printer: "Printer",
+    copies: "Copies",
+    print: "Print",
+    error_printing: "Error occurred while printing:",
+    error_no_printer: "No printer is set up. Add one in your system's printer settings, then try again.",
+    image_smoothing: "Image smoothing",
+    image_smoothing_hover: "Whether PDF viewers smooth page images when zooming. Smoothing makes photos look better but blurs text and line art.",
+    pdf_compression: "PDF compression:",
+    pdf_compression_hover: "How hard page images are compressed when saving. Stronger compression makes smaller files but takes longer to save.",
+    compression_off: "Off (fastest, largest)",
+    compression_fast: "Fast",
+    compression_balanced: "Balanced",
+    compression_smallest: "Smallest (slowest)",
+    smoothing_color_only: "Color pages only",
+    smoothing_always: "All pages",
+    smoothing_never: "No pages",
+    while_scanning: "While scanning",
+    background_scanning: "Minimize to the tray and notify when done",
+    background_scanning_hover: "Minimize the window and show a system tray icon while a scan runs, then show a desktop notification when the feeder runs out or an error occurs. Click the icon to bring the window back; it also comes back when the scan ends. On desktops without a tray, the window is minimized to the taskbar.",
+    notify_scan_finished: "Scan finished",
+    notify_scan_pages: "{} page(s) scanned",
+    notify_scan_error: "Scan error",
+    preview_layout: "Preview layout",
+    preview_layout_hover: "Show how the selected pages will be placed on the pages of the PDF, without saving",
+    preview_layout_title: "PDF layout preview",
+    preview_layout_note: "Pages are shown in output order with the current page breaks, splitting and watermark. Dewarping is applied only when saving.",
+    overwrite_prompt_choice: "Always ask",
+    overwrite_always: "Always overwrite",
+    overwrite_never: "Never overwrite (add a number)",
+    selection_highlight: "Selection highlight:",
+    strength: "strength",
+    page_data: "Page data:",
+    spill_pages: "Keep full-resolution page data on disk",
+    spill_pages_hover: "Reduces memory use for large batches. Page data is read back from a temporary file when saving.",
+    scanning: "Scanning:",
+    warn_low_resolution: "Warn once per session when scanning below",
+    warn_low_resolution_hover: "Text recognition (OCR) works poorly on scans below about 300 DPI",
+    warn_large_scans: "Warn when each page would come to more than",
+    warn_large_scans_hover: "Estimated from the scan area, resolution, mode and bit depth. Some scanners accept resolutions far beyond what they can really deliver, producing huge files slowly.",
+    device_settings: "Scanner settings",
+    remember_device_settings: "Remember resolution and source for each scanner",
+    remember_device_settings_hover: "The resolution and source used for the last scan on a scanner are set again when it is next opened",
+    virtual_scanner: "Virtual scanner",
+    test_pattern_scanner: "List a test pattern scanner",
+    test_pattern_scanner_hover: "Adds a scanner to the device list that needs no hardware and scans generated test pages, for trying out the app",
+    preferred_color_mode: "Preferred color mode:",
+    device_default_mode: "Device default",
+    preferred_color_mode_hover: "Color mode to switch each scanner to when it is opened, if the scanner offers it",
+    default_output: "Default output:",
+    use_current_output: "Use current",
+    default_output_hover: "Paper size, format and resolution chosen when the app starts",
+    forget_device_settings: "Forget all",
+    document_feeder: "Document feeder:",
+    feed_retries_suffix: " retries",
+    feed_retries_hover: "If starting the next page fails, try again this many times, waiting between attempts, before treating the feeder as empty.",
+    after_scanning: "After scanning:",
+    auto_save_scans: "Save each scan automatically",
+    after_saving: "After saving:",
+    auto_copy_path: "Copy the saved file's path to the clipboard",
+    keep_pages_after_save: "Keep pages after saving",
+    keep_pages_after_save_hover: "Leave saved pages in view, deselected, so they can be saved again in other files. They still count as saved.",
+    file_name_field: "File name field:",
+    absolute_file_names: "Save to absolute paths typed as the file name",
+    absolute_file_names_hover: "A file name starting from the root of the file system is used as is, ignoring the save location",
+    watermark: "Watermark:",
+    watermark_enabled: "Stamp the scan time on saved pages",
+    watermark_text_hint: "Extra text",
+    watermark_hover: "Adds the scan date and time (UTC) and optional text in a corner of every page of the PDF. Characters outside Western European alphabets are left out.",
+    page_margins: "Page margins",
+    page_margins_hover: "Blank space around the image on each side of saved and printed pages, so nothing falls in a printer's unprintable edge. With margins, pages keep their proportions and are centered between them.",
+    id_card_size: "ID card size:",
+    id_card_size_hover: "Width and height of cards on ID card pages. The default is the size of bank cards and most identity cards.",
+    margin_top: "Top",
+    margin_right: "Right",
+    margin_bottom: "Bottom",
+    margin_left: "Left",
+    opacity: "opacity",
+    corner_top_left: "Top left",
+    corner_top_right: "Top right",
+    corner_bottom_left: "Bottom left",
+    corner_bottom_right: "Bottom right",
+    accessibility: "Accessibility:",
+    text_markers: "Mark option states with text",
+    text_markers_hover: "Adds tags such as [inactive] and [range] to the scanner options so their state doesn't rely on color alone.",
+
+    about_title: "About / Diagnostics",
+    statistics_title: "Scan statistics",
+    profiles_title: "Scan profiles",
+    profile_device_options: "{} scanner options",
+    apply_profile: "Apply",
+    delete_profile: "Delete",
+    profile_name_hint: "Profile name",
+    save_profile: "Save current settings",
+    save_profile_hover: "Save the open scanner's options along with the output settings (paper size, resolution, format, adjustments, compression and export format), replacing any profile of the same name",
+    pages_scanned: "Pages scanned:",
+    pages_saved: "Pages saved:",
+    average_page_time: "Average time per page:",
+    app_version: "SlickScan version:",
+    sane_version: "SANE version:",
+    configured_backends: "Configured backends:",
+    backends_unavailable: "(dll.conf not found)",
+    device_name: "Device name:",
+    device_vendor: "Vendor:",
+    device_model: "Model:",
+    device_type: "Type:",
+    device_backend: "Backend:",
+    device_opened: "Opened:",
+    yes: "Yes",
+    no: "No",
+    copy_diagnostics: "Copy diagnostics",
+
+    common_values_title: "Common Values",
+    units: "Units:",
+    copy: "Copy",
+};
+
+pub const SPANISH: Strings = Strings {
+    error_title: "Error de SlickScan",
+    remove_partial_title: "¿Eliminar páginas parciales?",
+    delete_page_title: "¿Eliminar página?",
+    create_directory_title: "¿Crear directorio?",
+    overwrite_title: "¿Sobrescribir archivo?",
+    import_title: "Importar imágenes",
+    clear_tray_title: "¿Vaciar la bandeja?",
+    select_location_title: "Seleccionar ubicación raíz de guardado",
+    quit_title: "¿Salir de SlickScan?",
+    save_session_title: "Guardar sesión",
+    load_session_title: "Cargar sesión",
+    low_resolution_title: "Resolución baja",
+    large_scan_title: "Escaneo grande",
+
+    error_refreshing_devices: "Error al actualizar la lista de dispositivos:",
+    error_opening_device: "No se pudo abrir el dispositivo:",
+    error_open_timeout: "Se agotó el tiempo al abrir el dispositivo; puede que no responda. Compruebe su conexión y actualice la lista de dispositivos para volver a intentarlo.",
+    error_retrieving_options: "No se pudieron obtener las opciones:",
+    error_setting_resolution: "Error al establecer la resolución:",
+    error_setting_duplex: "Error al cambiar el escaneo a doble cara:",
+    error_setting_color_mode: "Error al cambiar el modo de color:",
+    error_applying_config: "Error al aplicar la configuración:",
+    error_prescan: "Error durante el preescaneo:",
+    error_no_scan_area: "Este escáner no permite seleccionar un área de escaneo.",
+    error_setting_scan_area: "Error al establecer el área de escaneo:",
+    error_reading_scan_area: "Error al leer el área de escaneo:",
+    error_converting_value: "Error al convertir el valor del editor",
+    error_no_device_handle: "¡No hay ningún dispositivo abierto!",
+    error_starting_scan: "Error al iniciar el escaneo:",
+    error_reading_image: "Error al leer los datos de la imagen:",
+    error_scan_parameters: "Error al obtener los parámetros de escaneo:",
+    error_scanner_closed: "Se cerró el escáner porque fallaron los últimos {} escaneos. Puede que haya que apagarlo y encenderlo antes de restablecerlo. El último error fue:",
+    reset_scanner: "Restablecer escáner",
+    reset_scanner_hover: "Volver a abrir el escáner",
+    error_importing_images: "No se pudieron importar algunas imágenes:",
+    error_refreshing_previews: "No se pudo actualizar la vista previa de {} página(s)",
+    error_moving_page_data: "Error al mover los datos de página:",
+    error_merging_pages: "Error al unir las páginas:",
+    error_making_id_card: "Error al crear la página de documento de identidad:",
+    error_staging_pages: "Error al copiar páginas a la bandeja:",
+    error_stopping_scan: "Error al detener el escaneo (consulte la consola para más detalles)",
+    error_saving_pdf: "Error al guardar el archivo PDF:",
+    error_save_permission_denied: "No tiene permiso para escribir en {}. No se ha borrado nada, así que podrá guardar las mismas páginas cuando elija otra ubicación con \"Seleccionar ubicación raíz de guardado...\" u obtenga acceso de escritura a esta carpeta (por ejemplo, de su propietario o de un administrador). Detalles:",
+    error_no_pages_selected: "No hay páginas seleccionadas",
+    error_no_save_location: "No se ha seleccionado una ubicación raíz de guardado",
+    error_path_outside_root: "El nombre de archivo sale de la ubicación raíz de guardado",
+    remove_partial_prompt: "{} página(s) parecen estar truncadas en comparación con las demás. ¿Eliminarlas?",
+    clear_tray_prompt: "¿Quitar de la bandeja las {} página(s) sin guardar?",
+    create_directory_prompt: "La ubicación {} no existe. ¿Crearla?",
+    overwrite_prompt: "Ya existe un archivo con ese nombre. ¿Sobrescribirlo?",
+    quit_prompt: "Hay escaneos sin guardar. ¿Salir de todos modos?",
+    low_resolution_prompt: "La resolución de escaneo es de {} PPP, inferior a los {} PPP recomendados para el reconocimiento de texto (OCR). ¿Escanear de todos modos?\n\nEste aviso se puede desactivar en Preferencias.",
+    large_scan_prompt: "A {} PPP, cada página ocupará unos {}, por encima del límite de {}, y puede tardar en escanearse y guardarse.",
+    large_scan_lower: "¿Bajar la resolución a {} PPP (unos {} por página) antes de escanear? Elige No para escanear con la resolución actual.",
+    large_scan_anyway: "¿Escanear de todos modos?\n\nEste aviso se puede desactivar en Preferencias.",
+    image_files: "Archivos de imagen",
+    session_files: "Sesiones de SlickScan",
+    replace_pages_prompt: "Cargar una sesión reemplaza las páginas actuales. ¿Continuar?",
+    error_saving_session: "Error al guardar la sesión:",
+    error_exporting_zip: "Error al exportar las páginas:",
+    error_loading_session: "Error al cargar la sesión:",
+
+    refresh_devices_hover: "Actualizar la lista de dispositivos",
+    search_network: "Buscar dispositivos en la red",
+    selected_scanner: " es el escáner seleccionado.",
+    profile: "Perfil",
+    profile_hover: "Aplicar en un clic un conjunto guardado de opciones del escáner y ajustes de salida",
+    no_profiles: "Aún no hay perfiles guardados",
+    manage_profiles: "Administrar perfiles...",
+    no_scanner: "(Ninguno)",
+    no_scanner_hover: "No hay ningún escáner disponible; pruebe a actualizar",
+    opening_device: "Abriendo dispositivo...",
+    cancel: "Cancelar",
+    cancel_opening_hover: "Dejar de esperar a este dispositivo",
+    configure_scanner: "Configurar escáner...",
+    resolution_hover: "Resolución de escaneo",
+    duplex: "Doble cara",
+    duplex_hover: "Escanear ambas caras de cada hoja en el alimentador de documentos",
+    format_color: "Color de 8 bits",
+    format_gray: "Gris de 8 bits",
+    format_lineart: "Blanco y negro de 1 bit",
+    color_mode_hover: "Modo de color y profundidad de bits del escaneo",
+    max_scan_area: "Área máxima",
+    max_scan_area_hover: "Ajustar el área de escaneo a toda la cama o el alimentador, por si quedó recortada",
+    scan_area_diagram: "Área de escaneo...",
+    scan_area_diagram_hover: "Mostrar el área de escaneo en un diagrama de la cama y ajustarla arrastrando",
+    scan_area_diagram_title: "Área de escaneo",
+    scan_area_diagram_help: "Arrastre el área o sus esquinas, o arrastre fuera de ella para dibujar una nueva. El escáner se actualiza al soltar.",
+    scan_area_size: "Área: {} × {} (en las unidades del escáner, normalmente mm)",
+    start_scanning: "Empezar a escanear",
+    disconnect_scanner: "Desconectar escáner",
+    scan_area: "Área de escaneo",
+    summary_hover: "Haga clic para mostrar esta opción en la configuración del escáner",
+    disconnect_scanner_hover: "Libera el escáner para que otras aplicaciones puedan usarlo. Pulse actualizar para volver a conectarlo.",
+    prescan: "Preescanear...",
+    prescan_hover: "Escanea rápidamente toda la superficie a baja resolución para después arrastrar el área a escanear",
+    prescanning: "Preescaneando...",
+    import_images: "Importar imágenes...",
+    import_images_hover: "Añadir archivos PNG, JPEG o TIFF como páginas junto a las escaneadas",
+    save_session: "Guardar sesión...",
+    save_session_hover: "Guardar todas las páginas, la selección y las opciones de salida para reanudar la sesión más tarde",
+    load_session: "Cargar sesión...",
+    load_session_hover: "Reanudar una sesión guardada anteriormente",
+    settings: "Preferencias...",
+    about: "Acerca de...",
+    statistics: "Estadísticas...",
+    cancel_scan: "Cancelar escaneo",
+    pause_scan: "Pausar",
+    pause_scan_hover: "Dejar de alimentar páginas después de la actual sin terminar el escaneo, por ejemplo para añadir más papel",
+    resume_scan: "Reanudar",
+    scan_paused: "En pausa — se conservan las páginas leídas",
+    scan_cancelling: "Cancelando — esperando a que el escáner se detenga",
+    scan_cancelled_summary: "Escaneo cancelado — {} páginas conservadas",
+    scan_on_button: "Escanear con el botón del dispositivo",
+    scan_on_button_hover: "Empezar a escanear al pulsar un botón físico (una opción de sensor) del escáner",
+    no_sensors: "(No se encontraron sensores)",
+    no_scanners_found: "No se encontraron escáneres; asegúrese de que los backends de SANE están instalados y el dispositivo está conectado.",
+    no_scanners_found_hover: "Los backends se habilitan en /etc/sane.d/dll.conf. Para escáneres de red, marque \"Buscar dispositivos en la red\".",
+    retry: "Reintentar",
+
+    preview_size: "Tamaño de vista previa",
+    preview_size_hover: "Ctrl+rueda sobre las páginas también cambia el tamaño de vista previa",
+    zoom_on_hover: "Ampliar al pasar el cursor",
+    zoom_on_hover_hover: "Mostrar una vista previa ampliada de la página al pasar el cursor sobre ella",
+    thumbnail_columns: "Columnas",
+    thumbnail_columns_hover: "Colocar las miniaturas en este número de columnas sea cual sea su tamaño, o en tantas como quepan en la ventana",
+    columns_fit_window: "Ajustar a la ventana",
+    next_unselected: "Primera sin seleccionar",
+    next_unselected_hover: "Desplazarse a la primera página que aún no se ha seleccionado (atajo: N)",
+    select_location: "Seleccionar ubicación raíz de guardado...",
+    no_save_location: "No se ha seleccionado ubicación de guardado",
+    file_name: "Nombre/ruta del archivo: ",
+    saving_to: "Guardando en {}",
+    copy_saved_path: "Copiar ruta guardada",
+    saved_size: "Guardado: {}",
+    saved_size_hover: "Tamaño total de los {} archivo(s) guardados por última vez",
+    copy_saved_path_hover: "Copiar al portapapeles la ruta completa del último PDF guardado",
+    selected_pages: "{} página(s), ~{}",
+    estimated_size_hover: "Tamaño de salida estimado: {} sin comprimir, ~{} comprimido. Reduzca la resolución de escaneo para disminuirlo.",
+    page_data_memory: "Datos de página: {} en memoria",
+    page_data_disk_hover: "{} de datos de página almacenados en disco",
+    show_saved: "Mostrar guardadas",
+    show_saved_hover: "Mostrar las imágenes escaneadas incluso después de guardarlas en un archivo (seleccionar muestra las imágenes guardadas anteriormente)",
+    saved_pages_hidden: "{} página(s) guardada(s) oculta(s)",
+    auto_save_active: "Guardado automático activado",
+    auto_save_hover: "Cuando el alimentador se vacía, todas las páginas del escaneo se guardan en la ubicación de guardado con el nombre de archivo indicado, sin preguntar. Los directorios que falten se crean y nunca se sobrescriben archivos existentes. Se desactiva en Preferencias.",
+    reorder_selection: "Reordenar...",
+    reorder_selection_hover: "Cambiar el orden de las páginas seleccionadas en el resultado sin mover sus miniaturas",
+    stage_selection: "Añadir a la bandeja",
+    stage_selection_hover: "Copiar las páginas seleccionadas a la bandeja, que reúne páginas de varios escaneos en un solo PDF",
+    staging_tray: "Bandeja: {} página(s)",
+    save_staged: "Guardar PDF de la bandeja",
+    save_staged_hover: "Guardar todas las páginas de la bandeja, en su orden, como un solo PDF con el nombre de archivo de abajo",
+    clear_tray: "Vaciar bandeja",
+    remove_from_tray: "Quitar de la bandeja",
+    invert_colors: "Invertir colores",
+    invert_colors_hover: "Invertir todas las páginas en la vista previa y en el archivo guardado, p. ej. para negativos y transparencias",
+    document_cleanup: "Limpieza de documentos",
+    document_cleanup_hover: "Convertir las páginas en texto negro sobre un fondo blanco limpio, para escaneos de documentos pequeños y legibles. Se aplica a la vista previa y al archivo guardado.",
+    cleanup_neighborhood_suffix: "% de área",
+    cleanup_neighborhood_hover: "Tamaño del área con la que se compara cada punto, como porcentaje del ancho de la página. Auméntelo si el texto grueso o grande queda hueco.",
+    cleanup_threshold_suffix: "% de contraste",
+    cleanup_threshold_hover: "Cuánto más oscuro que su entorno debe ser un punto para conservarse como texto. Auméntelo para eliminar más manchas del fondo y redúzcalo para conservar texto tenue.",
+    page_order: "orden de páginas",
+    page_order_hover: "Orden en que las páginas seleccionadas se escriben en el PDF. El orden inverso es útil para escaneos con alimentador que salen al revés.",
+    order_as_selected: "Según la selección",
+    order_reverse: "Inverso",
+    order_scan: "Según el escaneo",
+    output_mode_hover: "Escribir todas las páginas seleccionadas en un PDF, cada página en su propio PDF numerado (p. ej. scan-1.pdf, scan-2.pdf), o cada grupo de páginas entre hojas separadoras en blanco en su propio PDF numerado",
+    output_single_file: "Un PDF",
+    output_file_per_page: "Un PDF por página",
+    output_split_on_blank: "Un PDF por documento (dividir en páginas en blanco)",
+    error_only_blank_pages: "Todas las páginas seleccionadas están en blanco, así que no hay nada que guardar",
+    output_format_pdf: "PDF",
+    output_format_pdfa: "PDF/A (archivo)",
+    output_format_hover: "PDF/A-2b incluye un perfil de color y metadatos para poder archivar el archivo a largo plazo. Los archivos PDF/A no llevan marca de agua.",
+    watermark_not_in_pdfa: "La marca de agua se omite en los archivos PDF/A, que solo pueden usar fuentes incrustadas",
+    paper_letter: "Carta",
+    paper_legal: "Oficio",
+    paper_a4: "A4",
+    paper_size_hover: "Tamaño de página del PDF guardado",
+    output_full_resolution: "Resolución completa",
+    output_resolution_hover: "Reducir al guardar las páginas con más detalle que esta resolución, para obtener un PDF más pequeño",
+    output_dpi: "Salida: unos {} PPP",
+    split_pages: "Dividir páginas cada",
+    split_pages_hover: "Cortar cada escaneo largo (p. ej. un recibo) en varias páginas PDF de la longitud indicada",
+    remove_partial: "Eliminar páginas parciales ({})",
+    remove_partial_hover: "Eliminar páginas mucho más cortas que las demás, como una última página del alimentador truncada",
+
+    page_number: "Página {}",
+    selecting_page: "Seleccionando página {}...",
+    page_note: "Nota: {}",
+    partial_page_warning: "⚠ Esta página es mucho más corta que las demás y puede ser una lectura parcial",
+    note_editor: "Nota de página (se guarda como anotación PDF):",
+    done: "Listo",
+    insert_page_breaks: "Insertar saltos de página...",
+    insert_page_breaks_hover: "Ver el escaneo completo y hacer clic para dividirlo en varias páginas PDF",
+    dewarp: "Enderezar curvatura de libro",
+    dewarp_hover: "Estirar la zona cercana al lomo de un libro encuadernado para que las líneas curvadas queden más planas. Se aplica al guardar.",
+    dewarp_applied: "La corrección de curvatura de libro se aplica al guardar",
+    spine_side: "Lomo:",
+    spine_left: "Izquierda",
+    spine_right: "Derecha",
+    dewarp_strength: "intensidad",
+    dewarp_width: "anchura",
+    annotate: "Anotar...",
+    annotate_hover: "Dibujar o resaltar sobre la página. Las anotaciones se añaden al PDF guardado.",
+    annotated: "Las anotaciones se dibujan en la página al guardar",
+    flip_horizontal: "Voltear horizontalmente",
+    flip_vertical: "Voltear verticalmente",
+    duplicate_page: "Duplicar",
+    duplicate_page_hover: "Insertar una copia de esta página a continuación, con su propia nota, saltos y anotaciones",
+    merge_with_next: "Unir con la página siguiente...",
+    merge_with_next_hover: "Juntar esta página y la siguiente en una sola página, p. ej. dos mitades de un documento grande",
+    merge_title: "Unir páginas",
+    merge_side_by_side: "Lado a lado",
+    merge_stacked: "Una sobre otra",
+    merge_scale_to_match: "Escalar la segunda página para que coincida",
+    merge_scale_to_match_hover: "Redimensionar la segunda página para que los bordes donde se juntan las páginas tengan la misma longitud. Si no, la página más corta se rellena con blanco.",
+    merge_note: "Los volteos, anotaciones y correcciones de curvatura se dibujan en la página unida y ya no se pueden cambiar.",
+    merge_pages: "Unir",
+    add_page_to_tray: "Añadir a la bandeja",
+    add_page_to_tray_hover: "Copiar esta página a la bandeja, que reúne páginas de varios escaneos en un solo PDF",
+    rescan_page: "Reemplazar con un nuevo escaneo",
+    rescan_page_hover: "Escanear una sola página que ocupe el lugar de esta en la selección",
+    pages_locked_hover: "No disponible hasta que llegue la página que se está volviendo a escanear",
+    delete_page: "Eliminar",
+    delete_page_hover: "Quitar esta página del escaneo",
+    delete_page_prompt: "¿Eliminar esta página? No se puede deshacer.",
+    flipped: "Volteada",
+
+    selection_order_title: "Orden de las páginas seleccionadas",
+    selection_order_note: "Se aplica cuando el orden de páginas es \"Según la selección\"",
+    move_up: "Subir",
+    move_down: "Bajar",
+
+    config_title: "Configuración del escáner",
+    close: "Cerrar",
+    apply: "Aplicar",
+    common_values: "Valores numéricos comunes...",
+    swap_red_blue: "Intercambiar rojo/azul",
+    swap_red_blue_hover: "Actívelo si los colores de este escáner salen con el rojo y el azul intercambiados. Algunos backends entregan datos BGR aunque indiquen RGB. Se recuerda para este escáner y se aplica al siguiente escaneo.",
+    hardware_only_hover: "Esta opción no se puede cambiar por software; ajústela en el propio dispositivo.",
+    inactive: "(Inactiva)",
+    inactive_hover: "Esta opción está inactiva. Puede que otra opción, una vez aplicada, haga que esta tenga efecto.",
+    value_unavailable: "(No se pudo leer el valor)",
+    value_unavailable_hover: "El escáner devolvió un error al leer esta opción, por lo que no se puede editar: {}",
+    range: "(Rango: {} – {}, paso: {})",
+    type_to_filter: "Escriba para filtrar...",
+    activate: "Activar",
+    activate_pending: "Se activará al pulsar el botón Aplicar.",
+    activate_pending_background: "Se ejecutará en segundo plano al pulsar el botón Aplicar. Puede tardar un rato.",
+    auto: "Auto",
+    auto_hover: "Dejar que el escáner elija este valor automáticamente al pulsar el botón Aplicar",
+    device_busy: "El dispositivo está ocupado...",
+    reset: "Restablecer",
+    inactive_marker: "[inactiva]",
+    range_marker: "[rango]",
+    group_marker: "[grupo]",
+    error_marker: "[error]",
+    now_active: "★ ahora activa",
+    now_active_hover: "Esta opción está disponible desde el último Aplicar",
+    now_inactive: "ahora inactiva",
+    now_inactive_hover: "Esta opción dejó de tener efecto tras el último Aplicar",
+
+    page_breaks_title: "Saltos de página",
+    page_breaks_help: "{} página(s) PDF. Haga clic en el escaneo para insertar un salto de página; haga clic en un salto para quitarlo.",
+    clear_breaks: "Quitar saltos",
+
+    annotate_title: "Anotar página",
+    annotate_help: "Arrastre sobre la página para dibujar.",
+    pen: "Bolígrafo",
+    highlighter: "Resaltador",
+    undo: "Deshacer",
+    clear_annotations: "Borrar",
+
+    scan_area_title: "Área de escaneo",
+    scan_area_help: "Arrastre sobre el preescaneo para seleccionar el área a escanear.",
+    use_selected_area: "Usar el área seleccionada",
+    use_whole_bed: "Usar toda la superficie",
+
+    error_details: "Detalles",
+    ok: "Aceptar",
+    more_errors: "{} error(es) más",
+
+    settings_title: "Preferencias",
+    language: "Idioma:",
+    file_exists: "Si el archivo ya existe:",
+    save_shortcut: "Guardar desde el nombre de archivo con:",
+    save_shortcut_hover: "Tecla que guarda las páginas seleccionadas desde el campo de nombre de archivo. Con Ctrl+Intro, un Intro normal solo termina de editar el nombre.",
+    save_on_ctrl_enter: "Ctrl+Intro",
+    save_on_enter: "Intro",
+    save_pdf: "Guardar PDF",
+    save_pdf_hover: "Guardar las páginas seleccionadas con el nombre de archivo de la izquierda (atajo: {})",
+    save_needs_selection: "Seleccione páginas para guardar",
+    save_needs_selection_hover: "Haga clic en las páginas de abajo para seleccionarlas, en el orden en que deben aparecer en el PDF, y luego guarde",
+    scan_to_print: "Escanear e imprimir",
+    scan_to_print_hover: "Escanear y luego imprimir las páginas escaneadas",
+    scan_to_pdf: "Escanear directamente a PDF",
+    scan_to_pdf_hover: "Escribir cada página en un PDF en la ubicación de guardado a medida que se escanea, en lugar de mantenerla en la lista de páginas. Adecuado para escaneos muy largos. No se aplican notas, saltos de página, la marca de agua ni PDF/A.",
+    scanning_to_pdf: "Escaneando directamente a {}",
+    print_pages: "Imprimir...",
+    id_card_page: "Página de documento",
+    id_card_page_hover: "Reemplazar las dos páginas seleccionadas, el anverso y el reverso de una tarjeta en el orden de selección, por una página que muestra ambas caras a tamaño real",
+    id_card_needs_two_pages: "Selecciona el anverso y luego el reverso de una tarjeta",
+    id_card_layout_changed_title: "Páginas de documento de identidad",
+    id_card_layout_changed_prompt: "{} página(s) de documento de identidad se compusieron para otro tamaño de papel u otros márgenes, así que no saldrán a tamaño real. Vuelva a ese tamaño de papel y esos márgenes para mantenerlas a tamaño real. ¿Guardar de todos modos?",
+    print_pages_hover: "Imprimir las páginas seleccionadas",
+    export_zip: "Exportar como ZIP",
+    export_zip_hover: "Guardar las páginas seleccionadas como imágenes {} en un archivo ZIP",
+    export_zip_title: "Exportar páginas como ZIP",
+    zip_files: "Archivos ZIP",
+    export_image_format: "Imágenes exportadas",
+    image_format_png: "PNG",
+    image_format_jpeg: "JPEG",
+    print_title: "Imprimir",
+    print_page_count: "{} página(s) para imprimir",
+    printer: "Impresora",
+    copies: "Copias",
+    print: "Imprimir",
+    error_printing: "Error al imprimir:",
+    error_no_printer: "No hay ninguna impresora configurada. Añada una en la configuración de impresoras del sistema y vuelva a intentarlo.",
+    image_smoothing: "Suavizado de imágenes",
+    image_smoothing_hover: "Si los visores de PDF suavizan las imágenes de las páginas al ampliarlas. El suavizado mejora las fotos pero difumina el texto y los dibujos de líneas.",
+    pdf_compression: "Compresión de PDF:",
+    pdf_compression_hover: "Cuánto se comprimen las imágenes de las páginas al guardar. Una compresión mayor produce archivos más pequeños pero tarda más en guardar.",
+    compression_off: "Desactivada (más rápida, más grande)",
+    compression_fast: "Rápida",
+    compression_balanced: "Equilibrada",
+    compression_smallest: "Mínimo tamaño (más lenta)",
+    smoothing_color_only: "Solo páginas en color",
+    smoothing_always: "Todas las páginas",
+    smoothing_never: "Ninguna página",
+    while_scanning: "Durante el escaneo",
+    background_scanning: "Minimizar a la bandeja y avisar al terminar",
+    background_scanning_hover: "Minimizar la ventana y mostrar un icono en la bandeja del sistema mientras se escanea, y después mostrar una notificación de escritorio cuando el alimentador se vacíe o se produzca un error. Haga clic en el icono para recuperar la ventana; también vuelve al terminar el escaneo. En escritorios sin bandeja, la ventana se minimiza a la barra de tareas.",
+    notify_scan_finished: "Escaneo terminado",
+    notify_scan_pages: "{} página(s) escaneada(s)",
+    notify_scan_error: "Error de escaneo",
+    preview_layout: "Vista previa del diseño",
+    preview_layout_hover: "Mostrar cómo se colocarán las páginas seleccionadas en las páginas del PDF, sin guardar",
+    preview_layout_title: "Vista previa del diseño del PDF",
+    preview_layout_note: "Las páginas se muestran en el orden de salida con los saltos de página, la división y la marca de agua actuales. La corrección de curvatura solo se aplica al guardar.",
+    overwrite_prompt_choice: "Preguntar siempre",
+    overwrite_always: "Sobrescribir siempre",
+    overwrite_never: "No sobrescribir nunca (añadir un número)",
+    selection_highlight: "Resaltado de selección:",
+    strength: "intensidad",
+    page_data: "Datos de página:",
+    spill_pages: "Guardar los datos de página a resolución completa en disco",
+    spill_pages_hover: "Reduce el uso de memoria en lotes grandes. Los datos se leen de un archivo temporal al guardar.",
+    scanning: "Escaneo:",
+    warn_low_resolution: "Avisar una vez por sesión al escanear por debajo de",
+    warn_low_resolution_hover: "El reconocimiento de texto (OCR) funciona mal en escaneos por debajo de unos 300 PPP",
+    warn_large_scans: "Avisar cuando cada página ocuparía más de",
+    warn_large_scans_hover: "Estimado a partir del área de escaneo, la resolución, el modo y la profundidad de bits. Algunos escáneres aceptan resoluciones muy por encima de lo que realmente pueden ofrecer, produciendo archivos enormes lentamente.",
+    device_settings: "Ajustes del escáner",
+    remember_device_settings: "Recordar la resolución y el origen de cada escáner",
+    remember_device_settings_hover: "La resolución y el origen usados en el último escaneo de un escáner se vuelven a aplicar al abrirlo de nuevo",
+    virtual_scanner: "Escáner virtual",
+    test_pattern_scanner: "Mostrar un escáner de patrón de prueba",
+    test_pattern_scanner_hover: "Añade a la lista de dispositivos un escáner que no necesita hardware y escanea páginas de prueba generadas, para probar la aplicación",
+    preferred_color_mode: "Modo de color preferido:",
+    device_default_mode: "Predeterminado del dispositivo",
+    preferred_color_mode_hover: "Modo de color al que cambiar cada escáner al abrirlo, si el escáner lo ofrece",
+    default_output: "Salida predeterminada:",
+    use_current_output: "Usar la actual",
+    default_output_hover: "Tamaño de papel, formato y resolución elegidos al iniciar la aplicación",
+    forget_device_settings: "Olvidar todo",
+    document_feeder: "Alimentador de documentos:",
+    feed_retries_suffix: " reintentos",
+    feed_retries_hover: "Si falla el inicio de la siguiente página, se reintenta este número de veces, esperando entre intentos, antes de considerar vacío el alimentador.",
+    after_scanning: "Después de escanear:",
+    auto_save_scans: "Guardar cada escaneo automáticamente",
+    after_saving: "Después de guardar:",
+    auto_copy_path: "Copiar la ruta del archivo guardado al portapapeles",
+    keep_pages_after_save: "Conservar las páginas tras guardar",
+    keep_pages_after_save_hover: "Dejar a la vista las páginas guardadas, deseleccionadas, para que puedan guardarse de nuevo en otros archivos. Siguen contando como guardadas.",
+    file_name_field: "Campo de nombre de archivo:",
+    absolute_file_names: "Guardar en rutas absolutas escritas como nombre de archivo",
+    absolute_file_names_hover: "Un nombre de archivo que empieza en la raíz del sistema de archivos se usa tal cual, sin tener en cuenta la ubicación de guardado",
+    watermark: "Marca de agua:",
+    watermark_enabled: "Estampar la hora del escaneo en las páginas guardadas",
+    watermark_text_hint: "Texto adicional",
+    watermark_hover: "Añade la fecha y hora del escaneo (UTC) y un texto opcional en una esquina de cada página del PDF. Se omiten los caracteres fuera de los alfabetos de Europa occidental.",
+    page_margins: "Márgenes de página",
+    page_margins_hover: "Espacio en blanco alrededor de la imagen en cada lado de las páginas guardadas e impresas, para que nada quede en el borde no imprimible de la impresora. Con márgenes, las páginas mantienen sus proporciones y se centran entre ellos.",
+    id_card_size: "Tamaño de documento:",
+    id_card_size_hover: "Ancho y alto de las tarjetas en las páginas de documento. El valor predeterminado es el tamaño de las tarjetas bancarias y la mayoría de los documentos de identidad.",
+    margin_top: "Superior",
+    margin_right: "Derecho",
+    margin_bottom: "Inferior",
+    margin_left: "Izquierdo",
+    opacity: "opacidad",
+    corner_top_left: "Arriba a la izquierda",
+    corner_top_right: "Arriba a la derecha",
+    corner_bottom_left: "Abajo a la izquierda",
+    corner_bottom_right: "Abajo a la derecha",
+    accessibility: "Accesibilidad:",
+    text_markers: "Indicar el estado de las opciones con texto",
+    text_markers_hover: "Añade etiquetas como [inactiva] y [rango] a las opciones del escáner para que su estado no dependa solo del color.",
+
+    about_title: "Acerca de / Diagnóstico",
+    statistics_title: "Estadísticas de escaneo",
+    profiles_title: "Perfiles de escaneo",
+    profile_device_options: "{} opciones del escáner",
+    apply_profile: "Aplicar",
+    delete_profile: "Eliminar",
+    profile_name_hint: "Nombre del perfil",
+    save_profile: "Guardar ajustes actuales",
+    save_profile_hover: "Guardar las opciones del escáner abierto junto con los ajustes de salida (tamaño de papel, resolución, formato, ajustes de imagen, compresión y formato de exportación), reemplazando cualquier perfil con el mismo nombre",
+    pages_scanned: "Páginas escaneadas:",
+    pages_saved: "Páginas guardadas:",
+    average_page_time: "Tiempo medio por página:",
+    app_version: "Versión de SlickScan:",
+    sane_version: "Versión de SANE:",
+    configured_backends: "Backends configurados:",
+    backends_unavailable: "(no se encontró dll.conf)",
+    device_name: "Nombre del dispositivo:",
+    device_vendor: "Fabricante:",
+    device_model: "Modelo:",
+    device_type: "Tipo:",
+    device_backend: "Backend:",
+    device_opened: "Abierto:",
+    yes: "Sí",
+    no: "No",
+    copy_diagnostics: "Copiar diagnóstico",
+
+    common_values_title: "Valores comunes",
+    units: "Unidades:",
+    copy: "Copiar",
+};
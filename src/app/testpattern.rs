@@ -0,0 +1,266 @@
+use sane_scan::{Device, DeviceOption, DeviceOptionValue, Frame, OptionCapability, OptionConstraint, Parameters, Status, ValueType, ValueUnit};
+
+use super::{image::PageFormat, scanner::{ScanDevice, MODE_OPTION_NAME, RESOLUTION_OPTION_NAME}};
+use crate::{util::{cstring_to_string, string_to_cstring}, LETTER_HEIGHT_MM, LETTER_WIDTH_MM, MM_PER_INCH};
+
+/// Listed name of the built-in test pattern scanner. SANE names start with their backend, so this can't clash with a real device.
+const TEST_PATTERN_DEVICE_NAME: &str = "slickscan:test-pattern";
+
+const PAGE_COUNT_OPTION_NAME: &str = "page-count";
+
+const MODE_CHOICES: [&str; 3] = ["Lineart", "Gray", "Color"];
+const RESOLUTION_CHOICES: [i32; 3] = [75, 150, 300];
+const MAX_PAGE_COUNT: usize = 50;
+
+/// Colors of the bars across the top of each page
+const BAR_COLORS: [[u8; 3]; 8] = [
+    [255, 255, 255], [255, 255, 0], [0, 255, 255], [0, 255, 0],
+    [255, 0, 255], [255, 0, 0], [0, 0, 255], [0, 0, 0],
+];
+
+pub fn test_pattern_device() -> Device {
+    Device {
+        name: string_to_cstring(String::from(TEST_PATTERN_DEVICE_NAME)),
+        vendor: string_to_cstring(String::from("SlickScan")),
+        model: string_to_cstring(String::from("Test pattern")),
+        type_: string_to_cstring(String::from("virtual device")),
+    }
+}
+
+pub fn is_test_pattern_device(device: &Device) -> bool {
+    cstring_to_string(&device.name, "device name") == TEST_PATTERN_DEVICE_NAME
+}
+
+/// A scanner that needs no hardware, feeding batches of generated letter-size pages.
+/// Every batch is the same, so its pages can be told apart by the number of marks along their bottom edge.
+pub struct TestPatternDevice {
+    options: Vec<(DeviceOption, DeviceOptionValue)>,
+    /// Pages left in the batch being fed, or `None` between batches
+    remaining: Option<i32>,
+    /// Parameters and unread data of the page being fed
+    current: Option<(Parameters, Vec<u8>)>,
+    read_position: usize,
+    cancelled: bool,
+}
+
+impl Default for TestPatternDevice {
+    fn default() -> Self {
+        #[allow(clippy::cast_possible_truncation)]
+        #[allow(clippy::cast_possible_wrap)]
+        let max_page_count = MAX_PAGE_COUNT as i32;
+        let options = vec![
+            (
+                option(1, MODE_OPTION_NAME, "Scan mode", ValueType::String, ValueUnit::None,
+                    OptionConstraint::StringList(MODE_CHOICES.iter().map(|mode| string_to_cstring(String::from(*mode))).collect())),
+                DeviceOptionValue::String(string_to_cstring(String::from("Color"))),
+            ),
+            (
+                option(2, RESOLUTION_OPTION_NAME, "Scan resolution", ValueType::Int, ValueUnit::Dpi,
+                    OptionConstraint::WordList(RESOLUTION_CHOICES.to_vec())),
+                DeviceOptionValue::Int(150),
+            ),
+            (
+                option(3, PAGE_COUNT_OPTION_NAME, "Pages per scan", ValueType::Int, ValueUnit::None,
+                    OptionConstraint::Range { range: 1..max_page_count, quant: 1 }),
+                DeviceOptionValue::Int(3),
+            ),
+        ];
+
+        Self { options, remaining: None, current: None, read_position: 0, cancelled: false }
+    }
+}
+
+fn option(option_idx: i32, name: &str, title: &str, type_: ValueType, unit: ValueUnit, constraint: OptionConstraint) -> DeviceOption {
+    let size = match &constraint {
+        OptionConstraint::StringList(list) => list.iter().map(|choice| choice.as_bytes_with_nul().len()).max().unwrap_or(1),
+        _ => std::mem::size_of::<i32>(),
+    };
+
+    DeviceOption {
+        option_idx,
+        name: string_to_cstring(String::from(name)),
+        title: string_to_cstring(String::from(title)),
+        desc: string_to_cstring(String::from(title)),
+        type_,
+        unit,
+        size: i32::try_from(size).unwrap_or(i32::MAX),
+        cap: OptionCapability::SOFT_SELECT | OptionCapability::SOFT_DETECT,
+        constraint,
+    }
+}
+
+/// Whether `value` is one the option's constraint accepts. Range ends are inclusive, as in SANE.
+fn allows(option: &DeviceOption, value: &DeviceOptionValue) -> bool {
+    match (&option.constraint, value) {
+        (OptionConstraint::StringList(list), DeviceOptionValue::String(value)) => list.contains(value),
+        (OptionConstraint::WordList(list), DeviceOptionValue::Int(value)) => list.contains(value),
+        (OptionConstraint::Range { range, .. }, DeviceOptionValue::Int(value)) => (range.start..=range.end).contains(value),
+        _ => false,
+    }
+}
+
+impl TestPatternDevice {
+    fn value(&self, name: &str) -> Option<&DeviceOptionValue> {
+        self.options.iter()
+            .find(|(option, _)| cstring_to_string(&option.name, "option name") == name)
+            .map(|(_, value)| value)
+    }
+
+    fn format(&self) -> PageFormat {
+        match self.value(MODE_OPTION_NAME) {
+            Some(DeviceOptionValue::String(mode)) if mode.as_bytes() == b"Lineart" => PageFormat::Lineart,
+            Some(DeviceOptionValue::String(mode)) if mode.as_bytes() == b"Gray" => PageFormat::Gray,
+            _ => PageFormat::Color,
+        }
+    }
+
+    fn int_value(&self, name: &str) -> i32 {
+        match self.value(name) {
+            Some(DeviceOptionValue::Int(value)) => *value,
+            _ => 1,
+        }
+    }
+
+    /// Parameters of a page scanned with the current options
+    fn parameters(&self) -> Parameters {
+        #[allow(clippy::cast_precision_loss)]
+        let dpi = self.int_value(RESOLUTION_OPTION_NAME) as f32;
+        #[allow(clippy::cast_possible_truncation)]
+        let pixels_per_line = (LETTER_WIDTH_MM / MM_PER_INCH * dpi).round() as i32;
+        #[allow(clippy::cast_possible_truncation)]
+        let lines = (LETTER_HEIGHT_MM / MM_PER_INCH * dpi).round() as i32;
+
+        let (format, depth, bytes_per_line) = match self.format() {
+            PageFormat::Lineart => (Frame::Gray, 1, (pixels_per_line + 7) / 8),
+            PageFormat::Gray    => (Frame::Gray, 8, pixels_per_line),
+            PageFormat::Color   => (Frame::Rgb, 8, pixels_per_line * 3),
+        };
+
+        Parameters { format, last_frame: true, bytes_per_line, pixels_per_line, lines, depth }
+    }
+}
+
+/// Color of the test pattern at `(x, y)` on page `page` of a batch, for a page of `width` by `height` pixels
+fn pattern_color(x: usize, y: usize, width: usize, height: usize, page: usize) -> [u8; 3] {
+    let band = height / 8;
+    let square = (width / 17).max(1);
+    // Room for a mark for every page of the largest batch, with gaps between them
+    let mark_width = (width / (MAX_PAGE_COUNT * 2 + 1)).max(1);
+
+    if y < band {
+        // Color bars
+        BAR_COLORS[x * BAR_COLORS.len() / width]
+    } else if y < band * 2 {
+        // Gray ramp from black to white
+        #[allow(clippy::cast_possible_truncation)]
+        let level = (x * 255 / width.max(2).saturating_sub(1)).min(255) as u8;
+        [level; 3]
+    } else if y >= height - band {
+        // One mark per page number, to tell the pages of a batch apart
+        let mark = x / mark_width;
+        if mark % 2 == 1 && mark / 2 < page + 1 && y < height - band / 2 { [0; 3] } else { [255; 3] }
+    } else if (x / square + y / square).is_multiple_of(2) {
+        [255; 3]
+    } else {
+        [192; 3]
+    }
+}
+
+/// Generates the data the device sends for page `page` of a batch, in the format described by `parameters`
+fn render_page(parameters: &Parameters, page: usize) -> Vec<u8> {
+    let width = usize::try_from(parameters.pixels_per_line).unwrap_or_default();
+    let height = usize::try_from(parameters.lines).unwrap_or_default();
+    let bytes_per_line = usize::try_from(parameters.bytes_per_line).unwrap_or_default();
+
+    let mut data = vec![0; bytes_per_line * height];
+    for (y, line) in data.chunks_exact_mut(bytes_per_line.max(1)).enumerate() {
+        for x in 0..width {
+            let [red, green, blue] = pattern_color(x, y, width, height, page);
+            #[allow(clippy::cast_possible_truncation)]
+            let luminance = ((u32::from(red) * 299 + u32::from(green) * 587 + u32::from(blue) * 114) / 1000) as u8;
+            match (parameters.format, parameters.depth) {
+                // A set bit is black in lineart
+                (Frame::Gray, 1) => if luminance < 128 { line[x / 8] |= 0x80 >> (x % 8) },
+                (Frame::Gray, _) => line[x] = luminance,
+                _ => line[x * 3..x * 3 + 3].copy_from_slice(&[red, green, blue]),
+            }
+        }
+    }
+
+    data
+}
+
+impl ScanDevice for TestPatternDevice {
+    fn get_options(&mut self) -> Result<Vec<DeviceOption>, sane_scan::Error> {
+        Ok(self.options.iter().map(|(option, _)| option.clone()).collect())
+    }
+
+    fn get_option(&mut self, option: &DeviceOption) -> Result<DeviceOptionValue, sane_scan::Error> {
+        self.options.iter()
+            .find(|(candidate, _)| candidate.option_idx == option.option_idx)
+            .map(|(_, value)| value.clone())
+            .ok_or(sane_scan::Error(Status::Inval))
+    }
+
+    fn set_option(&mut self, option: &DeviceOption, value: DeviceOptionValue) -> Result<(), sane_scan::Error> {
+        let (option, current) = self.options.iter_mut()
+            .find(|(candidate, _)| candidate.option_idx == option.option_idx)
+            .ok_or(sane_scan::Error(Status::Inval))?;
+        if !allows(option, &value) {
+            return Err(sane_scan::Error(Status::Inval));
+        }
+
+        *current = value;
+        Ok(())
+    }
+
+    fn set_option_auto(&mut self, _option: &DeviceOption) -> Result<(), sane_scan::Error> {
+        Err(sane_scan::Error(Status::Unsupported))
+    }
+
+    fn start_scan(&mut self) -> Result<(), sane_scan::Error> {
+        self.cancelled = false;
+        let page_count = self.int_value(PAGE_COUNT_OPTION_NAME);
+        let remaining = self.remaining.unwrap_or(page_count);
+
+        // Like an emptied feeder, the end of a batch is reported once, and the next scan starts a new batch
+        if remaining == 0 {
+            self.remaining = None;
+            self.current = None;
+            return Err(sane_scan::Error(Status::NoDocs));
+        }
+
+        let parameters = self.parameters();
+        let page = usize::try_from(page_count - remaining).unwrap_or_default();
+        let data = render_page(&parameters, page);
+        self.current = Some((parameters, data));
+        self.read_position = 0;
+        self.remaining = Some(remaining - 1);
+
+        Ok(())
+    }
+
+    fn get_parameters(&mut self) -> Result<Parameters, sane_scan::Error> {
+        Ok(self.current.as_ref().map_or_else(|| self.parameters(), |(parameters, _)| parameters.clone()))
+    }
+
+    fn read(&mut self, buffer: &mut [u8]) -> Result<Option<usize>, sane_scan::Error> {
+        if self.cancelled {
+            return Err(sane_scan::Error(Status::Cancelled));
+        }
+        let (_, data) = self.current.as_ref().ok_or(sane_scan::Error(Status::Inval))?;
+        if self.read_position == data.len() {
+            return Ok(None);
+        }
+
+        let length = buffer.len().min(data.len() - self.read_position);
+        buffer[..length].copy_from_slice(&data[self.read_position..self.read_position + length]);
+        self.read_position += length;
+        Ok(Some(length))
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+        self.remaining = None;
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use eframe::egui::{self, Context};
+use zbus::{blocking::{Connection, ConnectionBuilder}, dbus_interface};
+
+/// Counts the icons shown, since each one needs its own bus name
+static ICONS_SHOWN: AtomicU32 = AtomicU32::new(0);
+
+/// A system tray icon, shown through the StatusNotifierItem D-Bus protocol. Activating it brings the window back.
+/// Dropping it closes its connection, which takes the icon out of the tray.
+pub struct TrayIcon {
+    _connection: Connection,
+}
+
+impl TrayIcon {
+    /// Fails if there's no session bus, or the desktop has no tray to register the icon with
+    pub fn show(ctx: Context) -> zbus::Result<Self> {
+        let name = format!("org.kde.StatusNotifierItem-{}-{}", std::process::id(), ICONS_SHOWN.fetch_add(1, Ordering::Relaxed) + 1);
+        let connection = ConnectionBuilder::session()?
+            .name(name.as_str())?
+            .serve_at("/StatusNotifierItem", StatusNotifierItem { ctx })?
+            .build()?;
+
+        connection.call_method(Some("org.kde.StatusNotifierWatcher"), "/StatusNotifierWatcher",
+            Some("org.kde.StatusNotifierWatcher"), "RegisterStatusNotifierItem", &(name.as_str(),))?;
+
+        Ok(Self { _connection: connection })
+    }
+}
+
+/// Unminimizes the window and brings it to the front
+pub fn restore_window(ctx: &Context) {
+    ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+    ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+}
+
+struct StatusNotifierItem {
+    ctx: Context,
+}
+
+#[dbus_interface(name = "org.kde.StatusNotifierItem")]
+impl StatusNotifierItem {
+    fn activate(&self, _x: i32, _y: i32) {
+        restore_window(&self.ctx);
+    }
+
+    fn secondary_activate(&self, _x: i32, _y: i32) {
+        restore_window(&self.ctx);
+    }
+
+    #[dbus_interface(property)]
+    fn category(&self) -> &str {
+        "ApplicationStatus"
+    }
+
+    #[dbus_interface(property)]
+    fn id(&self) -> &str {
+        "slickscan"
+    }
+
+    #[dbus_interface(property)]
+    fn title(&self) -> &str {
+        "SlickScan"
+    }
+
+    #[dbus_interface(property)]
+    fn status(&self) -> &str {
+        "Active"
+    }
+
+    #[dbus_interface(property)]
+    fn icon_name(&self) -> &str {
+        "scanner"
+    }
+
+    /// Clicking the icon activates it rather than opening a menu
+    #[dbus_interface(property)]
+    fn item_is_menu(&self) -> bool {
+        false
+    }
+}
@@ -1,7 +1,37 @@
+use crate::MM_PER_INCH;
+
 pub struct CommonValue {
     pub name: &'static str,
     pub description: &'static str,
-    pub value: &'static str,
+    pub value_mm: f64,
+}
+
+impl CommonValue {
+    pub fn value_in(&self, unit: LengthUnit) -> String {
+        let value = match unit {
+            LengthUnit::Millimeters => self.value_mm,
+            LengthUnit::Inches      => self.value_mm / f64::from(MM_PER_INCH),
+        };
+
+        // Limit precision, then drop insignificant trailing zeros
+        let formatted = format!("{value:.4}");
+        formatted.trim_end_matches('0').trim_end_matches('.').to_owned()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum LengthUnit {
+    Millimeters,
+    Inches,
+}
+
+impl LengthUnit {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Millimeters   => "mm",
+            Self::Inches        => "in",
+        }
+    }
 }
 
 pub enum ValueCategory {
@@ -21,28 +51,28 @@ impl ValueCategory {
         match self {
             ValueCategory::LetterUS => vec![
                 CommonValue {
-                    name: "Width mm",
-                    description: "Width of Letter (US) paper in millimeters",
-                    value: "215.9",
+                    name: "Width",
+                    description: "Width of Letter (US) paper",
+                    value_mm: 215.9,
                 },
                 CommonValue {
-                    name: "Height mm",
-                    description: "Height of Letter (US) paper in millimeters",
-                    value: "279.4",
+                    name: "Height",
+                    description: "Height of Letter (US) paper",
+                    value_mm: 279.4,
                 }
             ],
             ValueCategory::A4 => vec![
                 CommonValue {
-                    name: "Width mm",
-                    description: "Width of A4 (ISO 216) paper in millimeters",
-                    value: "210",
+                    name: "Width",
+                    description: "Width of A4 (ISO 216) paper",
+                    value_mm: 210.0,
                 },
                 CommonValue {
-                    name: "Height mm",
-                    description: "Height of A4 (ISO 216) paper in millimeters",
-                    value: "297",
+                    name: "Height",
+                    description: "Height of A4 (ISO 216) paper",
+                    value_mm: 297.0,
                 }
             ],
         }
     }
-}
\ No newline at end of file
+}
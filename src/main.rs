@@ -10,8 +10,14 @@ const DEFAULT_FILE_NAME: &str = "scan.pdf";
 const ERR_DIALOG_TITLE: &str = "SlickScan Error";
 const LETTER_WIDTH_MM: f32 = 215.9;
 const LETTER_HEIGHT_MM: f32 = 279.4;
-const LETTER_WIDTH_IN: f32 = 8.5;
-const LETTER_HEIGHT_IN: f32 = 11.0;
+const LEGAL_HEIGHT_MM: f32 = 355.6;
+const A4_WIDTH_MM: f32 = 210.0;
+const A4_HEIGHT_MM: f32 = 297.0;
+const MM_PER_INCH: f32 = 25.4;
+const SCAN_DPI: f32 = 300.0;
+/// Size of ID-1 cards, such as bank cards and most identity cards
+const ID1_CARD_WIDTH_MM: f32 = 85.6;
+const ID1_CARD_HEIGHT_MM: f32 = 53.98;
 
 fn main() {
     env_logger::init();
@@ -1,4 +1,6 @@
-use std::ffi::CString;
+use std::{ffi::CString, path::{Component, Path, PathBuf}, time::{SystemTime, UNIX_EPOCH}};
+
+use printpdf::OffsetDateTime;
 
 pub fn cstring_to_string(cstring: &CString, data_type: &str) -> String {
     cstring.clone().into_string().unwrap_or(format!("Error reading {data_type}!"))
@@ -8,8 +10,61 @@ pub fn string_to_cstring(string: String) -> CString {
     CString::new(string).unwrap_or_default()
 }
 
+/// Returns `path` if nothing exists there, otherwise the first free variant with a numeric suffix (`name-1.ext`, `name-2.ext`, ...)
+pub fn next_free_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+
+    (1..).map(|n| with_index_suffix(path, n))
+        .find(|candidate| !candidate.exists())
+        .expect("Ran out of numeric suffixes")
+}
+
+/// `name.ext` becomes `name-{index}.ext`
+pub fn with_index_suffix(path: &Path, index: usize) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().map(|ext| format!(".{}", ext.to_string_lossy())).unwrap_or_default();
+
+    path.with_file_name(format!("{stem}-{index}{extension}"))
+}
+
+/// Joins `relative` onto `root` with `.` and `..` resolved, or `None` if the result would leave `root`
+pub fn join_within(root: &Path, relative: &Path) -> Option<PathBuf> {
+    let mut components = Vec::new();
+    for component in relative.components() {
+        match component {
+            Component::Normal(name) => components.push(name),
+            Component::CurDir => (),
+            Component::ParentDir => { components.pop()?; },
+            // Joining an absolute path would replace the root entirely
+            Component::RootDir | Component::Prefix(_) => return None,
+        }
+    }
+
+    Some(components.into_iter().fold(root.to_path_buf(), |path, name| path.join(name)))
+}
+
+pub fn format_byte_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    #[allow(clippy::cast_precision_loss)]
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{bytes} {}", UNITS[0])
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
 pub fn repeat_all_elements<T: Clone>(ts: Vec<T>, repeated: usize) -> Vec<T> {
-    let mut result = Vec::new();
+    let mut result = Vec::with_capacity(ts.len() * repeated);
     for e in ts {
         for _ in 0..repeated {
             result.push(e.clone());
@@ -19,49 +74,419 @@ pub fn repeat_all_elements<T: Clone>(ts: Vec<T>, repeated: usize) -> Vec<T> {
     result
 }
 
-pub fn insert_after_every<T: Clone>(ts: Vec<T>, after: usize, elem: T) -> Vec<T> {
-    let mut result = Vec::new();
-    for (i, e) in ts.into_iter().enumerate() {
-        result.push(e);
-        if (i + 1) % after == 0 {
-            result.push(elem.clone());
+/// `time` as `YYYY-MM-DD HH:MM UTC`
+pub fn format_utc_timestamp(time: SystemTime) -> String {
+    let seconds = time.duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+    let time = i64::try_from(seconds).ok()
+        .and_then(|seconds| OffsetDateTime::from_unix_timestamp(seconds).ok())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH);
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", time.year(), u8::from(time.month()), time.day(), time.hour(), time.minute())
+}
+
+/// Bits of `bytes`, most significant first, as in SANE's 1-bit frames
+pub fn unpack_bits(bytes: &[u8]) -> impl Iterator<Item = bool> + '_ {
+    bytes.iter().flat_map(|byte| (0..8).rev().map(move |bit| byte & (1 << bit) != 0))
+}
+
+/// CRC-32 checksum of `bytes`, as used in ZIP archives and PNG images
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = u32::MAX;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
         }
     }
 
-    result
+    !crc
 }
 
-pub fn sane_fixed_to_float(fixed: i32) -> f64 {
-    if fixed == i32::MIN {
-        return -32768.0;
+/// Packs 8-bit RGB pixels into 1-bit gray rows padded to whole bytes, with a set bit for white as in PDF.
+/// `None` if any pixel isn't pure black or white.
+pub fn pack_bilevel(pixels: &[u8], size: [usize; 2]) -> Option<Vec<u8>> {
+    let row_bytes = size[0].div_ceil(8);
+    let mut packed = vec![0; row_bytes * size[1]];
+    for (i, pixel) in pixels.chunks_exact(3).enumerate() {
+        let (row, column) = (i / size[0], i % size[0]);
+        match pixel {
+            [0, 0, 0] => {},
+            [255, 255, 255] => packed[row * row_bytes + column / 8] |= 0x80 >> (column % 8),
+            _ => return None,
+        }
+    }
+
+    Some(packed)
+}
+
+/// Whether every 8-bit RGB pixel is a shade of gray, as in scans made in a gray or lineart mode
+pub fn is_grayscale(pixels: &[u8]) -> bool {
+    pixels.chunks_exact(3).all(|pixel| pixel[0] == pixel[1] && pixel[1] == pixel[2])
+}
+
+/// Whether 8-bit RGB pixels look like an empty sheet: dark pixels, such as text or scanner noise, make up at most `max_ink_fraction` of them
+pub fn is_blank_page(pixels: &[u8], max_ink_fraction: f32) -> bool {
+    let pixel_count = pixels.len() / 3;
+    let ink = pixels.chunks_exact(3).filter(|pixel| pixel.iter().any(|&channel| channel < BLANK_PAGE_INK_LEVEL)).count();
+    #[allow(clippy::cast_precision_loss)]
+    let blank = pixel_count == 0 || ink as f32 / pixel_count as f32 <= max_ink_fraction;
+    blank
+}
+
+/// Channel value below which a pixel counts as ink when looking for blank pages
+const BLANK_PAGE_INK_LEVEL: u8 = 160;
+
+/// Channel value below which a pixel stands out from a white background, such as a scanner's lid
+const CONTENT_LEVEL: u8 = 235;
+/// Fraction of a row or column's pixels that must stand out for it to count as content, so specks of dust don't
+const CONTENT_MIN_FRACTION: f32 = 0.02;
+
+/// Bounds of what was placed on the scanner within a page of 8-bit RGB pixels, as `[left, top, right, bottom]` with exclusive ends.
+/// `None` if nothing stands out from the background.
+pub fn content_bounds(pixels: &[u8], size: [usize; 2]) -> Option<[usize; 4]> {
+    let stands_out = |pixel: &[u8]| pixel.iter().any(|&channel| channel < CONTENT_LEVEL);
+    let mut row_counts = vec![0; size[1]];
+    let mut column_counts = vec![0; size[0]];
+    for (i, _) in pixels.chunks_exact(3).enumerate().filter(|(_, pixel)| stands_out(pixel)) {
+        row_counts[i / size[0]] += 1;
+        column_counts[i % size[0]] += 1;
     }
-    
-    let mut c = fixed.abs();
-    let mut sign = 1;
 
-    if fixed < 0 {
-        c = fixed - 1;
-        c = !c;
-        sign = -1;
+    #[allow(clippy::cast_precision_loss)]
+    let span = |counts: &[usize], length: usize| {
+        let minimum = length as f32 * CONTENT_MIN_FRACTION;
+        let first = counts.iter().position(|&count| count as f32 > minimum)?;
+        let last = counts.iter().rposition(|&count| count as f32 > minimum)?;
+        Some((first, last + 1))
+    };
+    let (left, right) = span(&column_counts, size[1])?;
+    let (top, bottom) = span(&row_counts, size[0])?;
+
+    Some([left, top, right, bottom])
+}
+
+/// Groups `items` into runs separated by separator items, which are dropped along with empty runs
+pub fn split_at_separators<T>(items: impl IntoIterator<Item = T>, mut is_separator: impl FnMut(&T) -> bool) -> Vec<Vec<T>> {
+    let mut groups = vec![Vec::new()];
+    for item in items {
+        if is_separator(&item) {
+            groups.push(Vec::new());
+        } else if let Some(group) = groups.last_mut() {
+            group.push(item);
+        }
     }
+    groups.retain(|group| !group.is_empty());
 
-    ((1.0 * f64::from(c)) / f64::from(2i32.pow(16))) * f64::from(sign)
+    groups
 }
 
-pub fn float_to_sane_fixed(float: f64) -> i32 {
-    if float <= -32768.0 {
-        return i32::MIN;
+/// Inserts `elem` after every complete group of `after` elements. A trailing partial group gets no insertion.
+pub fn insert_after_every<T: Clone>(ts: &[T], after: usize, elem: T) -> Vec<T> {
+    if after == 0 {
+        return ts.to_vec();
     }
 
-    let a = float * f64::from(2i32.pow(16));
+    let groups = ts.chunks_exact(after);
+    let remainder = groups.remainder();
+
+    let mut result = Vec::with_capacity(ts.len() + ts.len() / after);
+    for group in groups {
+        result.extend_from_slice(group);
+        result.push(elem.clone());
+    }
+    result.extend_from_slice(remainder);
+
+    result
+}
+
+/// Scale of SANE's 16.16 two's complement fixed-point representation (`1 << SANE_FIXED_SCALE_SHIFT`)
+const SANE_FIXED_SCALE: f64 = 65536.0;
+
+pub fn sane_fixed_to_float(fixed: i32) -> f64 {
+    // Equivalent to SANE_UNFIX: the fixed value is a plain two's complement integer scaled by 2^16
+    f64::from(fixed) / SANE_FIXED_SCALE
+}
+
+pub fn float_to_sane_fixed(float: f64) -> i32 {
+    // Equivalent to SANE_FIX, saturating at the limits of the representable range
+    let scaled = (float * SANE_FIXED_SCALE).round();
     #[allow(clippy::cast_possible_truncation)]
-    let mut b = a.round() as i32;
+    let fixed = scaled.clamp(f64::from(i32::MIN), f64::from(i32::MAX)) as i32;
+    fixed
+}
+
+/// Formats a version code returned by `sane_init` as `major.minor.build`
+pub fn sane_version_string(version_code: i32) -> String {
+    // Same layout as the SANE_VERSION_MAJOR/MINOR/BUILD macros
+    let major = (version_code >> 24) & 0xff;
+    let minor = (version_code >> 16) & 0xff;
+    let build = version_code & 0xffff;
+    format!("{major}.{minor}.{build}")
+}
+
+/// Backend names enabled in a SANE `dll.conf`, skipping comments and blank lines
+pub fn parse_backend_list(contents: &str) -> Vec<String> {
+    contents.lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
 
-    if a < 0.0 {
-        b = b.abs();
-        b = !b;
-        b += 1;
+/// Printer names from the output of `lpstat -a`, where each line starts with a printer's name
+pub fn parse_printer_list(output: &str) -> Vec<String> {
+    output.lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Default printer name from the output of `lpstat -d`, which is "system default destination: NAME" when one is set
+pub fn parse_default_printer(output: &str) -> Option<String> {
+    output.lines()
+        .find_map(|line| line.split_once("destination:"))
+        .map(|(_, name)| name.trim().to_owned())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeat_all_elements_empty_input() {
+        assert!(repeat_all_elements(Vec::<u8>::new(), 3).is_empty());
+        assert!(repeat_all_elements(vec![1u8, 2], 0).is_empty());
+    }
+
+    #[test]
+    fn repeat_all_elements_expands_grayscale_to_rgb() {
+        assert_eq!(repeat_all_elements(vec![10u8, 20], 3), vec![10, 10, 10, 20, 20, 20]);
+        assert_eq!(repeat_all_elements(vec![7u8], 1), vec![7]);
     }
 
-    b
-}
\ No newline at end of file
+    #[test]
+    fn crc32_matches_the_standard_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(&[]), 0);
+    }
+
+    #[test]
+    fn content_bounds_ignore_background_and_specks() {
+        // A 40x20 card in the top-left of a 100x100 white page, and one speck of dust
+        let mut pixels = vec![255; 100 * 100 * 3];
+        for (x, y) in (0..40).flat_map(|x| (0..20).map(move |y| (x, y))).chain([(80, 80)]) {
+            pixels[(y * 100 + x) * 3..(y * 100 + x) * 3 + 3].copy_from_slice(&[100, 120, 140]);
+        }
+
+        assert_eq!(content_bounds(&pixels, [100, 100]), Some([0, 0, 40, 20]));
+        assert_eq!(content_bounds(&[255; 4 * 3], [2, 2]), None);
+    }
+
+    #[test]
+    fn insert_after_every_empty_input() {
+        assert!(insert_after_every::<u8>(&[], 3, 255).is_empty());
+    }
+
+    #[test]
+    fn insert_after_every_expands_rgb_to_rgba() {
+        assert_eq!(insert_after_every(&[1u8, 2, 3, 4, 5, 6], 3, 255), vec![1, 2, 3, 255, 4, 5, 6, 255]);
+    }
+
+    #[test]
+    fn insert_after_every_skips_trailing_partial_group() {
+        assert_eq!(insert_after_every(&[1u8, 2, 3, 4, 5], 3, 0), vec![1, 2, 3, 0, 4, 5]);
+        assert_eq!(insert_after_every(&[1u8, 2], 3, 0), vec![1, 2]);
+    }
+
+    #[test]
+    fn insert_after_every_zero_group_is_unchanged() {
+        assert_eq!(insert_after_every(&[1u8, 2, 3], 0, 0), vec![1, 2, 3]);
+    }
+
+    /// Run with `cargo test --release -- --ignored --nocapture rgba_conversion_benchmark`
+    #[test]
+    #[ignore = "benchmark"]
+    fn rgba_conversion_benchmark() {
+        use std::{hint::black_box, time::Instant};
+
+        // Previous approach: clone the buffer, then push one element at a time
+        fn push_per_element(ts: Vec<u8>, after: usize, elem: u8) -> Vec<u8> {
+            let mut result = Vec::new();
+            for (i, e) in ts.into_iter().enumerate() {
+                result.push(e);
+                if (i + 1) % after == 0 {
+                    result.push(elem);
+                }
+            }
+            result
+        }
+
+        // Full US Letter page in color at 300 DPI
+        #[allow(clippy::cast_possible_truncation)]
+        let pixels: Vec<u8> = (0..2550 * 3300 * 3).map(|i| i as u8).collect();
+        let runs = 5;
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            black_box(push_per_element(black_box(pixels.clone()), 3, 255));
+        }
+        let previous = start.elapsed() / runs;
+
+        let start = Instant::now();
+        for _ in 0..runs {
+            black_box(insert_after_every(black_box(&pixels), 3, 255));
+        }
+        let current = start.elapsed() / runs;
+
+        assert_eq!(push_per_element(pixels.clone(), 3, 255), insert_after_every(&pixels, 3, 255));
+        println!("RGB to RGBA, 300 DPI Letter page: clone + push {previous:?}, preallocated chunks {current:?}");
+    }
+
+    #[test]
+    fn grayscale_scan_expands_to_rgba() {
+        let rgba = insert_after_every(&repeat_all_elements(vec![0u8, 128], 3), 3, 255);
+        assert_eq!(rgba, vec![0, 0, 0, 255, 128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn fixed_to_float_representative_values() {
+        assert_eq!(sane_fixed_to_float(0), 0.0);
+        assert_eq!(sane_fixed_to_float(1), 1.0 / 65536.0);
+        assert_eq!(sane_fixed_to_float(-1), -1.0 / 65536.0);
+        assert_eq!(sane_fixed_to_float(65536), 1.0);
+        assert_eq!(sane_fixed_to_float(-65536), -1.0);
+        assert_eq!(sane_fixed_to_float(-32768), -0.5);
+        assert!((sane_fixed_to_float(14_149_222) - 215.9).abs() < 1.0 / 65536.0);
+        assert_eq!(sane_fixed_to_float(i32::MAX), 32767.0 + 65535.0 / 65536.0);
+        assert_eq!(sane_fixed_to_float(i32::MIN), -32768.0);
+    }
+
+    #[test]
+    fn fixed_to_float_round_trips() {
+        for fixed in [0, 1, -1, 65536, -65536, -32768, 32768, i32::MIN, i32::MAX, 14_149_222, -14_149_222] {
+            assert_eq!(float_to_sane_fixed(sane_fixed_to_float(fixed)), fixed, "fixed value {fixed}");
+        }
+    }
+
+    #[test]
+    fn float_to_fixed_representative_values() {
+        assert_eq!(float_to_sane_fixed(0.0), 0);
+        assert_eq!(float_to_sane_fixed(1.0), 65536);
+        assert_eq!(float_to_sane_fixed(-1.0), -65536);
+        assert_eq!(float_to_sane_fixed(-0.5), -32768);
+        assert_eq!(float_to_sane_fixed(1.5), 98304);
+        assert_eq!(float_to_sane_fixed(-32768.0), i32::MIN);
+        assert_eq!(float_to_sane_fixed(215.9), 14_149_222);
+        assert_eq!(float_to_sane_fixed(-215.9), -14_149_222);
+    }
+
+    #[test]
+    fn float_to_fixed_saturates_out_of_range() {
+        assert_eq!(float_to_sane_fixed(40000.0), i32::MAX);
+        assert_eq!(float_to_sane_fixed(-40000.0), i32::MIN);
+    }
+
+    #[test]
+    fn fixed_round_trips_across_range() {
+        for fixed in (i32::MIN..=i32::MAX).step_by(9973).chain([i32::MAX]) {
+            assert_eq!(float_to_sane_fixed(sane_fixed_to_float(fixed)), fixed, "fixed value {fixed}");
+        }
+    }
+
+    #[test]
+    fn index_suffix_goes_before_extension() {
+        assert_eq!(with_index_suffix(Path::new("/out/scan.pdf"), 3), Path::new("/out/scan-3.pdf"));
+        assert_eq!(with_index_suffix(Path::new("notes"), 1), Path::new("notes-1"));
+    }
+
+    #[test]
+    fn sane_version_code_is_split_into_parts() {
+        assert_eq!(sane_version_string((1 << 24) | (2 << 16) | 1), "1.2.1");
+        assert_eq!(sane_version_string(0), "0.0.0");
+    }
+
+    #[test]
+    fn backend_list_skips_comments_and_blank_lines() {
+        let contents = "# Enabled backends\nnet\n\n#epson2\n  genesys  # USB flatbeds\nhpaio\n";
+        assert_eq!(parse_backend_list(contents), ["net", "genesys", "hpaio"]);
+    }
+
+    #[test]
+    fn printer_names_are_read_from_lpstat() {
+        let list = "Office_Laser accepting requests since Mon 12 Oct 2026 09:14:02 AM\nPhoto accepting requests since Tue 13 Oct 2026\n";
+        assert_eq!(parse_printer_list(list), ["Office_Laser", "Photo"]);
+        assert!(parse_printer_list("").is_empty());
+
+        assert_eq!(parse_default_printer("system default destination: Photo\n").as_deref(), Some("Photo"));
+        assert_eq!(parse_default_printer("no system default destination\n"), None);
+    }
+
+    #[test]
+    fn bits_unpack_most_significant_first() {
+        let bits: Vec<bool> = unpack_bits(&[0b1010_0000, 0b0000_0001]).collect();
+        assert_eq!(bits.len(), 16);
+        assert_eq!(&bits[..4], [true, false, true, false]);
+        assert!(bits[15]);
+    }
+
+    #[test]
+    fn bilevel_rows_are_padded_and_white_is_set() {
+        // 10 × 2 pixels: the first row is white, the second black except for its last pixel
+        let mut pixels = vec![255; 10 * 3];
+        pixels.extend(vec![0; 9 * 3]);
+        pixels.extend([255, 255, 255]);
+
+        assert_eq!(pack_bilevel(&pixels, [10, 2]).unwrap(), [0xff, 0xc0, 0x00, 0x40]);
+    }
+
+    #[test]
+    fn non_bilevel_pixels_are_not_packed() {
+        assert!(pack_bilevel(&[0, 0, 0, 128, 128, 128], [2, 1]).is_none());
+        assert!(pack_bilevel(&[255, 0, 0], [1, 1]).is_none());
+    }
+
+    #[test]
+    fn pages_with_little_ink_are_blank() {
+        let mut pixels = vec![250; 1000 * 3];
+        assert!(is_blank_page(&pixels, 0.005));
+
+        // A few specks of dust stay under the limit, a line of text doesn't
+        pixels[..3 * 3].fill(0);
+        assert!(is_blank_page(&pixels, 0.005));
+        pixels[..30 * 3].fill(0);
+        assert!(!is_blank_page(&pixels, 0.005));
+    }
+
+    #[test]
+    fn separators_split_and_are_dropped() {
+        let groups = split_at_separators([0, 1, 2, 0, 0, 3, 0], |n| *n == 0);
+        assert_eq!(groups, [vec![1, 2], vec![3]]);
+        assert!(split_at_separators([0, 0], |n| *n == 0).is_empty());
+    }
+
+    #[test]
+    fn grayscale_requires_equal_channels() {
+        assert!(is_grayscale(&[0, 0, 0, 128, 128, 128, 255, 255, 255]));
+        assert!(!is_grayscale(&[128, 128, 128, 128, 128, 129]));
+    }
+
+    #[test]
+    fn timestamps_are_formatted_in_utc() {
+        use std::time::Duration;
+
+        assert_eq!(format_utc_timestamp(UNIX_EPOCH), "1970-01-01 00:00 UTC");
+        // 2024-02-29 13:05:59 UTC
+        assert_eq!(format_utc_timestamp(UNIX_EPOCH + Duration::from_secs(1_709_211_959)), "2024-02-29 13:05 UTC");
+    }
+
+    #[test]
+    fn joined_paths_stay_within_root() {
+        let root = Path::new("/scans");
+        assert_eq!(join_within(root, Path::new("a/b/c.pdf")), Some(PathBuf::from("/scans/a/b/c.pdf")));
+        assert_eq!(join_within(root, Path::new("./a/../b/./c.pdf")), Some(PathBuf::from("/scans/b/c.pdf")));
+        assert_eq!(join_within(root, Path::new("a/../../c.pdf")), None);
+        assert_eq!(join_within(root, Path::new("../scans/c.pdf")), None);
+        assert_eq!(join_within(root, Path::new("/etc/c.pdf")), None);
+    }
+}